@@ -1,4 +1,5 @@
 use super::AnimationConfig;
+use super::AnimationRepeat;
 use crate::border_manager::Border;
 use crate::colors::ColorImpl;
 use crate::core::animation::AnimationEasing;
@@ -15,11 +16,25 @@ pub struct AnimationEngine {
     pub kind: AnimationKind,
     pub duration: f32,
     pub easing: AnimationEasing,
+    pub delay: f32,
+    /// How many times the animation plays, or `None` to loop forever.
+    pub repeat: Option<u32>,
+    /// Multiplier for the rotation speed of spiral and direction sweep animations.
+    pub speed: f32,
+    pub min_opacity: f32,
+    pub max_opacity: f32,
 }
 
 impl AnimationEngine {
     const MINIMUM_PROGRESS: f32 = 0.0;
     const MAXIMUM_PROGRESS: f32 = 1.0;
+    const DEFAULT_PULSE_MIN_OPACITY: f32 = 0.3;
+    const DEFAULT_PULSE_MAX_OPACITY: f32 = 1.0;
+
+    /// Returns `true` once the animation's delay has passed since the window's focus state changed.
+    pub fn is_ready(&self, border: &Border) -> bool {
+        border.animation_manager.progress.state_elapsed >= self.delay
+    }
 
     /// Plays the animation, updating the border state based on elapsed time.
     pub fn play(&self, border: &mut Border, elapsed_time: &StdDuration) {
@@ -33,18 +48,38 @@ impl AnimationEngine {
                 self.animate_spiral(border, elapsed_time, reverse);
             }
             AnimationKind::Fade => self.animate_fade(border, elapsed_time),
+            AnimationKind::HueRotate => self.animate_hue_rotate(border, elapsed_time),
+            AnimationKind::Pulse => self.animate_pulse(border, elapsed_time),
+            AnimationKind::DirectionSweep => self.animate_direction_sweep(border, elapsed_time),
         }
     }
 
     /// Animates a spiral effect on the border.
     fn animate_spiral(&self, border: &mut Border, elapsed_time: &StdDuration, reverse: bool) {
         let direction: f32 = if reverse { -1.0 } else { 1.0 };
-        let delta_x = elapsed_time.as_millis_f32() / self.duration * direction;
+        // Scale by the elapsed time so the rotation speed doesn't depend on the frame rate
+        let delta_x = elapsed_time.as_millis_f32() / self.duration * self.speed * direction;
         border.animation_manager.progress.spiral += delta_x;
 
         if !(Self::MINIMUM_PROGRESS..=Self::MAXIMUM_PROGRESS)
             .contains(&border.animation_manager.progress.spiral)
         {
+            border.animation_manager.progress.spiral_cycles += 1;
+
+            // A full turn ends where it started, so stopping here leaves the border unrotated
+            if self
+                .repeat
+                .is_some_and(|repeat| border.animation_manager.progress.spiral_cycles >= repeat)
+            {
+                border.animation_manager.progress.spiral = Self::MINIMUM_PROGRESS;
+                border.animation_manager.progress.angle = 0.0;
+                border.animation_manager.flags.spiral_finished = true;
+
+                border.active_color.set_transform(&Matrix3x2::identity());
+                border.inactive_color.set_transform(&Matrix3x2::identity());
+                return;
+            }
+
             border.animation_manager.progress.spiral =
                 border.animation_manager.progress.spiral.rem_euclid(1.0);
         }
@@ -81,46 +116,92 @@ impl AnimationEngine {
         border.inactive_color.set_transform(&transform);
     }
 
-    fn animate_fade(&self, border: &mut Border, elapsed_time: &StdDuration) {
-        // If both are 0, that means the window has been opened for the first time or has been
-        // unminimized. If that is the case, only one of the colors should be visible while fading.
-        if border.active_color.get_opacity() == Some(0.0)
-            && border.inactive_color.get_opacity() == Some(0.0)
-        {
-            // Set progress.fade here so we start from 0 opacity for the visible color
-            border.animation_manager.progress.fade = if border.is_window_active {
-                Self::MINIMUM_PROGRESS
-            } else {
-                Self::MAXIMUM_PROGRESS
-            };
-            border.animation_manager.flags.fade_to_visible = true;
+    /// Animates a continuous hue rotation of the current color, completing a full turn every
+    /// `duration` milliseconds.
+    fn animate_hue_rotate(&self, border: &mut Border, elapsed_time: &StdDuration) {
+        let delta_x = elapsed_time.as_millis_f32() / self.duration;
+        border.animation_manager.progress.hue =
+            (border.animation_manager.progress.hue + delta_x).rem_euclid(1.0);
+
+        let easing_fn = match self.easing.to_fn() {
+            Ok(func) => func,
+            Err(err) => {
+                error!("could not transform easing to function: {err}");
+                return;
+            }
+        };
+
+        let y_coord = match (easing_fn)(border.animation_manager.progress.hue) {
+            Ok(val) => val,
+            Err(err) => {
+                error!("could not create bezier easing function: {err}");
+                return;
+            }
+        };
+
+        let d2d_context = match border.render_resources.d2d_context() {
+            Ok(d2d_context) => d2d_context.clone(),
+            Err(err) => {
+                error!("could not get d2d context for hue rotation: {err}");
+                return;
+            }
+        };
+
+        let window_rect = border.window_rect.into();
+        let color = match border.is_window_active {
+            true => &mut border.active_color,
+            false => &mut border.inactive_color,
+        };
+
+        if let Err(err) = color.set_hue_rotation(360.0 * y_coord, &d2d_context, &window_rect) {
+            error!("could not rotate border color hue: {err}");
         }
+    }
 
-        let direction = if border.is_window_active { 1.0 } else { -1.0 };
+    /// Animates a sweep of the gradient direction, completing a full turn every `duration`
+    /// milliseconds. Unlike the spiral, which rotates the whole brush, the gradient stays aligned
+    /// to the window, so the stops keep their positions relative to its edges.
+    fn animate_direction_sweep(&self, border: &mut Border, elapsed_time: &StdDuration) {
+        let delta_x = elapsed_time.as_millis_f32() / self.duration * self.speed;
+        border.animation_manager.progress.sweep =
+            (border.animation_manager.progress.sweep + delta_x).rem_euclid(1.0);
 
-        let delta_x = elapsed_time.as_millis_f32() / self.duration * direction;
-        border.animation_manager.progress.fade += delta_x;
+        let easing_fn = match self.easing.to_fn() {
+            Ok(func) => func,
+            Err(err) => {
+                error!("could not transform easing to function: {err}");
+                return;
+            }
+        };
 
-        if !(Self::MINIMUM_PROGRESS..=Self::MAXIMUM_PROGRESS)
-            .contains(&border.animation_manager.progress.fade)
-        {
-            let final_opacity = border
-                .animation_manager
-                .progress
-                .fade
-                .clamp(Self::MINIMUM_PROGRESS, Self::MAXIMUM_PROGRESS);
-
-            border.active_color.set_opacity(final_opacity);
-            border
-                .inactive_color
-                .set_opacity(Self::MAXIMUM_PROGRESS - final_opacity);
-
-            border.animation_manager.progress.fade = final_opacity;
-            border.animation_manager.flags.fade_to_visible = false;
-            border.animation_manager.flags.should_fade = false;
+        let y_coord = match (easing_fn)(border.animation_manager.progress.sweep) {
+            Ok(val) => val,
+            Err(err) => {
+                error!("could not create bezier easing function: {err}");
+                return;
+            }
+        };
+
+        let color = match border.is_window_active {
+            true => &mut border.active_color,
+            false => &mut border.inactive_color,
+        };
+        color.turn_direction(360.0 * y_coord);
+    }
+
+    /// Animates a breathing effect on the active color, going from the max opacity down to the
+    /// min opacity and back every `duration` milliseconds for as long as the window is active.
+    fn animate_pulse(&self, border: &mut Border, elapsed_time: &StdDuration) {
+        if !border.is_window_active {
+            border.animation_manager.progress.pulse = Self::MINIMUM_PROGRESS;
+            border.animation_manager.progress.pulse_opacity = None;
             return;
         }
 
+        let delta_x = elapsed_time.as_millis_f32() / self.duration;
+        border.animation_manager.progress.pulse =
+            (border.animation_manager.progress.pulse + delta_x).rem_euclid(1.0);
+
         let easing_fn = match self.easing.to_fn() {
             Ok(func) => func,
             Err(err) => {
@@ -129,28 +210,103 @@ impl AnimationEngine {
             }
         };
 
-        let y_coord = match (easing_fn)(border.animation_manager.progress.fade) {
+        // Fold the progress into a triangle wave so the easing is applied on the way down and
+        // mirrored on the way back up
+        let wave = 1.0 - (2.0 * border.animation_manager.progress.pulse - 1.0).abs();
+        let y_coord = match (easing_fn)(wave) {
             Ok(val) => val,
             Err(err) => {
                 error!("could not create bezier easing function: {err}");
-                border.animation_manager.flags.should_fade = false;
                 return;
             }
         };
 
-        let (new_active_opacity, new_inactive_opacity) =
-            if border.animation_manager.flags.fade_to_visible {
-                if border.is_window_active {
-                    (y_coord, Self::MINIMUM_PROGRESS)
-                } else {
-                    (Self::MINIMUM_PROGRESS, Self::MAXIMUM_PROGRESS - y_coord)
-                }
+        border.animation_manager.progress.pulse_opacity =
+            Some(self.max_opacity - (self.max_opacity - self.min_opacity) * y_coord);
+    }
+
+    fn animate_fade(&self, border: &mut Border, elapsed_time: &StdDuration) {
+        // If both are 0, that means the window has been opened for the first time or has been
+        // unminimized. If that is the case, only one of the colors should be visible while fading.
+        // This is only checked once per fade, since some easings keep both opacities at 0 during
+        // the first frames, which would otherwise restart the fade every frame.
+        if !border.animation_manager.flags.fade_to_visible
+            && border.active_color.get_opacity() == Some(0.0)
+            && border.inactive_color.get_opacity() == Some(0.0)
+        {
+            // Set progress.fade here so we start from 0 opacity for the visible color
+            border.animation_manager.progress.fade = if border.is_window_active {
+                Self::MINIMUM_PROGRESS
             } else {
-                (y_coord, Self::MAXIMUM_PROGRESS - y_coord)
+                Self::MAXIMUM_PROGRESS
+            };
+            border.animation_manager.flags.fade_to_visible = true;
+        }
+
+        let direction = if border.is_window_active { 1.0 } else { -1.0 };
+
+        let delta_x = elapsed_time.as_millis_f32() / self.duration * direction;
+        let progress = (border.animation_manager.progress.fade + delta_x)
+            .clamp(Self::MINIMUM_PROGRESS, Self::MAXIMUM_PROGRESS);
+        border.animation_manager.progress.fade = progress;
+
+        // The fade is finished once the progress reaches the end it's moving towards
+        let finished = match border.is_window_active {
+            true => progress >= Self::MAXIMUM_PROGRESS,
+            false => progress <= Self::MINIMUM_PROGRESS,
+        };
+
+        // Easings map the end points onto themselves, so the last frame can skip the easing
+        let y_coord = if finished {
+            progress
+        } else {
+            let easing_fn = match self.easing.to_fn() {
+                Ok(func) => func,
+                Err(err) => {
+                    error!("could not transform easing to function: {err}");
+                    return;
+                }
             };
 
+            match (easing_fn)(progress) {
+                Ok(val) => val,
+                Err(err) => {
+                    error!("could not create bezier easing function: {err}");
+                    border.animation_manager.flags.should_fade = false;
+                    return;
+                }
+            }
+        };
+
+        let (new_active_opacity, new_inactive_opacity) = Self::fade_opacities(
+            y_coord,
+            border.animation_manager.flags.fade_to_visible,
+            border.is_window_active,
+        );
+
         border.active_color.set_opacity(new_active_opacity);
         border.inactive_color.set_opacity(new_inactive_opacity);
+
+        if finished {
+            border.animation_manager.flags.fade_to_visible = false;
+            border.animation_manager.flags.should_fade = false;
+        }
+    }
+
+    /// Returns the active and inactive opacities for the given eased fade progress.
+    ///
+    /// Normally the two colors cross-fade. When fading to visible, the color for the window's
+    /// current state fades in on its own while the other one stays hidden, so the border never
+    /// shows the wrong color while it appears. Easings that overshoot are clamped to valid
+    /// opacities.
+    fn fade_opacities(y_coord: f32, fade_to_visible: bool, is_window_active: bool) -> (f32, f32) {
+        let y_coord = y_coord.clamp(Self::MINIMUM_PROGRESS, Self::MAXIMUM_PROGRESS);
+
+        match (fade_to_visible, is_window_active) {
+            (true, true) => (y_coord, Self::MINIMUM_PROGRESS),
+            (true, false) => (Self::MINIMUM_PROGRESS, Self::MAXIMUM_PROGRESS - y_coord),
+            (false, _) => (y_coord, Self::MAXIMUM_PROGRESS - y_coord),
+        }
     }
 }
 
@@ -164,19 +320,128 @@ impl TryFrom<AnimationConfig> for AnimationEngine {
         let default_duration = match kind {
             AnimationKind::Spiral | AnimationKind::ReverseSpiral => 1800.0,
             AnimationKind::Fade => 200.0,
+            AnimationKind::HueRotate => 5000.0,
+            AnimationKind::Pulse => 2000.0,
+            AnimationKind::DirectionSweep => 4000.0,
         };
 
-        // Parse easing, using a default value if not provided or invalid.
-        let easing = AnimationEasing::from_str(value.easing.clone().unwrap_or_default().as_str())
-            .unwrap_or_default();
+        // Parse easing, using a default value if not provided.
+        let easing = match value.easing {
+            Some(ref easing) => AnimationEasing::from_str(easing.as_str())
+                .map_err(|err| anyhow!("invalid easing for {} animation: {err}", value.kind))?,
+            None => AnimationEasing::default(),
+        };
 
-        let duration = value.duration.as_duration_f32().unwrap_or(default_duration);
+        let duration = match value.duration.as_duration_f32() {
+            Some(duration) if !duration.is_finite() || duration <= 0.0 => {
+                warn!(
+                    "animation duration must be positive (got {duration}ms); using {default_duration}ms"
+                );
+                default_duration
+            }
+            Some(duration) => duration,
+            None => default_duration,
+        };
+
+        let delay = match value.delay.as_duration_f32() {
+            Some(delay) if !delay.is_finite() || delay < 0.0 => {
+                warn!("animation delay can't be negative (got {delay}ms); using 0ms");
+                0.0
+            }
+            Some(delay) => delay,
+            None => 0.0,
+        };
+
+        let repeat = match value.repeat {
+            Some(AnimationRepeat::Count(0)) => {
+                warn!("animation repeat count must be at least 1; using 1");
+                Some(1)
+            }
+            Some(AnimationRepeat::Count(count)) => Some(count),
+            Some(AnimationRepeat::Infinite(_)) | None => None,
+        };
+
+        let speed = match value.speed {
+            Some(speed) if !speed.is_finite() || speed <= 0.0 => {
+                warn!("animation speed must be positive (got {speed}); using 1");
+                1.0
+            }
+            Some(speed) => speed,
+            None => 1.0,
+        };
+
+        let mut min_opacity = value
+            .min
+            .unwrap_or(Self::DEFAULT_PULSE_MIN_OPACITY)
+            .clamp(0.0, 1.0);
+        let mut max_opacity = value
+            .max
+            .unwrap_or(Self::DEFAULT_PULSE_MAX_OPACITY)
+            .clamp(0.0, 1.0);
+        if min_opacity > max_opacity {
+            warn!(
+                "animation min opacity ({min_opacity}) is above max opacity ({max_opacity}); swapping them"
+            );
+            std::mem::swap(&mut min_opacity, &mut max_opacity);
+        }
 
         // Return the constructed Animation struct.
         Ok(AnimationEngine {
             kind,
             duration,
             easing,
+            delay,
+            repeat,
+            speed,
+            min_opacity,
+            max_opacity,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fade_to_visible_only_shows_active_color_when_active() {
+        // Active windows fade in as progress goes from 0 to 1
+        assert_eq!(AnimationEngine::fade_opacities(0.0, true, true), (0.0, 0.0));
+        assert_eq!(AnimationEngine::fade_opacities(0.5, true, true), (0.5, 0.0));
+        assert_eq!(AnimationEngine::fade_opacities(1.0, true, true), (1.0, 0.0));
+    }
+
+    #[test]
+    fn fade_to_visible_only_shows_inactive_color_when_inactive() {
+        // Inactive windows fade in as progress goes from 1 to 0
+        assert_eq!(
+            AnimationEngine::fade_opacities(1.0, true, false),
+            (0.0, 0.0)
+        );
+        assert_eq!(
+            AnimationEngine::fade_opacities(0.5, true, false),
+            (0.0, 0.5)
+        );
+        assert_eq!(
+            AnimationEngine::fade_opacities(0.0, true, false),
+            (0.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn fade_cross_fades_between_colors() {
+        assert_eq!(
+            AnimationEngine::fade_opacities(0.25, false, true),
+            (0.25, 0.75)
+        );
+    }
+
+    #[test]
+    fn fade_clamps_overshooting_easings() {
+        assert_eq!(AnimationEngine::fade_opacities(1.2, true, true), (1.0, 0.0));
+        assert_eq!(
+            AnimationEngine::fade_opacities(-0.2, false, false),
+            (0.0, 1.0)
+        );
+    }
+}
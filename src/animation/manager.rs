@@ -2,6 +2,7 @@ use std::time::Instant;
 
 use super::AnimationsConfig;
 use super::wrapper::AnimationEngineVec;
+use crate::app_manager::AppManager;
 use crate::core::timer::CustomTimer;
 use crate::error::LogIfErr;
 use serde::Deserialize;
@@ -11,28 +12,96 @@ pub struct AnimationProgress {
     pub fade: f32,
     pub spiral: f32,
     pub angle: f32,
+    /// Number of full turns the spiral animation has completed since the focus state changed.
+    pub spiral_cycles: u32,
+    pub hue: f32,
+    pub pulse: f32,
+    pub sweep: f32,
+    /// The opacity the active color is scaled by while pulsing, or `None` if it isn't pulsing.
+    pub pulse_opacity: Option<f32>,
+    /// Time (in milliseconds) since the window last became active or inactive, used for delays.
+    pub state_elapsed: f32,
 }
 
 #[derive(Debug, Deserialize, Clone, Default, PartialEq)]
 pub struct AnimationFlags {
     pub fade_to_visible: bool,
     pub should_fade: bool,
+    pub spiral_finished: bool,
 }
 
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct AnimationManager {
     active: AnimationEngineVec,
     inactive: AnimationEngineVec,
-    fps: i32,
+    /// Frame rate from the config, or `None` to use the global or default frame rate.
+    fps: Option<i32>,
+    /// Refresh rate of the border's monitor, used if the config doesn't set a frame rate.
+    monitor_fps: Option<i32>,
     timer: Option<CustomTimer>,
     last_animation_time: Option<Instant>,
+    keep_timer_alive: bool,
+    /// Whether the border's window is active, see `set_window_active`.
+    window_active: bool,
+    /// Whether animations run while the border's window is inactive.
+    animate_inactive: bool,
     pub progress: AnimationProgress,
     pub flags: AnimationFlags,
 }
 
 impl AnimationManager {
+    /// Lowest frame rate allowed for animations.
+    pub const MIN_FPS: i32 = 1;
+    /// Highest frame rate allowed for animations.
+    pub const MAX_FPS: i32 = 240;
+    /// Frame rate used if neither the config sets one nor the monitor's refresh rate is known.
+    pub const DEFAULT_FPS: i32 = 60;
+
     pub const fn fps(&self) -> f32 {
-        self.fps as f32
+        let fps = match (self.fps, self.monitor_fps) {
+            (Some(fps), _) | (None, Some(fps)) => fps,
+            (None, None) => Self::DEFAULT_FPS,
+        };
+
+        // Guard against a zero fps so intervals never divide by zero
+        let fps = if fps < Self::MIN_FPS {
+            Self::MIN_FPS
+        } else if fps > Self::MAX_FPS {
+            Self::MAX_FPS
+        } else {
+            fps
+        };
+
+        fps as f32
+    }
+
+    /// Interval (in milliseconds) between the frames of the animation timer.
+    pub fn timer_interval(&self) -> u64 {
+        (1000.0 / self.fps()) as u64
+    }
+
+    /// Uses the frame rate of `fallback` if this manager's config didn't set one.
+    ///
+    /// Window rules call this with the global manager, so the fps precedence is: the window rule's
+    /// `animations.fps`, then the global `animations.fps`, then the monitor's refresh rate (see
+    /// `set_monitor_fps`), then `DEFAULT_FPS`.
+    pub fn inherit_fps(&mut self, fallback: &AnimationManager) {
+        if self.fps.is_none() {
+            self.fps = fallback.fps;
+        }
+    }
+
+    /// Sets the refresh rate of the border's monitor, used if the config doesn't set a frame rate.
+    ///
+    /// Returns whether the effective frame rate changed.
+    pub fn set_monitor_fps(&mut self, refresh_rate: Option<u32>) -> bool {
+        let old_fps = self.fps();
+        self.monitor_fps = refresh_rate.map(|refresh_rate| refresh_rate as i32);
+        self.fps() != old_fps
+    }
+
+    pub fn is_timer_running(&self) -> bool {
+        self.timer.is_some()
     }
 
     pub fn get_active_animation(&self) -> &AnimationEngineVec {
@@ -47,10 +116,40 @@ impl AnimationManager {
         !self.active.is_empty() || !self.inactive.is_empty()
     }
 
+    /// Keeps the timer running even if there are no animations (e.g. for hover tracking).
+    pub fn set_keep_timer_alive(&mut self, keep_alive: bool) {
+        self.keep_timer_alive = keep_alive;
+    }
+
+    fn needs_timer(&self) -> bool {
+        self.keep_timer_alive || self.has_active_or_inactive_animations()
+    }
+
+    /// Sets whether animations run while the border's window is inactive (`animate_inactive`).
+    pub fn set_animate_inactive(&mut self, animate_inactive: bool) {
+        self.animate_inactive = animate_inactive;
+    }
+
+    /// Tracks whether the border's window is active, which decides if `set_timer` starts a timer
+    /// when `animate_inactive` is disabled.
+    pub fn set_window_active(&mut self, active: bool) {
+        self.window_active = active;
+    }
+
+    /// Returns whether the timer isn't needed because the window is inactive and inactive borders
+    /// aren't animated. Hover tracking still needs the timer, so it keeps it alive regardless.
+    pub fn is_idle(&self) -> bool {
+        !self.window_active && !self.animate_inactive && !self.keep_timer_alive
+    }
+
     pub fn set_timer(&mut self, hwnd: isize) -> anyhow::Result<()> {
-        if self.timer.is_none() && self.has_active_or_inactive_animations() {
-            let timer_duration = (1000.0 / self.fps()) as u64;
-            let timer = CustomTimer::start(hwnd, timer_duration)?;
+        // The timer is started again when animations are resumed
+        if AppManager::get().animations_paused() {
+            return Ok(());
+        }
+
+        if self.timer.is_none() && self.needs_timer() && !self.is_idle() {
+            let timer = CustomTimer::start(hwnd, self.timer_interval())?;
             self.timer = Some(timer);
             self.last_animation_time = Some(Instant::now());
         }
@@ -59,7 +158,7 @@ impl AnimationManager {
     }
 
     pub fn kill_timer(&mut self, hwnd: isize) -> anyhow::Result<()> {
-        if self.timer.is_some() && self.has_active_or_inactive_animations() {
+        if self.timer.is_some() && self.needs_timer() {
             CustomTimer::stop(hwnd).log_if_err();
             self.timer = None;
         }
@@ -80,6 +179,18 @@ impl AnimationManager {
 impl TryFrom<AnimationsConfig> for AnimationManager {
     type Error = anyhow::Error;
     fn try_from(value: AnimationsConfig) -> Result<AnimationManager, Self::Error> {
+        let fps = value.fps.map(|value| {
+            let fps = value.clamp(Self::MIN_FPS, Self::MAX_FPS);
+            if fps != value {
+                warn!(
+                    "animation fps must be between {} and {} (got {value}); using {fps}",
+                    Self::MIN_FPS,
+                    Self::MAX_FPS,
+                );
+            }
+            fps
+        });
+
         if value.enabled {
             let active = AnimationEngineVec::try_from(value.active.clone().unwrap_or_default())?;
             let inactive =
@@ -87,11 +198,95 @@ impl TryFrom<AnimationsConfig> for AnimationManager {
             return Ok(AnimationManager {
                 active,
                 inactive,
-                fps: value.fps,
+                fps,
                 ..Default::default()
             });
         }
 
-        Ok(AnimationManager::default())
+        // Keep the fps around so the timer can still be used without animations.
+        Ok(AnimationManager {
+            fps,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::AnimationConfig;
+    use crate::core::animation::AnimationKind;
+    use crate::core::value::Value;
+
+    fn manager_with_fps(fps: i32) -> AnimationManager {
+        AnimationManager::try_from(AnimationsConfig {
+            fps: Some(fps),
+            enabled: true,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn zero_fps_is_clamped_to_min_fps() {
+        let manager = manager_with_fps(0);
+        assert_eq!(manager.fps(), AnimationManager::MIN_FPS as f32);
+        assert!((1000.0 / manager.fps()).is_finite());
+    }
+
+    #[test]
+    fn large_fps_is_clamped_to_max_fps() {
+        let manager = manager_with_fps(100_000);
+        assert_eq!(manager.fps(), AnimationManager::MAX_FPS as f32);
+    }
+
+    #[test]
+    fn window_rule_fps_overrides_global_fps() {
+        let global = manager_with_fps(60);
+        let mut rule = manager_with_fps(30);
+        rule.inherit_fps(&global);
+        rule.set_monitor_fps(Some(144));
+
+        assert_eq!(rule.fps(), 30.0);
+        assert_eq!(rule.timer_interval(), 1000 / 30);
+    }
+
+    #[test]
+    fn window_rule_without_fps_inherits_global_fps() {
+        let global = manager_with_fps(60);
+        let mut rule = AnimationManager::try_from(AnimationsConfig {
+            enabled: true,
+            ..Default::default()
+        })
+        .unwrap();
+        rule.inherit_fps(&global);
+        rule.set_monitor_fps(Some(144));
+
+        assert_eq!(rule.timer_interval(), 1000 / 60);
+    }
+
+    #[test]
+    fn negative_duration_uses_default_duration() {
+        let manager = AnimationManager::try_from(AnimationsConfig {
+            active: Some(vec![AnimationConfig {
+                kind: "fade".to_string(),
+                duration: Some(Value::Number(-100.0)),
+                easing: None,
+                delay: None,
+                repeat: None,
+                speed: None,
+                min: None,
+                max: None,
+            }]),
+            enabled: true,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let fade = manager
+            .get_active_animation()
+            .get(&AnimationKind::Fade)
+            .unwrap();
+        assert_eq!(fade.duration, 200.0);
     }
 }
@@ -1,5 +1,4 @@
 use crate::core::helpers::serde_default_bool;
-use crate::core::helpers::serde_default_i32;
 use crate::core::value::Value;
 use schema_jsonrs::JsonSchema;
 use serde::Deserialize;
@@ -30,8 +29,8 @@ pub struct AnimationsConfig {
 
     /// The frame rate for the animations, specified in frames per second (FPS).
     /// This controls how smoothly the animations are rendered during transitions of custom window borders.
-    #[serde(default = "serde_default_i32::<60>")]
-    pub fps: i32,
+    /// A window rule without an fps uses the global fps, which defaults to the monitor's refresh rate.
+    pub fps: Option<i32>,
 
     /// Indicates whether animations are enabled or not.
     /// This boolean flag determines whether any animations in `active` or `inactive` should be applied.
@@ -46,6 +45,11 @@ pub struct AnimationsConfig {
 /// - `kind`: The type of animation (e.g., "fade", "slide", "zoom") applied to the custom border.
 /// - `duration`: The duration of the animation, either as a string (e.g., "100ms") or a number (e.g., 100).
 /// - `easing`: The easing function for the animation (e.g., "ease-in", "linear") to control the timing of the transition.
+/// - `delay`: How long to wait after the window's focus state changes before starting the animation.
+/// - `repeat`: How many times a spiral animation plays, either a count or `"infinite"`.
+/// - `speed`: A multiplier for how fast a spiral animation rotates.
+/// - `min`: The lowest opacity reached by a pulse animation.
+/// - `max`: The highest opacity reached by a pulse animation.
 #[derive(Clone, PartialEq, Debug, Deserialize, JsonSchema)]
 pub struct AnimationConfig {
     /// The type or kind of animation (e.g., "fade", "spiral", "reverse-spiral") to apply to the custom border.
@@ -59,4 +63,39 @@ pub struct AnimationConfig {
     /// The easing function for the animation, specified as a string (e.g., "ease-in", "linear", "cubic-bezier(0.42, 0.0, 0.58, 1.0)").
     /// This defines the pacing of the animation's progress over time.
     pub easing: Option<String>,
+
+    /// How long to wait after the window becomes active or inactive before starting the animation,
+    /// specified either as a string (e.g., "200ms") or a number (e.g., 200). Defaults to no delay.
+    pub delay: Option<Value>,
+
+    /// How many times a spiral animation plays before stopping, either as a count (e.g., 2) or
+    /// `"infinite"`. Defaults to `"infinite"`.
+    pub repeat: Option<AnimationRepeat>,
+
+    /// A multiplier for how fast a spiral animation rotates, e.g. 2.0 for twice as fast
+    /// (default: 1.0). This scales the rotation without changing the duration or fps.
+    pub speed: Option<f32>,
+
+    /// The lowest opacity reached by a pulse animation, ranging from 0.0 to 1.0 (default: 0.3).
+    pub min: Option<f32>,
+
+    /// The highest opacity reached by a pulse animation, ranging from 0.0 to 1.0 (default: 1.0).
+    pub max: Option<f32>,
+}
+
+/// The number of times an animation repeats.
+#[derive(Clone, Copy, PartialEq, Debug, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum AnimationRepeat {
+    /// The animation plays this many times, then stops.
+    Count(u32),
+    /// The animation loops forever.
+    Infinite(InfiniteRepeat),
+}
+
+/// The `"infinite"` keyword for `AnimationRepeat`.
+#[derive(Clone, Copy, PartialEq, Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum InfiniteRepeat {
+    Infinite,
 }
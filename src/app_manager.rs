@@ -1,6 +1,9 @@
 use crate::border_manager::Border;
 use crate::config_watcher::ConfigWatcher;
 use crate::error::LogIfErr;
+use crate::parsed_config::WindowRule;
+use crate::set_log_level;
+use crate::theme_manager::ThemeManager;
 use crate::user_config::UserConfig;
 use crate::windows_api::WindowsApi;
 use anyhow::Context;
@@ -8,6 +11,7 @@ use anyhow::Context;
 use fx_hash::{FxHashMap as HashMap, FxHashMapExt};
 #[cfg(not(feature = "fast-hash"))]
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Mutex;
 use std::sync::MutexGuard;
 use std::sync::OnceLock;
@@ -21,7 +25,9 @@ use windows::Win32::Graphics::Direct2D::D2D1_FACTORY_TYPE_MULTI_THREADED;
 use windows::Win32::Graphics::Direct2D::D2D1CreateFactory;
 use windows::Win32::Graphics::Direct2D::ID2D1Device7;
 use windows::Win32::Graphics::Direct2D::ID2D1Factory8;
+use windows::Win32::Graphics::Direct3D::D3D_DRIVER_TYPE;
 use windows::Win32::Graphics::Direct3D::D3D_DRIVER_TYPE_HARDWARE;
+use windows::Win32::Graphics::Direct3D::D3D_DRIVER_TYPE_WARP;
 use windows::Win32::Graphics::Direct3D::D3D_FEATURE_LEVEL;
 use windows::Win32::Graphics::Direct3D::D3D_FEATURE_LEVEL_9_1;
 use windows::Win32::Graphics::Direct3D::D3D_FEATURE_LEVEL_9_2;
@@ -38,6 +44,9 @@ use windows::Win32::Graphics::Direct3D11::D3D11_SDK_VERSION;
 use windows::Win32::Graphics::Direct3D11::D3D11CreateDevice;
 use windows::Win32::Graphics::Direct3D11::ID3D11Device;
 use windows::Win32::Graphics::Dxgi::IDXGIDevice;
+use windows::Win32::Graphics::Gdi::HMONITOR;
+use windows::Win32::UI::WindowsAndMessaging::WINDOW_EX_STYLE;
+use windows::Win32::UI::WindowsAndMessaging::WINDOW_STYLE;
 use windows::core::Interface;
 
 /// A global instance of the AppManager initialized lazily.
@@ -50,12 +59,41 @@ pub struct AppManager {
     borders: Mutex<HashMap<isize, Border>>,
     /// Holds the handle of the currently active window
     active_window: Mutex<isize>,
+    /// Tracking windows of the borders, ordered from least to most recently focused
+    focus_order: Mutex<Vec<isize>>,
     /// User configuration stored in a read-write lock
     config: RwLock<UserConfig>,
     /// Watches configuration file for changes
     config_watcher: RwLock<ConfigWatcher>,
+    /// Config file picked from the tray menu, which is read instead of the detected one
+    active_config_path: RwLock<Option<PathBuf>>,
     /// Flag to indicate whether active window polling is enabled
     is_polling_active_window: AtomicBool,
+    /// Flag to indicate whether borders are enabled, toggled with the `toggle` keybinding
+    borders_enabled: AtomicBool,
+    /// Flag to indicate whether animations are paused, toggled with the `pause_animations`
+    /// keybinding
+    animations_paused: AtomicBool,
+    /// Per-window border overrides keyed by window handle, toggled with the `toggle_window`
+    /// keybinding. They take precedence over the window rules for the rest of the session.
+    window_overrides: Mutex<HashMap<isize, bool>>,
+    /// Window rules resolved by `WindowsApi::get_window_rule`, keyed by window handle and stored
+    /// with the window title and styles they were resolved for
+    rule_cache: Mutex<HashMap<isize, (RuleCacheKey, WindowRule)>>,
+    /// Refresh rates of the monitors keyed by monitor handle, or `None` if it couldn't be queried
+    refresh_rates: Mutex<HashMap<isize, Option<u32>>>,
+    /// Theme picked with the `next_theme` and `prev_theme` keybindings, which takes precedence
+    /// over the configured theme for the rest of the session
+    active_theme: RwLock<Option<ThemeManager>>,
+    /// Direct2D factory the devices were created from
+    d2d_factory: ID2D1Factory8,
+    /// DirectX devices shared by all borders, recreated if the GPU device is removed
+    devices: RwLock<DirectXDevices>,
+}
+
+/// The DirectX devices every border creates its swap chain and device context from.
+#[derive(Debug, Clone)]
+struct DirectXDevices {
     /// Direct3D 11 device used for rendering
     device: ID3D11Device,
     /// DirectX Graphics Infrastructure device
@@ -64,6 +102,16 @@ pub struct AppManager {
     d2d_device: ID2D1Device7,
 }
 
+/// The window properties a cached window rule was resolved for. They are checked on every
+/// lookup since rules may match them and they change over the window's lifetime, e.g. the title
+/// when switching browser tabs or the styles when toggling fullscreen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleCacheKey {
+    pub title: String,
+    pub style: WINDOW_STYLE,
+    pub ex_style: WINDOW_EX_STYLE,
+}
+
 unsafe impl Send for AppManager {}
 unsafe impl Sync for AppManager {}
 
@@ -90,6 +138,41 @@ impl AppManager {
         }
     }
 
+    /// Moves the given window to the end of the focus order, making it the most recently focused.
+    pub fn mark_focused(&self, hwnd: isize) {
+        let mut focus_order = self.focus_order.lock().unwrap_or_else(|e| e.into_inner());
+        focus_order.retain(|&other| other != hwnd);
+        focus_order.push(hwnd);
+    }
+
+    /// Removes the given window from the focus order, e.g. once its border is destroyed.
+    pub fn forget_focus(&self, hwnd: isize) {
+        self.focus_order
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|&other| other != hwnd);
+    }
+
+    /// Returns the tracking windows of the least recently focused borders that exceed `limit`.
+    /// Borders whose window never had focus count as less recently focused than all others.
+    pub fn borders_over_limit(&self, borders: &HashMap<isize, Border>, limit: usize) -> Vec<isize> {
+        let focus_order = self.focus_order.lock().unwrap_or_else(|e| e.into_inner());
+
+        let never_focused = borders
+            .keys()
+            .copied()
+            .filter(|hwnd| !focus_order.contains(hwnd));
+        let focused = focus_order
+            .iter()
+            .copied()
+            .filter(|hwnd| borders.contains_key(hwnd));
+
+        never_focused
+            .chain(focused)
+            .take(borders.len().saturating_sub(limit))
+            .collect()
+    }
+
     /// Stops the configuration file watcher.
     pub fn stop_config_watcher(&self) {
         if let Ok(mut config_watcher) = self.config_watcher.write() {
@@ -104,6 +187,32 @@ impl AppManager {
         }
     }
 
+    /// Returns the config file picked from the tray menu, if any.
+    pub fn active_config_path(&self) -> Option<PathBuf> {
+        self.active_config_path
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Makes `path` the config file that is read from now on, and points the config watcher at it.
+    pub fn set_active_config_path(&self, path: PathBuf) {
+        if let Ok(mut config_watcher) = self.config_watcher.write() {
+            let was_running = config_watcher.is_running();
+            config_watcher.stop().log_if_err();
+
+            *config_watcher = ConfigWatcher::new(path.clone(), Duration::from_millis(200));
+            if was_running {
+                config_watcher.start().log_if_err();
+            }
+        }
+
+        *self
+            .active_config_path
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = Some(path);
+    }
+
     /// Returns whether the configuration file watcher is currently running.
     pub fn config_watcher_is_running(&self) -> bool {
         self.config_watcher.read().is_ok_and(|w| w.is_running())
@@ -121,19 +230,55 @@ impl AppManager {
         }
     }
 
-    /// Returns a reference to the Direct3D device.
-    pub fn device(&self) -> &ID3D11Device {
-        &self.device
+    /// Returns a reference to the Direct2D factory.
+    pub fn d2d_factory(&self) -> &ID2D1Factory8 {
+        &self.d2d_factory
     }
 
-    /// Returns a reference to the Direct2D device.
-    pub fn d2d_device(&self) -> &ID2D1Device7 {
-        &self.d2d_device
+    /// Returns a read-only lock for the shared DirectX devices.
+    fn devices(&self) -> RwLockReadGuard<'_, DirectXDevices> {
+        self.devices.read().unwrap_or_else(|e| e.into_inner())
     }
 
-    /// Returns a reference to the DXGI device.
-    pub fn dxgi_device(&self) -> &IDXGIDevice {
-        &self.dxgi_device
+    /// Returns the shared Direct3D device.
+    pub fn device(&self) -> ID3D11Device {
+        self.devices().device.clone()
+    }
+
+    /// Returns the shared Direct2D device.
+    pub fn d2d_device(&self) -> ID2D1Device7 {
+        self.devices().d2d_device.clone()
+    }
+
+    /// Returns the shared DXGI device.
+    pub fn dxgi_device(&self) -> IDXGIDevice {
+        self.devices().dxgi_device.clone()
+    }
+
+    /// Recreates the shared DirectX devices if the GPU device was removed (e.g. by a driver update
+    /// or a GPU reset). Borders call this before recreating their own render resources.
+    ///
+    /// Returns whether the devices were recreated, in which case every resource created from the
+    /// old devices (brushes, bitmaps, ...) must be recreated as well.
+    pub fn recreate_devices_if_removed(&self) -> anyhow::Result<bool> {
+        let mut devices = self.devices.write().unwrap_or_else(|e| e.into_inner());
+
+        // Another border may have already recreated the devices while we waited for the lock
+        if unsafe { devices.device.GetDeviceRemovedReason() }.is_ok() {
+            return Ok(false);
+        }
+
+        warn!("directx device has been removed; recreating shared devices");
+
+        let (device, dxgi_device, d2d_device) = create_directx_devices(&self.d2d_factory)
+            .context("could not recreate directx devices")?;
+        *devices = DirectXDevices {
+            device,
+            dxgi_device,
+            d2d_device,
+        };
+
+        Ok(true)
     }
 
     /// Returns whether the polling of the active window is enabled.
@@ -146,6 +291,124 @@ impl AppManager {
         self.is_polling_active_window.store(val, Ordering::SeqCst);
     }
 
+    /// Returns whether borders are enabled.
+    pub fn borders_enabled(&self) -> bool {
+        self.borders_enabled.load(Ordering::SeqCst)
+    }
+
+    /// Sets whether borders are enabled.
+    pub fn set_borders_enabled(&self, val: bool) {
+        self.borders_enabled.store(val, Ordering::SeqCst);
+    }
+
+    /// Returns whether animations are paused.
+    pub fn animations_paused(&self) -> bool {
+        self.animations_paused.load(Ordering::SeqCst)
+    }
+
+    /// Sets whether animations are paused.
+    pub fn set_animations_paused(&self, val: bool) {
+        self.animations_paused.store(val, Ordering::SeqCst);
+    }
+
+    /// Returns whether borders are forced on or off for the given window, if overridden.
+    pub fn window_override(&self, hwnd: isize) -> Option<bool> {
+        self.window_overrides
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&hwnd)
+            .copied()
+    }
+
+    /// Forces borders on or off for the given window, or removes the override if `None`.
+    pub fn set_window_override(&self, hwnd: isize, enabled: Option<bool>) {
+        let mut window_overrides = self
+            .window_overrides
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        match enabled {
+            Some(enabled) => window_overrides.insert(hwnd, enabled),
+            None => window_overrides.remove(&hwnd),
+        };
+    }
+
+    /// Returns the cached window rule for the given window, unless its title or styles have
+    /// changed since.
+    pub fn cached_window_rule(&self, hwnd: isize, key: &RuleCacheKey) -> Option<WindowRule> {
+        match self
+            .rule_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&hwnd)
+        {
+            Some((cached_key, rule)) if cached_key == key => Some(rule.clone()),
+            _ => None,
+        }
+    }
+
+    /// Caches the window rule resolved for the given window, title and styles.
+    pub fn cache_window_rule(&self, hwnd: isize, key: RuleCacheKey, rule: WindowRule) {
+        self.rule_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(hwnd, (key, rule));
+    }
+
+    /// Forgets the cached window rule of the given window.
+    pub fn remove_cached_window_rule(&self, hwnd: isize) {
+        self.rule_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&hwnd);
+    }
+
+    /// Forgets all cached window rules, e.g. after the config changed.
+    pub fn clear_rule_cache(&self) {
+        self.rule_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    /// Returns the refresh rate of the given monitor, querying it only the first time.
+    pub fn monitor_refresh_rate(&self, hmonitor: HMONITOR) -> Option<u32> {
+        *self
+            .refresh_rates
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(hmonitor.0 as isize)
+            .or_insert_with(|| {
+                WindowsApi::get_monitor_refresh_rate(hmonitor)
+                    .inspect_err(|err| warn!("{err:#}"))
+                    .ok()
+            })
+    }
+
+    /// Forgets all cached refresh rates, e.g. after the display settings may have changed.
+    pub fn clear_refresh_rates(&self) {
+        self.refresh_rates
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    /// Returns the theme colors are resolved with: the one picked with `next_theme` or
+    /// `prev_theme`, as long as the config still lists it, or else the configured theme.
+    pub fn active_theme(&self) -> ThemeManager {
+        let config = self.config();
+        let active_theme = self.active_theme.read().unwrap_or_else(|e| e.into_inner());
+
+        match active_theme.as_ref() {
+            Some(theme) if config.theme_cycle().contains(theme) => theme.clone(),
+            _ => config.theme.clone(),
+        }
+    }
+
+    /// Sets the theme that takes precedence over the configured theme, or removes it if `None`.
+    pub fn set_active_theme(&self, theme: Option<ThemeManager>) {
+        *self.active_theme.write().unwrap_or_else(|e| e.into_inner()) = theme;
+    }
+
     /// Initializes a new AppManager instance, setting up configuration and DirectX devices.
     fn new() -> Self {
         let active_window = WindowsApi::get_foreground_window();
@@ -162,42 +425,76 @@ impl AppManager {
             UserConfig::default()
         });
 
+        set_log_level(config.log_level.into());
+
         if config.monitor_config_changes {
             config_watcher.start().log_if_err();
         }
 
         let factory = unsafe {
             D2D1CreateFactory::<ID2D1Factory8>(D2D1_FACTORY_TYPE_MULTI_THREADED, None)
-                .unwrap_or_else(|err| {
-                    error!("could not create ID2D1Factory: {err}");
-                    panic!()
-                })
+                .unwrap_or_else(|err| exit_with_error("could not create ID2D1Factory", &err))
         };
 
-        let (device, dxgi_device, d2d_device) =
-            create_directx_devices(&factory).unwrap_or_else(|err| {
-                error!("could not create directx devices: {err}");
-                println!("could not create directx devices: {err}");
-                panic!("could not create directx devices: {err}");
-            });
+        let (device, dxgi_device, d2d_device) = create_directx_devices(&factory)
+            .unwrap_or_else(|err| exit_with_error("could not create directx devices", &err));
 
         Self {
             borders: Mutex::new(HashMap::new()),
             active_window: Mutex::new(active_window),
+            focus_order: Mutex::new(Vec::new()),
             is_polling_active_window: AtomicBool::new(false),
+            borders_enabled: AtomicBool::new(true),
+            animations_paused: AtomicBool::new(false),
+            window_overrides: Mutex::new(HashMap::new()),
+            rule_cache: Mutex::new(HashMap::new()),
+            refresh_rates: Mutex::new(HashMap::new()),
+            active_theme: RwLock::new(None),
             config: RwLock::new(config),
             config_watcher: RwLock::new(config_watcher),
-            device,
-            dxgi_device,
-            d2d_device,
+            active_config_path: RwLock::new(None),
+            d2d_factory: factory,
+            devices: RwLock::new(DirectXDevices {
+                device,
+                dxgi_device,
+                d2d_device,
+            }),
         }
     }
 }
 
+/// Logs the error and shows it in a dialog, then exits, since nothing can be drawn without the
+/// Direct2D factory and devices.
+fn exit_with_error(context: &str, err: &dyn std::fmt::Display) -> ! {
+    error!("{context}: {err}");
+    WindowsApi::show_error_dialog("Fatal error", &format!("{context}: {err}"));
+    std::process::exit(1);
+}
+
 /// Helper function to create Direct3D and Direct2D devices.
+///
+/// Falls back to the WARP software rasterizer if no hardware device can be created, e.g. in VMs or
+/// remote desktop sessions without hardware acceleration.
 fn create_directx_devices(
     factory: &ID2D1Factory8,
 ) -> anyhow::Result<(ID3D11Device, IDXGIDevice, ID2D1Device7)> {
+    let device = match create_d3d11_device(D3D_DRIVER_TYPE_HARDWARE) {
+        Ok(device) => device,
+        Err(err) => {
+            warn!("could not create hardware d3d11 device, falling back to WARP: {err:#}");
+            create_d3d11_device(D3D_DRIVER_TYPE_WARP)?
+        }
+    };
+
+    let dxgi_device: IDXGIDevice = device.cast().context("ID3D11Device cast")?;
+    let d2d_device =
+        unsafe { factory.CreateDevice(&dxgi_device) }.context("Failed to create D2D device")?;
+
+    Ok((device, dxgi_device, d2d_device))
+}
+
+/// Creates a Direct3D 11 device with the given driver type.
+fn create_d3d11_device(driver_type: D3D_DRIVER_TYPE) -> anyhow::Result<ID3D11Device> {
     let creation_flags = D3D11_CREATE_DEVICE_BGRA_SUPPORT;
 
     let feature_levels = [
@@ -219,7 +516,7 @@ fn create_directx_devices(
     unsafe {
         D3D11CreateDevice(
             None,
-            D3D_DRIVER_TYPE_HARDWARE,
+            driver_type,
             HMODULE::default(),
             creation_flags,
             Some(&feature_levels),
@@ -230,12 +527,9 @@ fn create_directx_devices(
         )
     }?;
 
-    debug!("App Manager: DirectX Device Created (Feature Level: {feature_level:X?})");
+    debug!(
+        "App Manager: DirectX Device Created (Driver Type: {driver_type:?}, Feature Level: {feature_level:X?})"
+    );
 
-    let device = device_opt.context("Could not get D3D11 device")?;
-    let dxgi_device: IDXGIDevice = device.cast().context("ID3D11Device cast")?;
-    let d2d_device =
-        unsafe { factory.CreateDevice(&dxgi_device) }.context("Failed to create D2D device")?;
-
-    Ok((device, dxgi_device, d2d_device))
+    device_opt.context("Could not get D3D11 device")
 }
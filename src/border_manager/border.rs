@@ -3,28 +3,50 @@ use crate::animation::wrapper::AnimationEngineVec;
 use crate::app_manager::AppManager;
 use crate::colors::Color;
 use crate::colors::ColorImpl;
+use crate::colors::functions::interpolate_d2d1_colors;
 use crate::core::animation::AnimationKind;
 use crate::core::rect::Rect;
 use crate::effect::manager::EffectManager;
 use crate::effect::wrapper::EffectEngineVec;
 use crate::error::LogIfErr;
+use crate::game_mode;
+use crate::parsed_config::BorderLayer;
+use crate::parsed_config::BorderOffset;
+use crate::parsed_config::BorderWidth;
+use crate::parsed_config::MonitorOverride;
 use crate::parsed_config::ParsedConfig;
+use crate::parsed_config::StrokeStyle;
 use crate::parsed_config::WindowRule;
+use crate::parsed_config::monitor_key_matches;
 use crate::render_resources::RenderResources;
+use crate::user_config::BorderPlacement;
+use crate::user_config::CornerRadii;
+use crate::user_config::ForcedCorners;
+use crate::user_config::StrokeKind;
+use crate::user_config::UnminimizeDetection;
 use crate::windows_api::HWNDConversion;
 use crate::windows_api::PointerConversion;
 use crate::windows_api::ToWideString;
+use crate::windows_api::WM_APP_ANIMATIONS;
+use crate::windows_api::WM_APP_FLASH;
+use crate::windows_api::WM_APP_FORCE_STATE;
 use crate::windows_api::WM_APP_FOREGROUND;
 use crate::windows_api::WM_APP_HIDECLOAKED;
 use crate::windows_api::WM_APP_LOCATIONCHANGE;
 use crate::windows_api::WM_APP_MINIMIZEEND;
 use crate::windows_api::WM_APP_MINIMIZESTART;
+use crate::windows_api::WM_APP_RECREATE;
 use crate::windows_api::WM_APP_REORDER;
+use crate::windows_api::WM_APP_RESTYLE;
 use crate::windows_api::WM_APP_SHOWUNCLOAKED;
+use crate::windows_api::WM_APP_STATUS;
 use crate::windows_api::WM_APP_TIMER;
 use crate::windows_api::WindowsApi;
 use anyhow::Context;
 use anyhow::anyhow;
+use std::cell::RefCell;
+use std::num::NonZeroUsize;
+use std::sync::mpsc::Sender;
 use std::thread;
 use std::time;
 use std::time::Instant;
@@ -40,25 +62,44 @@ use windows::Win32::Foundation::RECT;
 use windows::Win32::Foundation::S_OK;
 use windows::Win32::Foundation::TRUE;
 use windows::Win32::Foundation::WPARAM;
+use windows::Win32::Graphics::Direct2D::Common::D2D_POINT_2F;
 use windows::Win32::Graphics::Direct2D::Common::D2D_RECT_F;
+use windows::Win32::Graphics::Direct2D::Common::D2D_SIZE_F;
 use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
 use windows::Win32::Graphics::Direct2D::Common::D2D1_COMPOSITE_MODE_SOURCE_OVER;
+use windows::Win32::Graphics::Direct2D::Common::D2D1_FIGURE_BEGIN_FILLED;
+use windows::Win32::Graphics::Direct2D::Common::D2D1_FIGURE_END_CLOSED;
+use windows::Win32::Graphics::Direct2D::D2D1_ANTIALIAS_MODE_ALIASED;
+use windows::Win32::Graphics::Direct2D::D2D1_ARC_SEGMENT;
+use windows::Win32::Graphics::Direct2D::D2D1_ARC_SIZE_SMALL;
 use windows::Win32::Graphics::Direct2D::D2D1_BRUSH_PROPERTIES;
+use windows::Win32::Graphics::Direct2D::D2D1_CAP_STYLE_FLAT;
+use windows::Win32::Graphics::Direct2D::D2D1_CAP_STYLE_ROUND;
+use windows::Win32::Graphics::Direct2D::D2D1_DASH_STYLE_CUSTOM;
+use windows::Win32::Graphics::Direct2D::D2D1_DASH_STYLE_DASH;
+use windows::Win32::Graphics::Direct2D::D2D1_DASH_STYLE_DOT;
 use windows::Win32::Graphics::Direct2D::D2D1_INTERPOLATION_MODE_LINEAR;
+use windows::Win32::Graphics::Direct2D::D2D1_LINE_JOIN_MITER;
 use windows::Win32::Graphics::Direct2D::D2D1_ROUNDED_RECT;
+use windows::Win32::Graphics::Direct2D::D2D1_STROKE_STYLE_PROPERTIES1;
+use windows::Win32::Graphics::Direct2D::D2D1_STROKE_TRANSFORM_TYPE_NORMAL;
+use windows::Win32::Graphics::Direct2D::D2D1_SWEEP_DIRECTION_CLOCKWISE;
 use windows::Win32::Graphics::Direct2D::ID2D1Brush;
 use windows::Win32::Graphics::Direct2D::ID2D1CommandList;
 use windows::Win32::Graphics::Direct2D::ID2D1DeviceContext7;
+use windows::Win32::Graphics::Direct2D::ID2D1GeometrySink;
+use windows::Win32::Graphics::Direct2D::ID2D1PathGeometry1;
+use windows::Win32::Graphics::Direct2D::ID2D1StrokeStyle1;
 use windows::Win32::Graphics::Dwm::DWM_BB_BLURREGION;
 use windows::Win32::Graphics::Dwm::DWM_BB_ENABLE;
 use windows::Win32::Graphics::Dwm::DWM_BLURBEHIND;
 use windows::Win32::Graphics::Dwm::DwmEnableBlurBehindWindow;
-use windows::Win32::Graphics::Dxgi::DXGI_PRESENT;
 use windows::Win32::Graphics::Gdi::CreateRectRgn;
 use windows::Win32::Graphics::Gdi::HMONITOR;
 use windows::Win32::UI::WindowsAndMessaging::CREATESTRUCTW;
 use windows::Win32::UI::WindowsAndMessaging::GWLP_USERDATA;
 use windows::Win32::UI::WindowsAndMessaging::GetSystemMetrics;
+use windows::Win32::UI::WindowsAndMessaging::HTTRANSPARENT;
 use windows::Win32::UI::WindowsAndMessaging::LWA_ALPHA;
 use windows::Win32::UI::WindowsAndMessaging::LWA_COLORKEY;
 use windows::Win32::UI::WindowsAndMessaging::MSG;
@@ -67,18 +108,42 @@ use windows::Win32::UI::WindowsAndMessaging::SM_CXVIRTUALSCREEN;
 use windows::Win32::UI::WindowsAndMessaging::SWP_HIDEWINDOW;
 use windows::Win32::UI::WindowsAndMessaging::SWP_SHOWWINDOW;
 use windows::Win32::UI::WindowsAndMessaging::WM_CREATE;
+use windows::Win32::UI::WindowsAndMessaging::WM_DISPLAYCHANGE;
+use windows::Win32::UI::WindowsAndMessaging::WM_DPICHANGED;
+use windows::Win32::UI::WindowsAndMessaging::WM_DWMCOLORIZATIONCOLORCHANGED;
+use windows::Win32::UI::WindowsAndMessaging::WM_LBUTTONDOWN;
 use windows::Win32::UI::WindowsAndMessaging::WM_NCDESTROY;
+use windows::Win32::UI::WindowsAndMessaging::WM_NCHITTEST;
 use windows::Win32::UI::WindowsAndMessaging::WM_PAINT;
 use windows::Win32::UI::WindowsAndMessaging::WM_QUIT;
+use windows::Win32::UI::WindowsAndMessaging::WM_TIMER;
 use windows::Win32::UI::WindowsAndMessaging::WM_WINDOWPOSCHANGED;
 use windows::Win32::UI::WindowsAndMessaging::WM_WINDOWPOSCHANGING;
 use windows::core::CloneType;
 use windows::core::TypeKind;
 
+use super::BorderStatus;
+use super::ForcedState;
 use super::get_active_window;
+use super::spawn_border_thread;
 use super::window_border;
 use super::window_borders;
 
+/// Some windows don't show their border after the first pass, so by default we render twice.
+const DEFAULT_INITIAL_RENDER_PASSES: u32 = 2;
+const DEFAULT_INITIAL_RENDER_INTERVAL: u32 = 5;
+
+/// Timer id and delay of the one-shot timer that coalesces renders requested by location changes.
+const LOCATION_RENDER_TIMER_ID: usize = 1;
+const LOCATION_RENDER_DELAY: u32 = 8;
+
+/// How often the window rect is polled while waiting for a restored window to settle.
+const UNMINIMIZE_POLL_INTERVAL: time::Duration = time::Duration::from_millis(10);
+
+/// Number of per-corner outlines kept by `corner_geometry`. A frame draws only a few distinct
+/// rects (border, layers, effects), so this is enough to reuse all of them.
+const MAX_CACHED_CORNER_GEOMETRIES: usize = 8;
+
 impl TypeKind for Border {
     type TypeKind = CloneType;
 }
@@ -100,9 +165,27 @@ pub struct Border {
     pub window_padding: i32,
     pub render_rect: D2D1_ROUNDED_RECT,
     pub width: i32,
-    pub offset: i32,
+    /// Width of each edge when they differ; `width` then holds the widest edge.
+    pub edge_widths: Option<BorderWidth>,
+    pub stroke_style: StrokeStyle,
+    pub placement: BorderPlacement,
+    /// Corner shape used by the "auto" border style instead of the window's corner preference.
+    pub force_corners: Option<ForcedCorners>,
+    /// Extra strokes drawn behind the border, from back to front.
+    pub layers: Vec<BorderLayer>,
+    /// Cached stroke style for `stroke_style`; `None` for solid borders.
+    pub d2d_stroke_style: Option<ID2D1StrokeStyle1>,
+    /// Cached stroke style of each layer, in the same order as `layers`.
+    pub layer_stroke_styles: Vec<Option<ID2D1StrokeStyle1>>,
+    pub offset: BorderOffset,
     pub radius: f32,
+    /// Radius of each corner when they differ; `radius` then holds the largest one.
+    pub corner_radii: Option<CornerRadii>,
+    /// Outlines built by `corner_geometry`, reused until their rect or radii change.
+    pub corner_geometries: RefCell<Vec<(D2D_RECT_F, CornerRadii, ID2D1PathGeometry1)>>,
     pub current_monitor: HMONITOR,
+    /// Override applied for `current_monitor`, if the config has one.
+    pub monitor_override: Option<MonitorOverride>,
     pub current_dpi: f32,
     pub render_resources: RenderResources,
     pub active_color: Color,
@@ -112,7 +195,44 @@ pub struct Border {
     pub last_render_time: Option<Instant>,
     pub initialize_delay: u32,
     pub unminimize_delay: u32,
+    pub unminimize_detection: UnminimizeDetection,
+    pub creation_grace_period: u32,
+    pub hover_only: bool,
+    pub hover_progress: f32,
+    pub always_active: bool,
+    pub forced_state: Option<ForcedState>,
+    pub trail_color: Option<Color>,
+    pub trail_duration: u32,
+    pub trail_progress: f32,
+    pub flash_color: Option<Color>,
+    pub flash_duration: u32,
+    pub flash_progress: f32,
+    /// The solid active color before a restyle changed it, see `apply_color_transition`.
+    pub transition_from_active: Option<D2D1_COLOR_F>,
+    /// The solid inactive color before a restyle changed it, see `apply_color_transition`.
+    pub transition_from_inactive: Option<D2D1_COLOR_F>,
+    pub transition_duration: u32,
+    pub transition_progress: f32,
+    pub clip_to_monitor: bool,
+    pub hide_on_fullscreen: bool,
+    pub border_maximized: bool,
+    pub always_on_top: bool,
+    /// Whether the border is hidden because its window is fullscreen, see `update_hidden`
+    pub is_fullscreen: bool,
+    /// Monitors that never get borders, see `disabled_monitors` in the config.
+    pub disabled_monitors: Vec<String>,
+    /// Whether the border is hidden because its window is on a disabled monitor, see
+    /// `update_hidden`
+    pub on_disabled_monitor: bool,
+    /// Whether the border is hidden because game mode is active, see `update_hidden`
+    pub suppressed_by_game_mode: bool,
+    pub active_opacity: f32,
+    pub inactive_opacity: f32,
+    pub initial_render_passes: u32,
+    pub initial_render_interval: u32,
     pub pause: bool,
+    /// Whether a location change is waiting for the next render, see `schedule_location_render`
+    pub location_render_pending: bool,
     pub process_name: String,
 }
 
@@ -221,7 +341,20 @@ impl Border {
     }
 
     pub fn create(tracking_window: isize, window_rule: WindowRule) {
-        std::thread::spawn(move || {
+        // Don't create borders while they are toggled off
+        if !AppManager::get().borders_enabled() {
+            return;
+        }
+
+        if is_on_disabled_monitor(
+            tracking_window,
+            &AppManager::get().config().disabled_monitors,
+        ) {
+            debug!("window is on a disabled monitor: {tracking_window:?}");
+            return;
+        }
+
+        spawn_border_thread(move || {
             let mut borders_hashmap = window_borders();
 
             // Check to see if there is already a border for the given tracking window
@@ -229,6 +362,19 @@ impl Border {
                 return;
             }
 
+            // Past the limit, only the focused window gets a border; the others get theirs once
+            // they gain focus
+            let app_manager = AppManager::get();
+            let max_borders = app_manager.config().max_borders.map(NonZeroUsize::get);
+            let is_active = *app_manager.active_window() == tracking_window;
+            match max_borders {
+                Some(max_borders) if !is_active && borders_hashmap.len() >= max_borders => {
+                    debug!("max_borders reached, deferring border for {tracking_window:?}");
+                    return;
+                }
+                _ => {}
+            }
+
             let mut border = Self {
                 tracking_window,
                 window_rect: Rect(RECT::default()),
@@ -242,6 +388,19 @@ impl Border {
 
             borders_hashmap.insert(tracking_window, border.clone());
 
+            if is_active {
+                app_manager.mark_focused(tracking_window);
+            }
+
+            if let Some(max_borders) = max_borders {
+                for hwnd in app_manager.borders_over_limit(&borders_hashmap, max_borders) {
+                    if let Some(evicted) = borders_hashmap.remove(&hwnd) {
+                        debug!("max_borders reached, destroying border for {hwnd:?}");
+                        evicted.destroy();
+                    }
+                }
+            }
+
             drop(borders_hashmap);
             let _ = window_rule;
             let _ = tracking_window;
@@ -260,7 +419,8 @@ impl Border {
         )
         .as_raw_pcwstr();
 
-        self.border_window = WindowsApi::create_border_window(title, self)?;
+        let clickable = AppManager::get().config().debug_clickable;
+        self.border_window = WindowsApi::create_border_window(title, self, clickable)?;
         self.load_from_config(window_rule)?;
         self.process_name = WindowsApi::get_process_name(self.tracking_window)
             .unwrap_or_else(|_| "unknown".to_string());
@@ -277,6 +437,18 @@ impl Border {
     pub fn init(&mut self) -> anyhow::Result<()> {
         thread::sleep(time::Duration::from_millis(self.initialize_delay as u64));
 
+        if !self.wait_for_creation_grace_period() {
+            debug!(
+                "Border: Window disappeared during grace period (Process: {}, Tracking Window ID: {:?})",
+                self.process_name,
+                self.tracking_window()
+            );
+            // The message loop hasn't started yet, so just clean up and let the thread end. The
+            // border window is destroyed along with the thread that owns it.
+            self.exit_border_thread();
+            return Ok(());
+        }
+
         unsafe {
             // Make the window border transparent
             let pos: i32 = -GetSystemMetrics(SM_CXVIRTUALSCREEN) - 8;
@@ -313,7 +485,7 @@ impl Border {
             self.render_resources
                 .create(
                     self.current_monitor,
-                    self.width,
+                    self.placement_margin(),
                     self.window_padding,
                     self.border_window,
                 )
@@ -345,20 +517,38 @@ impl Border {
             self.inactive_color
                 .to_d2d1_brush(d2d_context, &self.window_rect.into(), &brush_properties)
                 .log_if_err();
+            let overlay_colors = self
+                .trail_color
+                .iter_mut()
+                .chain(self.flash_color.iter_mut())
+                .chain(
+                    self.layers
+                        .iter_mut()
+                        .flat_map(|layer| [&mut layer.active_color, &mut layer.inactive_color]),
+                );
+            for overlay_color in overlay_colors {
+                overlay_color
+                    .to_d2d1_brush(d2d_context, &self.window_rect.into(), &brush_properties)
+                    .log_if_err();
+            }
 
             self.update_color(Some(self.initialize_delay)).log_if_err();
             self.update_window_rect().log_if_err();
 
-            if WindowsApi::has_native_border(self.tracking_window) {
-                self.update_position(Some(SWP_SHOWWINDOW)).log_if_err();
-                self.render().log_if_err();
+            if WindowsApi::has_native_border(self.tracking_window) && !self.update_hidden() {
+                // Sometimes, it doesn't show the window at first, so we wait a few ms and update
+                // it again. This is very hacky and needs to be looked into. It may be related to
+                // the issue detailed in the wnd_proc. TODO
+                for pass in 0..self.initial_render_passes {
+                    if pass > 0 {
+                        thread::sleep(time::Duration::from_millis(
+                            self.initial_render_interval as u64,
+                        ));
+                    }
 
-                // Sometimes, it doesn't show the window at first, so we wait 5ms and update it.
-                // This is very hacky and needs to be looked into. It may be related to the issue
-                // detailed in the wnd_proc. TODO
-                thread::sleep(time::Duration::from_millis(5));
-                self.update_position(Some(SWP_SHOWWINDOW)).log_if_err();
-                self.render().log_if_err();
+                    self.update_position(Some(SWP_SHOWWINDOW)).log_if_err();
+                    self.render().log_if_err();
+                }
             }
 
             self.animation_manager
@@ -377,7 +567,11 @@ impl Border {
             }
 
             self.render_resources
-                .update(self.current_monitor, self.width, self.window_padding)
+                .update(
+                    self.current_monitor,
+                    self.placement_margin(),
+                    self.window_padding,
+                )
                 .log_if_err();
             let d2d_context = self.render_resources.d2d_context()?;
             let border_bitmap = self.render_resources.border_bitmap()?;
@@ -428,6 +622,30 @@ impl Border {
         Ok(())
     }
 
+    /// Waits until the tracking window has stayed visible for `creation_grace_period`.
+    ///
+    /// Returns `false` if the window disappeared before the grace period elapsed, in which
+    /// case the border should never be shown (e.g. short-lived splash screens).
+    fn wait_for_creation_grace_period(&self) -> bool {
+        if self.creation_grace_period == 0 {
+            return true;
+        }
+
+        let grace_period = time::Duration::from_millis(self.creation_grace_period as u64);
+        let poll_interval = time::Duration::from_millis(10);
+        let start = Instant::now();
+
+        while start.elapsed() < grace_period {
+            if !WindowsApi::is_window_visible_on_screen(self.tracking_window) {
+                return false;
+            }
+
+            thread::sleep(poll_interval.min(grace_period.saturating_sub(start.elapsed())));
+        }
+
+        WindowsApi::is_window_visible_on_screen(self.tracking_window)
+    }
+
     fn load_from_config(&mut self, window_rule: &WindowRule) -> anyhow::Result<()> {
         let current_dpi = match WindowsApi::get_dpi_for_window(self.tracking_window) {
             Ok(dpi) => dpi as f32,
@@ -441,93 +659,116 @@ impl Border {
         let config = ParsedConfig::try_from(user_config)?;
         let global = &config.global_rule;
 
-        let config_width = window_rule
-            .border_width
+        // Monitor overrides take precedence over both the window rule and the global rule
+        let current_monitor = WindowsApi::monitor_from_window(self.tracking_window);
+        let monitor_override = WindowsApi::get_monitor_device_name(current_monitor)
+            .ok()
+            .and_then(|device_name| config.monitor_override(&device_name))
+            .cloned();
+        let monitor = monitor_override.as_ref();
+
+        let config_width = monitor
+            .and_then(|monitor| monitor.border_width)
+            .or(window_rule.border_width)
             .unwrap_or(config.global_rule.border_width);
-        let config_offset = window_rule
-            .border_offset
+        let config_offset = monitor
+            .and_then(|monitor| monitor.border_offset)
+            .or(window_rule.border_offset)
             .unwrap_or(config.global_rule.border_offset);
 
-        let active_color = window_rule
-            .active_color
-            .as_ref()
+        let active_color = monitor
+            .and_then(|monitor| monitor.active_color.as_ref())
+            .or(window_rule.active_color.as_ref())
             .unwrap_or(&global.active_color);
 
-        let inactive_color = window_rule
-            .inactive_color
-            .as_ref()
+        let inactive_color = monitor
+            .and_then(|monitor| monitor.inactive_color.as_ref())
+            .or(window_rule.inactive_color.as_ref())
             .unwrap_or(&global.inactive_color);
 
-        let animation_manager = window_rule
+        let mut animation_manager = window_rule
             .animation_manager
             .as_ref()
-            .unwrap_or(&global.animation_manager);
-
-        let effect_manager = window_rule
-            .effect_manager
-            .as_ref()
-            .unwrap_or(&global.effect_manager);
+            .unwrap_or(&global.animation_manager)
+            .clone();
+        animation_manager.inherit_fps(&global.animation_manager);
+        animation_manager.set_animate_inactive(config.animate_inactive);
+        animation_manager.set_window_active(self.is_window_active);
+        animation_manager.set_monitor_fps(AppManager::get().monitor_refresh_rate(current_monitor));
+
+        let effect_manager = EffectManager::for_window_rule(
+            window_rule.effect_manager.as_ref(),
+            &global.effect_manager,
+        );
 
-        let config_style = window_rule
-            .border_style
-            .as_ref()
+        let config_style = monitor
+            .and_then(|monitor| monitor.border_style.as_ref())
+            .or(window_rule.border_style.as_ref())
             .unwrap_or(&global.border_style);
 
         self.active_color = active_color.clone();
-        self.inactive_color = inactive_color.clone();
+        self.inactive_color = inactive_color.resolve(active_color)?;
 
-        self.current_monitor = WindowsApi::monitor_from_window(self.tracking_window);
+        self.current_monitor = current_monitor;
         self.current_dpi = current_dpi;
 
-        self.width = (config_width as f32 * current_dpi / 96.0).round() as i32;
-        self.radius = config_style.to_radius(self.width, current_dpi, self.tracking_window);
+        self.force_corners = window_rule.force_corners.or(global.force_corners);
+        self.set_width(config_width, current_dpi);
+        self.radius = config_style.to_radius(
+            self.width,
+            current_dpi,
+            self.tracking_window,
+            self.force_corners,
+        );
+        self.corner_radii = config_style.to_corner_radii(current_dpi);
+        self.stroke_style = window_rule.stroke_style.unwrap_or(global.stroke_style);
+        self.placement = window_rule.placement.unwrap_or(global.placement);
+        self.layers = window_rule
+            .layers
+            .clone()
+            .unwrap_or_else(|| global.layers.clone());
+        self.update_stroke_style()?;
         self.offset = config_offset;
+        self.monitor_override = monitor_override;
 
-        self.animation_manager = animation_manager.clone();
+        self.animation_manager = animation_manager;
         self.effect_manager = effect_manager.clone();
 
-        let max_active_padding = self
-            .effect_manager
-            .active()
-            .iter()
-            .max_by_key(|params| {
-                // Try to find the effect params with the largest required padding
-                let max_std_dev = params.standard_deviation;
-                let max_translation = (params.translation.x).max(params.translation.y);
-
-                ((max_std_dev * 3.0).ceil() + max_translation.ceil()) as i32
-            })
-            .map(|params| {
-                // Now that we found it, go ahead and calculate it as an f32
-                let max_std_dev = params.standard_deviation;
-                let max_translation = (params.translation.x).max(params.translation.y);
-
-                (max_std_dev * 3.0).ceil() + max_translation.ceil()
-            })
-            .unwrap_or(0.0);
-        let max_inactive_padding = self
-            .effect_manager
-            .inactive()
-            .iter()
-            .max_by_key(|params| {
-                // Try to find the effect params with the largest required padding
-                let max_std_dev = params.standard_deviation;
-                let max_translation = (params.translation.x).max(params.translation.y);
-
-                // 3 standard deviations gets us 99.7% coverage, which should be good enough
-                ((max_std_dev * 3.0).ceil() + max_translation.ceil()) as i32
-            })
-            .map(|params| {
-                // Now that we found it, go ahead and calculate it as an f32
-                let max_std_dev = params.standard_deviation;
-                let max_translation = (params.translation.x).max(params.translation.y);
-
-                // 3 standard deviations gets us 99.7% coverage, which should be good enough
-                (max_std_dev * 3.0).ceil() + max_translation.ceil()
-            })
-            .unwrap_or(0.0);
+        self.always_active = window_rule.always_active.unwrap_or(false);
+        self.initial_render_passes = window_rule
+            .initial_render_passes
+            .unwrap_or(DEFAULT_INITIAL_RENDER_PASSES)
+            .max(1);
+        self.initial_render_interval = window_rule
+            .initial_render_interval
+            .unwrap_or(DEFAULT_INITIAL_RENDER_INTERVAL);
+        self.hover_only = window_rule.hover_only.unwrap_or(global.hover_only);
+        self.clip_to_monitor = window_rule
+            .clip_to_monitor
+            .unwrap_or(global.clip_to_monitor);
+        self.hide_on_fullscreen = config.hide_on_fullscreen;
+        self.disabled_monitors = config.disabled_monitors.clone();
+        self.border_maximized = config.border_maximized;
+        self.always_on_top = config.always_on_top;
+        self.active_opacity = window_rule
+            .active_opacity
+            .unwrap_or(global.active_opacity)
+            .clamp(0.0, 1.0);
+        self.inactive_opacity = window_rule
+            .inactive_opacity
+            .unwrap_or(global.inactive_opacity)
+            .clamp(0.0, 1.0);
+        self.trail_color = global.focus_trail.as_ref().map(|trail| trail.color.clone());
+        self.trail_duration = global
+            .focus_trail
+            .as_ref()
+            .map_or(0, |trail| trail.duration);
+        self.flash_color = global.flash_color.clone();
+        self.flash_duration = global.flash_duration;
+        self.transition_duration = global.color_transition_duration;
+        self.update_keep_timer_alive();
 
-        self.window_padding = max_active_padding.max(max_inactive_padding).ceil() as i32;
+        self.window_padding = self.effect_manager.window_padding();
 
         let available_windows = WindowsApi::collect_window_handles().unwrap_or_default();
 
@@ -541,12 +782,29 @@ impl Border {
         self.unminimize_delay = window_rule
             .unminimize_delay
             .unwrap_or(global.unminimize_delay);
+        self.unminimize_detection = global.unminimize_detection;
+
+        // Like initialize_delay, the grace period only applies to windows that have just been
+        // created, not to those that already existed when the borders were (re)loaded.
+        self.creation_grace_period = match available_windows.contains(&self.tracking_window) {
+            true => 0,
+            false => window_rule
+                .creation_grace_period
+                .unwrap_or(global.creation_grace_period),
+        };
 
         Ok(())
     }
 
-    fn update_window_rect(&mut self) -> anyhow::Result<()> {
-        self.window_rect = WindowsApi::window_rect(self.tracking_window).map_err(|e| {
+    /// Updates `window_rect` from the tracking window's rect.
+    ///
+    /// Minimized windows are moved to `Rect::MINIMIZED_POSITION` and collapsed, so their rect is
+    /// ignored and the last known one is kept. Otherwise a restore could briefly render the
+    /// border at the collapsed size.
+    ///
+    /// Returns whether `window_rect` was updated.
+    fn update_window_rect(&mut self) -> anyhow::Result<bool> {
+        let window_rect = WindowsApi::window_rect(self.tracking_window).map_err(|e| {
             self.exit_border_thread(); // Exit the thread on error
             e.context(format!(
                 "could not get window rect for: {:?}",
@@ -554,10 +812,85 @@ impl Border {
             )) // Add context
         })?;
 
+        if window_rect.is_minimized() || WindowsApi::is_window_minimized(self.tracking_window) {
+            return Ok(false);
+        }
+
+        self.window_rect = window_rect;
+
+        // Maximized windows fill the work area, so their border would be drawn off-screen or
+        // under the taskbar. Pull it in until its outer edge lines up with the work area instead.
+        if self.border_maximized && WindowsApi::is_window_maximized(self.tracking_window) {
+            let offset = self
+                .offset
+                .to_pixels(self.window_rect.width(), self.window_rect.height());
+            self.window_rect
+                .add_margin(-(self.placement_margin() + offset).max(0));
+        }
+
         self.window_rect
-            .add_margin(self.width + self.window_padding);
+            .add_margin(self.placement_margin() + self.window_padding);
 
-        Ok(())
+        Ok(true)
+    }
+
+    /// Blocks until the tracking window finished restoring from minimized, see
+    /// `UnminimizeDetection`.
+    fn wait_for_unminimize(&self) {
+        let timeout = time::Duration::from_millis(self.unminimize_delay as u64);
+
+        match self.unminimize_detection {
+            UnminimizeDetection::Delay => thread::sleep(timeout),
+            UnminimizeDetection::Settle => {
+                let started = Instant::now();
+                let mut last_rect = None;
+
+                // The window has settled once two consecutive rects are equal
+                while started.elapsed() < timeout {
+                    let rect = WindowsApi::window_rect(self.tracking_window)
+                        .ok()
+                        .filter(|rect| !rect.is_minimized());
+                    if rect.is_some() && rect == last_rect {
+                        return;
+                    }
+
+                    last_rect = rect;
+                    thread::sleep(UNMINIMIZE_POLL_INTERVAL);
+                }
+            }
+        }
+    }
+
+    fn status(&self) -> BorderStatus {
+        BorderStatus {
+            tracking_window: self.tracking_window,
+            process_name: WindowsApi::get_process_name(self.tracking_window).unwrap_or_default(),
+            active: self.is_window_active,
+            visible: !self.pause,
+            monitor: WindowsApi::get_monitor_device_name(self.current_monitor).unwrap_or_default(),
+            width: self.width,
+            animating: self.animation_manager.is_timer_running(),
+        }
+    }
+
+    /// Returns how far the border reaches outside the window's edge, which depends on its
+    /// placement and on how far its layers reach.
+    fn placement_margin(&self) -> i32 {
+        let border_margin = match self.placement {
+            BorderPlacement::Outside => self.width,
+            BorderPlacement::Center => (self.width + 1) / 2,
+            BorderPlacement::Inside => 0,
+        };
+
+        let scale = self.current_dpi / 96.0;
+        let layers_margin = self
+            .layers
+            .iter()
+            .map(|layer| ((layer.offset + layer.width as i32) as f32 * scale).ceil() as i32)
+            .max()
+            .unwrap_or(0);
+
+        border_margin.max(layers_margin)
     }
 
     fn update_position(&mut self, other_flags: Option<SET_WINDOW_POS_FLAGS>) -> anyhow::Result<()> {
@@ -566,6 +899,7 @@ impl Border {
             self.border_window,
             &self.window_rect,
             self.tracking_window,
+            self.always_on_top,
             other_flags,
         )
         .with_context(|| {
@@ -585,11 +919,32 @@ impl Border {
     }
 
     fn update_color(&mut self, check_delay: Option<u32>) -> anyhow::Result<()> {
-        self.is_window_active = self.tracking_window == *get_active_window();
+        let was_window_active = self.is_window_active;
+        self.is_window_active = ForcedState::is_window_active(
+            self.forced_state,
+            self.always_active,
+            self.tracking_window == *get_active_window(),
+        );
+        self.animation_manager
+            .set_window_active(self.is_window_active);
+
+        // Animation delays and repeat counts start over whenever the focus state changes
+        if self.is_window_active != was_window_active {
+            self.animation_manager.progress.state_elapsed = 0.0;
+            self.animation_manager.progress.spiral_cycles = 0;
+            self.animation_manager.flags.spiral_finished = false;
+        }
+
+        // Pulsing only runs while the window is active, so settle back to full opacity
+        if !self.is_window_active {
+            self.animation_manager.progress.pulse = 0.0;
+            self.animation_manager.progress.pulse_opacity = None;
+        }
 
         if self.current_animations().contains_kind(AnimationKind::Fade) {
-            if check_delay.is_some_and(|delay| delay == 0) {
-                // More idiomatic check
+            // Without the animation timer, the fade would never run, so snap to the new state
+            if check_delay.is_some_and(|delay| delay == 0) || AppManager::get().animations_paused()
+            {
                 self.update_brush_opacities();
                 self.refresh_fade_progress();
             } else {
@@ -621,14 +976,252 @@ impl Border {
         let config = AppManager::get().config().clone();
         let global = &config.global_rule;
 
-        let width_config = window_rule.border_width.unwrap_or(global.border_width);
-        let style_config = window_rule
-            .border_style
-            .as_ref()
-            .unwrap_or(&global.border_style);
+        let monitor = self.monitor_override.as_ref();
+
+        let width_config = match monitor
+            .and_then(|monitor| monitor.border_width)
+            .or(window_rule.border_width)
+        {
+            Some(width) => width,
+            None => match BorderWidth::try_from(global.border_width.clone()) {
+                Ok(width) => width,
+                Err(err) => {
+                    error!("could not update border width: {err:#}");
+                    return;
+                }
+            },
+        };
+        let style_config = monitor
+            .and_then(|monitor| monitor.border_style.as_ref())
+            .or(window_rule.border_style.as_ref())
+            .unwrap_or(&global.border_style)
+            .clone();
+
+        self.set_width(width_config, self.current_dpi);
+        self.radius = style_config.to_radius(
+            self.width,
+            self.current_dpi,
+            self.tracking_window,
+            self.force_corners,
+        );
+        self.corner_radii = style_config.to_corner_radii(self.current_dpi);
+        self.render_rect.radiusX = self.radius;
+        self.render_rect.radiusY = self.radius;
+        self.update_stroke_style().log_if_err();
+    }
+
+    /// Reads the tracking window's DPI again and rescales the width and radius if it changed.
+    ///
+    /// Returns whether the DPI changed.
+    fn update_dpi(&mut self) -> anyhow::Result<bool> {
+        let new_dpi = WindowsApi::get_dpi_for_window(self.tracking_window)
+            .context("could not get dpi for window")? as f32;
+
+        if new_dpi == self.current_dpi {
+            return Ok(false);
+        }
+
+        self.current_dpi = new_dpi;
+        self.update_width_radius();
+
+        Ok(true)
+    }
+
+    /// Resizes the render resources for `current_monitor` and the current width, and recreates
+    /// the brushes if the DPI changed.
+    fn update_monitor_resources(&mut self, dpi_changed: bool) {
+        self.render_resources
+            .update(
+                self.current_monitor,
+                self.placement_margin(),
+                self.window_padding,
+            )
+            .context("could not update render resources")
+            .log_if_err();
+
+        if let (Ok(d2d_context), Ok(border_bitmap), Ok(mask_bitmap)) = (
+            self.render_resources.d2d_context(),
+            self.render_resources.border_bitmap(),
+            self.render_resources.mask_bitmap(),
+        ) {
+            self.effect_manager
+                .create_command_lists_if_enabled(d2d_context, border_bitmap, mask_bitmap)
+                .log_if_err();
+        } else {
+            error!("could not get resources to create effects command list");
+        }
+
+        // The gradient geometry is in pixels, so it must be recomputed against the new scaling
+        // rather than the one the brushes were created with
+        if dpi_changed {
+            self.recreate_brushes()
+                .context("could not recreate brushes")
+                .log_if_err();
+        }
+    }
+
+    /// Matches the animation frame rate to the refresh rate of `current_monitor`, unless the config
+    /// sets one. A running animation timer is restarted so the new interval takes effect.
+    fn update_monitor_fps(&mut self) -> anyhow::Result<()> {
+        let refresh_rate = AppManager::get().monitor_refresh_rate(self.current_monitor);
+
+        if self.animation_manager.set_monitor_fps(refresh_rate)
+            && self.animation_manager.is_timer_running()
+        {
+            self.animation_manager.kill_timer(self.border_window)?;
+            self.animation_manager.set_timer(self.border_window)?;
+        }
+
+        Ok(())
+    }
+
+    /// Looks up the override for `current_monitor` after the window moved to another monitor.
+    /// When a different override applies, the width, offset, style and colors are resolved again.
+    ///
+    /// Returns whether the override changed.
+    fn update_monitor_override(&mut self) -> anyhow::Result<bool> {
+        let config = ParsedConfig::try_from(AppManager::get().config().clone())?;
+        let device_name = WindowsApi::get_monitor_device_name(self.current_monitor)?;
+        let monitor_override = config.monitor_override(&device_name).cloned();
+
+        let old_key = self.monitor_override.as_ref().map(|monitor| &monitor.key);
+        if monitor_override.as_ref().map(|monitor| &monitor.key) == old_key {
+            return Ok(false);
+        }
+
+        let window_rule = WindowsApi::get_window_rule(self.tracking_window);
+        let global = &config.global_rule;
+        let monitor = monitor_override.as_ref();
+
+        self.active_color = monitor
+            .and_then(|monitor| monitor.active_color.as_ref())
+            .or(window_rule.active_color.as_ref())
+            .unwrap_or(&global.active_color)
+            .clone();
+        self.inactive_color = monitor
+            .and_then(|monitor| monitor.inactive_color.as_ref())
+            .or(window_rule.inactive_color.as_ref())
+            .unwrap_or(&global.inactive_color)
+            .resolve(&self.active_color)?;
+        self.offset = monitor
+            .and_then(|monitor| monitor.border_offset)
+            .or(window_rule.border_offset)
+            .unwrap_or(global.border_offset);
+
+        self.monitor_override = monitor_override;
+        self.update_width_radius();
+
+        let brush_properties = D2D1_BRUSH_PROPERTIES {
+            opacity: 0.0,
+            transform: Matrix3x2::identity(),
+        };
+        let d2d_context = self.render_resources.d2d_context()?;
+        self.active_color.to_d2d1_brush(
+            d2d_context,
+            &self.window_rect.into(),
+            &brush_properties,
+        )?;
+        self.inactive_color.to_d2d1_brush(
+            d2d_context,
+            &self.window_rect.into(),
+            &brush_properties,
+        )?;
+        self.update_brush_opacities();
+
+        Ok(true)
+    }
+
+    /// Scales the configured edge widths to the given DPI. Borders with differing edges are sized
+    /// for their widest edge.
+    fn set_width(&mut self, width: BorderWidth, dpi: f32) {
+        let scaled = width.scale(dpi);
+
+        self.width = scaled.max();
+        self.edge_widths = (!scaled.is_uniform()).then_some(scaled);
+    }
+
+    /// Recreates the cached stroke styles of the border and its layers. Dash lengths are relative
+    /// to the stroke width in Direct2D, so this must be called whenever the width or DPI changes.
+    fn update_stroke_style(&mut self) -> anyhow::Result<()> {
+        let scale = self.current_dpi / 96.0;
+
+        self.d2d_stroke_style = create_stroke_style(&self.stroke_style, self.width as f32, scale)?;
+        self.layer_stroke_styles = self
+            .layers
+            .iter()
+            .map(|layer| {
+                create_stroke_style(&layer.stroke_style, layer.width as f32 * scale, scale)
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(())
+    }
+
+    /// Returns the area enclosed by the border when its edges have different widths.
+    fn edge_inner_rect(&self, edge_widths: &BorderWidth) -> D2D_RECT_F {
+        let half_width = self.width as f32 / 2.0;
+        let rect = &self.render_rect.rect;
+
+        D2D_RECT_F {
+            left: rect.left - half_width + edge_widths.left as f32,
+            top: rect.top - half_width + edge_widths.top as f32,
+            right: rect.right + half_width - edge_widths.right as f32,
+            bottom: rect.bottom + half_width - edge_widths.bottom as f32,
+        }
+    }
+
+    /// Returns the radius of each corner at the center of the border line. Rounded corners can't
+    /// join edges of different widths, so those corners are square.
+    fn effective_corner_radii(&self) -> Option<CornerRadii> {
+        let Some(ref edge_widths) = self.edge_widths else {
+            return self.corner_radii;
+        };
 
-        self.width = (width_config as f32 * self.current_dpi / 96.0).round() as i32;
-        self.radius = style_config.to_radius(self.width, self.current_dpi, self.tracking_window);
+        let radii = self
+            .corner_radii
+            .unwrap_or_else(|| CornerRadii::uniform(self.radius));
+        let corner = |radius: f32, a: i32, b: i32| match a == b {
+            true => radius,
+            false => 0.0,
+        };
+
+        Some(CornerRadii {
+            top_left: corner(radii.top_left, edge_widths.top, edge_widths.left),
+            top_right: corner(radii.top_right, edge_widths.top, edge_widths.right),
+            bottom_right: corner(radii.bottom_right, edge_widths.bottom, edge_widths.right),
+            bottom_left: corner(radii.bottom_left, edge_widths.bottom, edge_widths.left),
+        })
+    }
+
+    /// Returns the corner radii of the outer edge and of `edge_inner_rect`, which stay concentric
+    /// at the corners that are rounded.
+    fn edge_corner_radii(&self, edge_widths: &BorderWidth) -> (CornerRadii, CornerRadii) {
+        let outer = self
+            .effective_corner_radii()
+            .unwrap_or_default()
+            .expand(self.width as f32 / 2.0);
+        // Rounded corners join edges of the same width, so either edge can be subtracted
+        let inner = CornerRadii {
+            top_left: (outer.top_left - edge_widths.top as f32).max(0.0),
+            top_right: (outer.top_right - edge_widths.top as f32).max(0.0),
+            bottom_right: (outer.bottom_right - edge_widths.bottom as f32).max(0.0),
+            bottom_left: (outer.bottom_left - edge_widths.bottom as f32).max(0.0),
+        };
+
+        (outer, inner)
+    }
+
+    /// Returns whether a point in screen coordinates lies inside the inner edge of the border,
+    /// i.e. over the tracking window rather than over the border itself.
+    fn is_inside_inner_edge(&self, x: i32, y: i32) -> bool {
+        let inner = match self.edge_widths {
+            Some(ref edge_widths) => self.edge_inner_rect(edge_widths),
+            None => self.edge_inner_rect(&BorderWidth::uniform(self.width)),
+        };
+        let x = (x - self.window_rect.left) as f32;
+        let y = (y - self.window_rect.top) as f32;
+
+        x >= inner.left && x < inner.right && y >= inner.top && y < inner.bottom
     }
 
     fn current_animations(&self) -> &AnimationEngineVec {
@@ -655,94 +1248,395 @@ impl Border {
         }
     }
 
-    fn render(&mut self) -> anyhow::Result<()> {
-        self.last_render_time = Some(std::time::Instant::now());
-        let rect_width = self.window_rect.width() as f32;
-        let rect_height = self.window_rect.height() as f32;
+    /// Advances the hover fade towards the current hover state of the tracking window.
+    ///
+    /// Returns `true` if the hover progress changed and the border needs to be re-rendered.
+    fn update_hover_progress(&mut self, elapsed_time: &time::Duration) -> bool {
+        let target = match WindowsApi::is_cursor_over_window(self.tracking_window) {
+            true => 1.0,
+            false => 0.0,
+        };
 
-        let border_width = self.width as f32;
-        let border_offset = self.offset as f32;
-        let window_padding = self.window_padding as f32;
-        let border_radius = self.radius;
+        if self.hover_progress == target {
+            return false;
+        }
 
-        self.render_rect.rect = D2D_RECT_F {
-            left: border_width / 2.0 + window_padding - border_offset,
-            top: border_width / 2.0 + window_padding - border_offset,
-            right: rect_width - border_width / 2.0 - window_padding + border_offset,
-            bottom: rect_height - border_width / 2.0 - window_padding + border_offset,
+        // Reuse the fade duration if there is one so hovering feels consistent with focus changes
+        let duration = self
+            .current_animations()
+            .iter()
+            .find(|animation| animation.kind == AnimationKind::Fade)
+            .map(|animation| animation.duration)
+            .filter(|duration| *duration > 0.0)
+            .unwrap_or(200.0);
+        let delta = elapsed_time.as_millis_f32() / duration;
+
+        self.hover_progress = match target > self.hover_progress {
+            true => (self.hover_progress + delta).min(target),
+            false => (self.hover_progress - delta).max(target),
         };
 
-        if !self.current_effects().is_empty() {
-            self.render_with_effects(border_radius)?;
-            return Ok(());
+        true
+    }
+
+    fn update_trail_progress(&mut self, elapsed_time: &time::Duration) -> bool {
+        if self.trail_progress <= 0.0 {
+            return false;
         }
 
-        let d2d_context = self.render_resources.d2d_context()?;
+        let delta = match self.trail_duration {
+            0 => 1.0,
+            duration => elapsed_time.as_millis_f32() / duration as f32,
+        };
+        self.trail_progress = (self.trail_progress - delta).max(0.0);
 
-        unsafe {
-            let (bottom_color, top_color) = match self.is_window_active {
-                true => (&self.inactive_color, &self.active_color),
-                false => (&self.active_color, &self.inactive_color),
-            };
+        true
+    }
 
-            let target_bitmap = self.render_resources.target_bitmap()?;
-            d2d_context.SetTarget(target_bitmap);
+    fn trail_opacity(&self) -> f32 {
+        match self.hover_only {
+            true => self.trail_progress * self.hover_progress,
+            false => self.trail_progress,
+        }
+    }
 
-            d2d_context.BeginDraw();
-            d2d_context.Clear(None);
+    fn update_flash_progress(&mut self, elapsed_time: &time::Duration) -> bool {
+        if self.flash_progress <= 0.0 {
+            return false;
+        }
 
-            if bottom_color.get_opacity() > Some(0.0) {
-                if let Color::Gradient(gradient) = bottom_color {
-                    gradient.update_start_end_points(&self.window_rect.into());
-                }
+        let delta = match self.flash_duration {
+            0 => 1.0,
+            duration => elapsed_time.as_millis_f32() / duration as f32,
+        };
+        self.flash_progress = (self.flash_progress - delta).max(0.0);
 
-                match bottom_color.get_brush() {
-                    Some(id2d1_brush) => {
-                        self.draw_rectangle(d2d_context, id2d1_brush, border_radius)
-                    }
-                    None => debug!("ID2D1Brush for bottom_color has not been created yet"),
-                }
-            }
+        true
+    }
 
-            if top_color.get_opacity() > Some(0.0) {
-                if let Color::Gradient(gradient) = top_color {
-                    gradient.update_start_end_points(&self.window_rect.into());
-                }
+    fn update_transition_progress(&mut self, elapsed_time: &time::Duration) -> bool {
+        if self.transition_progress <= 0.0 {
+            return false;
+        }
 
-                match top_color.get_brush() {
-                    Some(id2d1_brush) => {
-                        self.draw_rectangle(d2d_context, id2d1_brush, border_radius)
-                    }
-                    None => debug!("ID2D1Brush for top_color has not been created yet"),
-                }
-            }
+        let delta = match self.transition_duration {
+            0 => 1.0,
+            duration => elapsed_time.as_millis_f32() / duration as f32,
+        };
+        self.transition_progress = (self.transition_progress - delta).max(0.0);
+        self.apply_color_transition();
 
-            if let Err(err) = d2d_context.EndDraw(None, None) {
-                self.handle_end_draw_error(err.clone());
-                return Err(err.into());
-            }
+        if self.transition_progress <= 0.0 {
+            self.transition_from_active = None;
+            self.transition_from_inactive = None;
+            self.update_keep_timer_alive();
+        }
 
-            // Present the swap chain buffer
-            let hresult = self
-                .render_resources
-                .swap_chain()?
-                .Present(1, DXGI_PRESENT::default());
-            if hresult != S_OK {
-                return Err(anyhow!("could not present swap_chain: {hresult}"));
+        true
+    }
+
+    /// Sets the brushes of the solid active and inactive colors to the color between the one
+    /// before the restyle and the new one, according to `transition_progress`.
+    fn apply_color_transition(&self) {
+        let transitions = [
+            (&self.active_color, self.transition_from_active),
+            (&self.inactive_color, self.transition_from_inactive),
+        ];
+
+        for (color, from) in transitions {
+            let (Color::Solid(solid), Some(from)) = (color, from) else {
+                continue;
+            };
+
+            if let Some(ref id2d1_brush) = solid.brush {
+                let color =
+                    interpolate_d2d1_colors(&from, &solid.color, 1.0 - self.transition_progress);
+                unsafe { id2d1_brush.SetColor(&color) };
             }
         }
+    }
 
-        Ok(())
+    /// Hover tracking, the focus trail, the flash and color transitions are driven by the animation
+    /// timer, so it must run for them even without animations.
+    fn update_keep_timer_alive(&mut self) {
+        self.animation_manager.set_keep_timer_alive(
+            self.hover_only
+                || self.trail_color.is_some()
+                || self.flash_color.is_some()
+                || self.transition_progress > 0.0,
+        );
     }
 
-    fn render_with_effects(&mut self, border_radius: f32) -> anyhow::Result<()> {
-        let d2d_context = self.render_resources.d2d_context()?;
+    /// Returns the colors drawn on top of the border while they fade out, with their opacity: the
+    /// focus trail, then the flash.
+    fn overlay_colors(&self) -> impl Iterator<Item = (&Color, f32)> {
+        let trail = self
+            .trail_color
+            .as_ref()
+            .filter(|_| self.trail_progress > 0.0)
+            .map(|color| (color, self.trail_opacity()));
+        let flash = self
+            .flash_color
+            .as_ref()
+            .filter(|_| self.flash_progress > 0.0)
+            .map(|color| (color, self.flash_progress));
 
-        unsafe {
-            let (bottom_color, top_color) = match self.is_window_active {
-                true => (&self.inactive_color, &self.active_color),
-                false => (&self.active_color, &self.inactive_color),
-            };
+        trail.into_iter().chain(flash)
+    }
+
+    /// Draws the overlay colors with `draw`, after the border itself so the focus trail and flash
+    /// fade out into the border color.
+    fn draw_overlay_colors(&self, mut draw: impl FnMut(&ID2D1Brush)) {
+        for (overlay_color, opacity) in self.overlay_colors() {
+            overlay_color.set_opacity(opacity);
+
+            if let Color::Gradient(gradient) = overlay_color {
+                gradient.update_geometry(&self.window_rect.into());
+            }
+
+            match overlay_color.get_brush() {
+                Some(id2d1_brush) => draw(id2d1_brush),
+                None => debug!("ID2D1Brush for overlay_color has not been created yet"),
+            }
+        }
+    }
+
+    /// Returns the current monitor's bounds relative to the border window, if clipping is enabled.
+    fn monitor_clip_rect(&self) -> Option<D2D_RECT_F> {
+        if !self.clip_to_monitor {
+            return None;
+        }
+
+        let monitor_rect = match WindowsApi::get_monitor_info(self.current_monitor) {
+            Ok(monitor_info) => monitor_info.rcMonitor,
+            Err(err) => {
+                error!("could not get monitor info to clip border: {err}");
+                return None;
+            }
+        };
+
+        Some(Self::clip_rect_relative_to(
+            &monitor_rect,
+            &self.window_rect,
+        ))
+    }
+
+    /// Converts a clip rect in screen coordinates to coordinates relative to the border window at
+    /// `window_rect`.
+    fn clip_rect_relative_to(clip_rect: &RECT, window_rect: &RECT) -> D2D_RECT_F {
+        D2D_RECT_F {
+            left: (clip_rect.left - window_rect.left) as f32,
+            top: (clip_rect.top - window_rect.top) as f32,
+            right: (clip_rect.right - window_rect.left) as f32,
+            bottom: (clip_rect.bottom - window_rect.top) as f32,
+        }
+    }
+
+    /// Hides the border while its window covers the entire monitor, e.g. in borderless fullscreen,
+    /// where it would otherwise be drawn over the edges of the screen, or while its window is on a
+    /// disabled monitor.
+    ///
+    /// Returns whether the border is hidden, in which case the caller should skip rendering.
+    fn update_hidden(&mut self) -> bool {
+        // With an auto-hiding taskbar, maximized windows cover the entire monitor as well
+        let is_bordered_maximized =
+            self.border_maximized && WindowsApi::is_window_maximized(self.tracking_window);
+        self.is_fullscreen = self.hide_on_fullscreen
+            && !is_bordered_maximized
+            && WindowsApi::is_window_fullscreen(self.tracking_window);
+        self.on_disabled_monitor =
+            is_on_disabled_monitor(self.tracking_window, &self.disabled_monitors);
+        self.suppressed_by_game_mode = game_mode::is_suppressed(self.tracking_window);
+
+        let is_hidden = self.is_hidden();
+        if is_hidden && WindowsApi::is_window_visible(self.border_window) {
+            self.update_position(Some(SWP_HIDEWINDOW)).log_if_err();
+        }
+
+        is_hidden
+    }
+
+    /// Returns whether the border was hidden by the last call to `update_hidden`.
+    fn is_hidden(&self) -> bool {
+        self.is_fullscreen || self.on_disabled_monitor || self.suppressed_by_game_mode
+    }
+
+    /// Renders once the location render timer fires, instead of on every location change.
+    ///
+    /// Dragging or resizing a window sends dozens of location changes per frame, so rendering
+    /// each of them makes the border stutter. The timer isn't restarted by further changes, which
+    /// keeps the border rendering at a steady rate while the window keeps moving.
+    fn schedule_location_render(&mut self) {
+        if self.location_render_pending {
+            return;
+        }
+
+        match WindowsApi::set_timer(
+            self.border_window,
+            LOCATION_RENDER_TIMER_ID,
+            LOCATION_RENDER_DELAY,
+        ) {
+            Ok(()) => self.location_render_pending = true,
+            Err(err) => {
+                error!("{err:#}");
+                self.render().log_if_err();
+            }
+        }
+    }
+
+    fn render(&mut self) -> anyhow::Result<()> {
+        // Any render, e.g. an animation frame, also draws the latest location
+        if self.location_render_pending {
+            self.location_render_pending = false;
+            WindowsApi::kill_timer(self.border_window, LOCATION_RENDER_TIMER_ID).log_if_err();
+        }
+
+        let hover_scale = match self.hover_only {
+            true => self.hover_progress,
+            false => 1.0,
+        };
+        let pulse_scale = self.animation_manager.progress.pulse_opacity.unwrap_or(1.0);
+        let active_scale = self.active_opacity * pulse_scale * hover_scale;
+        let inactive_scale = self.inactive_opacity * hover_scale;
+
+        if active_scale == 1.0 && inactive_scale == 1.0 {
+            return self.render_frame();
+        }
+
+        // Scale whatever opacities the focus colors and fade animation have settled on by the
+        // configured opacity, pulse and hover progress, then restore them so they aren't compounded.
+        let active_opacity = self.active_color.get_opacity().unwrap_or_default();
+        let inactive_opacity = self.inactive_color.get_opacity().unwrap_or_default();
+
+        self.active_color.set_opacity(active_opacity * active_scale);
+        self.inactive_color
+            .set_opacity(inactive_opacity * inactive_scale);
+
+        let result = self.render_frame();
+
+        self.active_color.set_opacity(active_opacity);
+        self.inactive_color.set_opacity(inactive_opacity);
+
+        result
+    }
+
+    fn render_frame(&mut self) -> anyhow::Result<()> {
+        self.last_render_time = Some(std::time::Instant::now());
+        let rect_width = self.window_rect.width() as f32;
+        let rect_height = self.window_rect.height() as f32;
+
+        let border_width = self.width as f32;
+        // window_rect includes the room for the border, but a percentage offset is relative to
+        // the tracking window itself
+        let margin = self.placement_margin() + self.window_padding;
+        let border_offset = self.offset.to_pixels(
+            self.window_rect.width() - 2 * margin,
+            self.window_rect.height() - 2 * margin,
+        ) as f32;
+        // Distance from the window's edge to the center of the border line, inwards
+        let line_center = match self.placement {
+            BorderPlacement::Outside => -border_width / 2.0,
+            BorderPlacement::Center => 0.0,
+            BorderPlacement::Inside => border_width / 2.0,
+        };
+        let inset = margin as f32 + line_center - border_offset;
+        let border_radius = self.radius;
+
+        self.render_rect.rect = D2D_RECT_F {
+            left: inset,
+            top: inset,
+            right: rect_width - inset,
+            bottom: rect_height - inset,
+        };
+
+        if !self.current_effects().is_empty() {
+            self.render_with_effects(border_radius)?;
+            return Ok(());
+        }
+
+        let clip_rect = self.monitor_clip_rect();
+        let d2d_context = self.render_resources.d2d_context()?;
+
+        unsafe {
+            let (bottom_color, top_color) = match self.is_window_active {
+                true => (&self.inactive_color, &self.active_color),
+                false => (&self.active_color, &self.inactive_color),
+            };
+
+            let target_bitmap = self.render_resources.target_bitmap()?;
+            d2d_context.SetTarget(target_bitmap);
+
+            d2d_context.BeginDraw();
+            d2d_context.Clear(None);
+
+            if let Some(ref clip_rect) = clip_rect {
+                d2d_context.PushAxisAlignedClip(clip_rect, D2D1_ANTIALIAS_MODE_ALIASED);
+            }
+
+            self.draw_layers(d2d_context, border_radius);
+
+            if bottom_color.get_opacity() > Some(0.0) {
+                if let Color::Gradient(gradient) = bottom_color {
+                    gradient.update_geometry(&self.window_rect.into());
+                }
+
+                match bottom_color.get_brush() {
+                    Some(id2d1_brush) => {
+                        self.draw_rectangle(d2d_context, id2d1_brush, border_radius)
+                    }
+                    None => debug!("ID2D1Brush for bottom_color has not been created yet"),
+                }
+            }
+
+            if top_color.get_opacity() > Some(0.0) {
+                if let Color::Gradient(gradient) = top_color {
+                    gradient.update_geometry(&self.window_rect.into());
+                }
+
+                match top_color.get_brush() {
+                    Some(id2d1_brush) => {
+                        self.draw_rectangle(d2d_context, id2d1_brush, border_radius)
+                    }
+                    None => debug!("ID2D1Brush for top_color has not been created yet"),
+                }
+            }
+
+            self.draw_overlay_colors(|id2d1_brush| {
+                self.draw_rectangle(d2d_context, id2d1_brush, border_radius)
+            });
+
+            if clip_rect.is_some() {
+                d2d_context.PopAxisAlignedClip();
+            }
+
+            if let Err(err) = d2d_context.EndDraw(None, None) {
+                self.handle_end_draw_error(err.clone());
+                return Err(err.into());
+            }
+
+            // Present the swap chain buffer
+            let (sync_interval, present_flags) = self.render_resources.present_options();
+            let hresult = self
+                .render_resources
+                .swap_chain()?
+                .Present(sync_interval, present_flags);
+            if hresult != S_OK {
+                return Err(anyhow!("could not present swap_chain: {hresult}"));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render_with_effects(&mut self, border_radius: f32) -> anyhow::Result<()> {
+        let clip_rect = self.monitor_clip_rect();
+        let d2d_context = self.render_resources.d2d_context()?;
+
+        unsafe {
+            let (bottom_color, top_color) = match self.is_window_active {
+                true => (&self.inactive_color, &self.active_color),
+                false => (&self.active_color, &self.inactive_color),
+            };
 
             let render_rect_adjusted = D2D1_ROUNDED_RECT {
                 rect: D2D_RECT_F {
@@ -761,13 +1655,15 @@ impl Border {
             d2d_context.BeginDraw();
             d2d_context.Clear(None);
 
+            self.draw_layers(d2d_context, border_radius);
+
             if bottom_color.get_opacity() > Some(0.0) {
                 if let Color::Gradient(gradient) = bottom_color {
-                    gradient.update_start_end_points(&self.window_rect.into());
+                    gradient.update_geometry(&self.window_rect.into());
                 }
 
                 match bottom_color.get_brush() {
-                    Some(id2d1_brush) => self.fill_rectangle(
+                    Some(id2d1_brush) => self.fill_border(
                         &render_rect_adjusted,
                         d2d_context,
                         id2d1_brush,
@@ -779,11 +1675,11 @@ impl Border {
 
             if top_color.get_opacity() > Some(0.0) {
                 if let Color::Gradient(gradient) = top_color {
-                    gradient.update_start_end_points(&self.window_rect.into());
+                    gradient.update_geometry(&self.window_rect.into());
                 }
 
                 match top_color.get_brush() {
-                    Some(id2d1_brush) => self.fill_rectangle(
+                    Some(id2d1_brush) => self.fill_border(
                         &render_rect_adjusted,
                         d2d_context,
                         id2d1_brush,
@@ -793,6 +1689,15 @@ impl Border {
                 }
             }
 
+            self.draw_overlay_colors(|id2d1_brush| {
+                self.fill_border(
+                    &render_rect_adjusted,
+                    d2d_context,
+                    id2d1_brush,
+                    border_radius,
+                )
+            });
+
             if let Err(err) = d2d_context.EndDraw(None, None) {
                 self.handle_end_draw_error(err.clone());
                 return Err(err.into());
@@ -831,12 +1736,17 @@ impl Border {
             d2d_context.BeginDraw();
             d2d_context.Clear(None);
 
-            self.fill_rectangle(
-                &render_rect_adjusted,
-                d2d_context,
-                &opaque_brush,
-                border_radius,
-            );
+            match self.edge_widths {
+                Some(ref edge_widths) => {
+                    self.fill_edge_inner_rect(d2d_context, &opaque_brush, edge_widths)
+                }
+                None => self.fill_rectangle(
+                    &render_rect_adjusted,
+                    d2d_context,
+                    &opaque_brush,
+                    border_radius,
+                ),
+            }
 
             if let Err(err) = d2d_context.EndDraw(None, None) {
                 self.handle_end_draw_error(err.clone());
@@ -854,6 +1764,10 @@ impl Border {
             d2d_context.BeginDraw();
             d2d_context.Clear(None);
 
+            if let Some(ref clip_rect) = clip_rect {
+                d2d_context.PushAxisAlignedClip(clip_rect, D2D1_ANTIALIAS_MODE_ALIASED);
+            }
+
             // Draw using the command list
             d2d_context.DrawImage(
                 command_list,
@@ -863,16 +1777,21 @@ impl Border {
                 D2D1_COMPOSITE_MODE_SOURCE_OVER,
             );
 
+            if clip_rect.is_some() {
+                d2d_context.PopAxisAlignedClip();
+            }
+
             if let Err(err) = d2d_context.EndDraw(None, None) {
                 self.handle_end_draw_error(err.clone());
                 return Err(err.into());
             }
 
             // Present the swap chain buffer
+            let (sync_interval, present_flags) = self.render_resources.present_options();
             let hresult = self
                 .render_resources
                 .swap_chain()?
-                .Present(1, DXGI_PRESENT::default());
+                .Present(sync_interval, present_flags);
             if hresult != S_OK {
                 return Err(anyhow!("could not present swap_chain: {hresult}"));
             }
@@ -887,24 +1806,323 @@ impl Border {
         brush: &ID2D1Brush,
         border_radius: f32,
     ) {
+        if let Some(ref edge_widths) = self.edge_widths {
+            self.fill_edges(d2d_context, brush, edge_widths);
+            return;
+        }
+
+        if self.stroke_style.kind == StrokeKind::Double {
+            self.draw_double_rectangle(d2d_context, brush, border_radius);
+            return;
+        }
+
+        self.stroke_rectangle(
+            d2d_context,
+            brush,
+            &self.render_rect,
+            self.width as f32,
+            border_radius,
+        );
+    }
+
+    /// Draws two concentric lines, each a third of the border width, separated by a gap of the
+    /// same size. Both lines stay within the area of a single stroke, so no extra room is needed.
+    fn draw_double_rectangle(
+        &self,
+        d2d_context: &ID2D1DeviceContext7,
+        brush: &ID2D1Brush,
+        border_radius: f32,
+    ) {
+        let line_width = self.width as f32 / 3.0;
+        let inset_rect = |inset: f32| D2D1_ROUNDED_RECT {
+            rect: D2D_RECT_F {
+                left: self.render_rect.rect.left + inset,
+                top: self.render_rect.rect.top + inset,
+                right: self.render_rect.rect.right - inset,
+                bottom: self.render_rect.rect.bottom - inset,
+            },
+            radiusX: (border_radius - inset).max(0.0),
+            radiusY: (border_radius - inset).max(0.0),
+        };
+
+        for inset in [-line_width, line_width] {
+            let rounded_rect = inset_rect(inset);
+            let radius = rounded_rect.radiusX;
+            self.stroke_rectangle(d2d_context, brush, &rounded_rect, line_width, radius);
+        }
+    }
+
+    /// Draws the border layers, from back to front. They share the border's opacity so they fade
+    /// in and out along with it.
+    fn draw_layers(&self, d2d_context: &ID2D1DeviceContext7, border_radius: f32) {
+        let opacity = [&self.active_color, &self.inactive_color]
+            .into_iter()
+            .filter_map(|color| color.get_opacity())
+            .fold(0.0, f32::max);
+        if opacity <= 0.0 {
+            return;
+        }
+
+        // Layers have the same radius at every corner, but edges of different widths always square
+        // at least one corner of the border
+        let border_radius = match self.edge_widths {
+            Some(_) => 0.0,
+            None => border_radius,
+        };
+        let scale = self.current_dpi / 96.0;
+        let margin = (self.placement_margin() + self.window_padding) as f32;
+        let rect_width = self.window_rect.width() as f32;
+        let rect_height = self.window_rect.height() as f32;
+        // Distance from the window's edge to the center of the border line, outwards
+        let border_center = margin - self.render_rect.rect.left;
+
+        for (layer, stroke_style) in self.layers.iter().zip(self.layer_stroke_styles.iter()) {
+            let color = match self.is_window_active {
+                true => &layer.active_color,
+                false => &layer.inactive_color,
+            };
+            let Some(id2d1_brush) = color.get_brush() else {
+                debug!("ID2D1Brush for layer color has not been created yet");
+                continue;
+            };
+
+            color.set_opacity(opacity);
+            if let Color::Gradient(gradient) = color {
+                gradient.update_geometry(&self.window_rect.into());
+            }
+
+            let layer_width = layer.width as f32 * scale;
+            let layer_center = layer.offset as f32 * scale + layer_width / 2.0;
+            // Takes the distance from the window's edge to the center of the line, outwards
+            let line_rect = |center: f32| {
+                let inset = margin - center;
+                // Keep the layer's corners concentric with the border's
+                let radius = match border_radius {
+                    0.0 => 0.0,
+                    _ => (border_radius + center - border_center).max(0.0),
+                };
+                D2D1_ROUNDED_RECT {
+                    rect: D2D_RECT_F {
+                        left: inset,
+                        top: inset,
+                        right: rect_width - inset,
+                        bottom: rect_height - inset,
+                    },
+                    radiusX: radius,
+                    radiusY: radius,
+                }
+            };
+
+            // Like the border, a double layer is two lines a third of its width each
+            let lines = match layer.stroke_style.kind {
+                StrokeKind::Double => {
+                    let line_width = layer_width / 3.0;
+                    vec![
+                        (line_rect(layer_center - line_width), line_width),
+                        (line_rect(layer_center + line_width), line_width),
+                    ]
+                }
+                _ => vec![(line_rect(layer_center), layer_width)],
+            };
+
+            for (rounded_rect, line_width) in lines {
+                unsafe {
+                    match rounded_rect.radiusX {
+                        0.0 => d2d_context.DrawRectangle(
+                            &rounded_rect.rect,
+                            id2d1_brush,
+                            line_width,
+                            stroke_style.as_deref(),
+                        ),
+                        _ => d2d_context.DrawRoundedRectangle(
+                            &rounded_rect,
+                            id2d1_brush,
+                            line_width,
+                            stroke_style.as_deref(),
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    fn stroke_rectangle(
+        &self,
+        d2d_context: &ID2D1DeviceContext7,
+        brush: &ID2D1Brush,
+        rounded_rect: &D2D1_ROUNDED_RECT,
+        stroke_width: f32,
+        border_radius: f32,
+    ) {
+        let geometry = match border_radius {
+            0.0 => None,
+            _ => self.corner_geometry(rounded_rect),
+        };
+        if let Some(geometry) = geometry {
+            unsafe {
+                d2d_context.DrawGeometry(
+                    &geometry,
+                    brush,
+                    stroke_width,
+                    self.d2d_stroke_style.as_deref(),
+                )
+            };
+            return;
+        }
+
         unsafe {
             match border_radius {
                 0.0 => d2d_context.DrawRectangle(
-                    &self.render_rect.rect,
+                    &rounded_rect.rect,
                     brush,
-                    self.width as f32,
-                    None,
+                    stroke_width,
+                    self.d2d_stroke_style.as_deref(),
                 ),
                 _ => d2d_context.DrawRoundedRectangle(
-                    &self.render_rect,
+                    rounded_rect,
                     brush,
-                    self.width as f32,
-                    None,
+                    stroke_width,
+                    self.d2d_stroke_style.as_deref(),
                 ),
             }
         }
     }
 
+    /// Builds the outline of `rounded_rect` with the configured per-corner radii, or returns
+    /// `None` if every corner has the same radius (drawn as a plain rounded rectangle instead).
+    fn corner_geometry(&self, rounded_rect: &D2D1_ROUNDED_RECT) -> Option<ID2D1PathGeometry1> {
+        let corner_radii = self.effective_corner_radii()?;
+
+        // The rect may be inset or expanded from render_rect, so its corners follow suit
+        let expansion = self.render_rect.rect.left - rounded_rect.rect.left;
+        let radii = corner_radii.expand(expansion);
+
+        let mut cache = self.corner_geometries.borrow_mut();
+        if let Some((_, _, geometry)) = cache
+            .iter()
+            .find(|(rect, cached_radii, _)| *rect == rounded_rect.rect && *cached_radii == radii)
+        {
+            return Some(geometry.clone());
+        }
+
+        match rounded_rect_geometry(&rounded_rect.rect, &radii) {
+            Ok(geometry) => {
+                // Evict the oldest outline, which is likely from before a resize
+                if cache.len() >= MAX_CACHED_CORNER_GEOMETRIES {
+                    cache.remove(0);
+                }
+                cache.push((rounded_rect.rect, radii, geometry.clone()));
+                Some(geometry)
+            }
+            Err(err) => {
+                error!("could not create per-corner border geometry: {err}");
+                None
+            }
+        }
+    }
+
+    /// Paints the border shape used as the source for effects. Patterned borders must be stroked,
+    /// since filling the outer rectangle would cover their gaps.
+    fn fill_border(
+        &self,
+        rounded_rect: &D2D1_ROUNDED_RECT,
+        d2d_context: &ID2D1DeviceContext7,
+        brush: &ID2D1Brush,
+        border_radius: f32,
+    ) {
+        match self.stroke_style.kind {
+            StrokeKind::Solid => {
+                self.fill_rectangle(rounded_rect, d2d_context, brush, border_radius)
+            }
+            _ => self.draw_rectangle(d2d_context, brush, border_radius),
+        }
+    }
+
+    /// Fills the area between the outer edge of the border and `edge_inner_rect`, so every side
+    /// can have a different width. Without rounded corners, each edge is filled as its own band.
+    fn fill_edges(
+        &self,
+        d2d_context: &ID2D1DeviceContext7,
+        brush: &ID2D1Brush,
+        edge_widths: &BorderWidth,
+    ) {
+        let half_width = self.width as f32 / 2.0;
+        let rect = &self.render_rect.rect;
+        let outer = D2D_RECT_F {
+            left: rect.left - half_width,
+            top: rect.top - half_width,
+            right: rect.right + half_width,
+            bottom: rect.bottom + half_width,
+        };
+        let inner = self.edge_inner_rect(edge_widths);
+
+        let (outer_radii, inner_radii) = self.edge_corner_radii(edge_widths);
+        if outer_radii.max() > 0.0 {
+            match rounded_band_geometry(&outer, &outer_radii, &inner, &inner_radii) {
+                Ok(geometry) => {
+                    unsafe { d2d_context.FillGeometry(&geometry, brush, None) };
+                    return;
+                }
+                Err(err) => error!("could not create border edge geometry: {err}"),
+            }
+        }
+
+        // The top and bottom bands span the whole width, the sides fill the gap between them
+        let edges = [
+            D2D_RECT_F {
+                bottom: inner.top,
+                ..outer
+            },
+            D2D_RECT_F {
+                top: inner.bottom,
+                ..outer
+            },
+            D2D_RECT_F {
+                top: inner.top,
+                right: inner.left,
+                bottom: inner.bottom,
+                ..outer
+            },
+            D2D_RECT_F {
+                left: inner.right,
+                top: inner.top,
+                bottom: inner.bottom,
+                ..outer
+            },
+        ];
+
+        for edge in edges
+            .iter()
+            .filter(|e| e.right > e.left && e.bottom > e.top)
+        {
+            unsafe { d2d_context.FillRectangle(edge, brush) };
+        }
+    }
+
+    /// Fills the area enclosed by the border when its edges have different widths, with the same
+    /// corners as `fill_edges`.
+    fn fill_edge_inner_rect(
+        &self,
+        d2d_context: &ID2D1DeviceContext7,
+        brush: &ID2D1Brush,
+        edge_widths: &BorderWidth,
+    ) {
+        let inner = self.edge_inner_rect(edge_widths);
+        let (_, inner_radii) = self.edge_corner_radii(edge_widths);
+
+        if inner_radii.max() > 0.0 {
+            match rounded_rect_geometry(&inner, &inner_radii) {
+                Ok(geometry) => {
+                    unsafe { d2d_context.FillGeometry(&geometry, brush, None) };
+                    return;
+                }
+                Err(err) => error!("could not create border edge geometry: {err}"),
+            }
+        }
+
+        unsafe { d2d_context.FillRectangle(&inner, brush) };
+    }
+
     fn fill_rectangle(
         &self,
         rounded_rect: &D2D1_ROUNDED_RECT,
@@ -912,6 +2130,15 @@ impl Border {
         brush: &ID2D1Brush,
         border_radius: f32,
     ) {
+        let geometry = match border_radius {
+            0.0 => None,
+            _ => self.corner_geometry(rounded_rect),
+        };
+        if let Some(geometry) = geometry {
+            unsafe { d2d_context.FillGeometry(&geometry, brush, None) };
+            return;
+        }
+
         unsafe {
             match border_radius {
                 0.0 => d2d_context.FillRectangle(&rounded_rect.rect, brush),
@@ -927,34 +2154,8 @@ impl Border {
             // drivers, changing screen resolution, etc.
             warn!("render target has been lost; attempting to recreate");
 
-            if let Err(err_2) = self.render_resources.create(
-                self.current_monitor,
-                self.width,
-                self.window_padding,
-                self.border_window,
-            ) {
-                error!("could not recreate render target; exiting thread: {err_2}");
-                self.exit_border_thread();
-                return;
-            }
-
-            // This really should not fail. If it does, I messed up somewhere.
-            let (Ok(d2d_context), Ok(border_bitmap), Ok(mask_bitmap)) = (
-                self.render_resources.d2d_context(),
-                self.render_resources.border_bitmap(),
-                self.render_resources.mask_bitmap(),
-            ) else {
-                error!("could not get render resources even after recreating them; exiting thread");
-                self.exit_border_thread();
-                return;
-            };
-
-            if let Err(err_3) = self.effect_manager.create_command_lists_if_enabled(
-                d2d_context,
-                border_bitmap,
-                mask_bitmap,
-            ) {
-                error!("could not recreate effects command lists; exiting thread: {err_3}");
+            if let Err(err_2) = self.recreate_render_resources() {
+                error!("{err_2:#}; exiting thread");
                 self.exit_border_thread();
                 return;
             }
@@ -966,6 +2167,236 @@ impl Border {
         }
     }
 
+    fn recreate_render_resources(&mut self) -> anyhow::Result<()> {
+        let devices_recreated = AppManager::get().recreate_devices_if_removed()?;
+
+        self.render_resources
+            .create(
+                self.current_monitor,
+                self.placement_margin(),
+                self.window_padding,
+                self.border_window,
+            )
+            .context("could not recreate render target")?;
+
+        // This really should not fail. If it does, I messed up somewhere.
+        let (Ok(d2d_context), Ok(border_bitmap), Ok(mask_bitmap)) = (
+            self.render_resources.d2d_context(),
+            self.render_resources.border_bitmap(),
+            self.render_resources.mask_bitmap(),
+        ) else {
+            return Err(anyhow!(
+                "could not get render resources even after recreating them"
+            ));
+        };
+
+        self.effect_manager
+            .create_command_lists_if_enabled(d2d_context, border_bitmap, mask_bitmap)
+            .context("could not recreate effects command lists")?;
+
+        // Brushes belong to the device they were created on, so they only need to be recreated
+        // along with the devices
+        if devices_recreated {
+            self.recreate_brushes()
+                .context("could not recreate brushes")?;
+        }
+
+        Ok(())
+    }
+
+    /// Recreates the brushes of all colors on the current device context, keeping their opacity.
+    fn recreate_brushes(&mut self) -> anyhow::Result<()> {
+        let d2d_context = self.render_resources.d2d_context()?.clone();
+        let window_rect: RECT = self.window_rect.into();
+
+        let colors = [&mut self.active_color, &mut self.inactive_color]
+            .into_iter()
+            .chain(self.trail_color.as_mut())
+            .chain(self.flash_color.as_mut())
+            .chain(
+                self.layers
+                    .iter_mut()
+                    .flat_map(|layer| [&mut layer.active_color, &mut layer.inactive_color]),
+            );
+
+        for color in colors {
+            let brush_properties = D2D1_BRUSH_PROPERTIES {
+                opacity: color.get_opacity().unwrap_or(0.0),
+                transform: Matrix3x2::identity(),
+            };
+            color.to_d2d1_brush(&d2d_context, &window_rect, &brush_properties)?;
+        }
+
+        Ok(())
+    }
+
+    /// Resolves the colors that use the Windows accent color again, after it changed.
+    fn refresh_accent_colors(&mut self) -> anyhow::Result<()> {
+        let uses_accent = [&self.active_color, &self.inactive_color]
+            .into_iter()
+            .chain(self.trail_color.as_ref())
+            .chain(self.flash_color.as_ref())
+            .chain(
+                self.layers
+                    .iter()
+                    .flat_map(|layer| [&layer.active_color, &layer.inactive_color]),
+            )
+            .any(|color| color.is_accent());
+        if !uses_accent {
+            return Ok(());
+        }
+
+        // Cached window rules hold colors resolved from the old accent color
+        AppManager::get().clear_rule_cache();
+
+        let config = ParsedConfig::try_from(AppManager::get().config().clone())?;
+        let window_rule = WindowsApi::get_window_rule(self.tracking_window);
+        let global = &config.global_rule;
+        let monitor = self.monitor_override.as_ref();
+
+        let active_color = monitor
+            .and_then(|monitor| monitor.active_color.as_ref())
+            .or(window_rule.active_color.as_ref())
+            .unwrap_or(&global.active_color);
+        let inactive_color = monitor
+            .and_then(|monitor| monitor.inactive_color.as_ref())
+            .or(window_rule.inactive_color.as_ref())
+            .unwrap_or(&global.inactive_color)
+            .resolve(active_color)?;
+        let trail_color = global.focus_trail.as_ref().map(|trail| &trail.color);
+        let flash_color = global.flash_color.as_ref();
+        let layers = window_rule.layers.as_ref().unwrap_or(&global.layers);
+
+        let d2d_context = self.render_resources.d2d_context()?.clone();
+        let window_rect: RECT = self.window_rect.into();
+
+        let colors = [
+            (&mut self.active_color, Some(active_color)),
+            (&mut self.inactive_color, Some(&inactive_color)),
+        ]
+        .into_iter()
+        .chain(self.trail_color.as_mut().map(|color| (color, trail_color)))
+        .chain(self.flash_color.as_mut().map(|color| (color, flash_color)))
+        .chain(
+            self.layers
+                .iter_mut()
+                .zip(layers.iter())
+                .flat_map(|(layer, new_layer)| {
+                    [
+                        (&mut layer.active_color, Some(&new_layer.active_color)),
+                        (&mut layer.inactive_color, Some(&new_layer.inactive_color)),
+                    ]
+                }),
+        );
+
+        for (color, new_color) in colors {
+            let Some(new_color) = new_color.filter(|_| color.is_accent()) else {
+                continue;
+            };
+
+            // Keep the opacity, which may be mid-animation
+            let brush_properties = D2D1_BRUSH_PROPERTIES {
+                opacity: color.get_opacity().unwrap_or(0.0),
+                transform: Matrix3x2::identity(),
+            };
+            *color = new_color.clone();
+            color.to_d2d1_brush(&d2d_context, &window_rect, &brush_properties)?;
+        }
+
+        self.render()?;
+
+        Ok(())
+    }
+
+    /// Applies the current config to this border in place, for config changes that don't require
+    /// recreating it (see `restyle_borders`). Keeps the border window and its render resources, so
+    /// the border doesn't flash like it does when it is recreated.
+    fn restyle(&mut self) -> anyhow::Result<()> {
+        let old_margin = self.placement_margin();
+        let old_padding = self.window_padding;
+
+        // load_from_config replaces the animation manager, so stop its timer first
+        self.animation_manager.kill_timer(self.border_window)?;
+
+        // Only solid colors are faded to their new color, gradients switch instantly
+        let solid_color = |color: &Color| match color {
+            Color::Solid(solid) => Some(solid.color),
+            Color::Gradient(_) => None,
+        };
+        let old_active = solid_color(&self.active_color);
+        let old_inactive = solid_color(&self.inactive_color);
+
+        let window_rule = WindowsApi::get_window_rule(self.tracking_window);
+        self.load_from_config(&window_rule)?;
+
+        self.transition_from_active = None;
+        self.transition_from_inactive = None;
+        self.transition_progress = 0.0;
+        if self.transition_duration > 0 && !AppManager::get().animations_paused() {
+            let changed = |old: Option<D2D1_COLOR_F>, new: &Color| {
+                old.filter(|old| solid_color(new).is_some_and(|new| new != *old))
+            };
+            self.transition_from_active = changed(old_active, &self.active_color);
+            self.transition_from_inactive = changed(old_inactive, &self.inactive_color);
+
+            if self.transition_from_active.is_some() || self.transition_from_inactive.is_some() {
+                self.transition_progress = 1.0;
+            }
+        }
+        self.update_keep_timer_alive();
+
+        if self.placement_margin() != old_margin || self.window_padding != old_padding {
+            self.render_resources.update(
+                self.current_monitor,
+                self.placement_margin(),
+                self.window_padding,
+            )?;
+        }
+
+        // The effect command lists and brushes were replaced along with the config
+        let d2d_context = self.render_resources.d2d_context()?.clone();
+        self.effect_manager.create_command_lists_if_enabled(
+            &d2d_context,
+            self.render_resources.border_bitmap()?,
+            self.render_resources.mask_bitmap()?,
+        )?;
+
+        let brush_properties = D2D1_BRUSH_PROPERTIES {
+            opacity: 0.0,
+            transform: Matrix3x2::identity(),
+        };
+        let window_rect: RECT = self.window_rect.into();
+        let colors = [&mut self.active_color, &mut self.inactive_color]
+            .into_iter()
+            .chain(self.trail_color.as_mut())
+            .chain(self.flash_color.as_mut())
+            .chain(
+                self.layers
+                    .iter_mut()
+                    .flat_map(|layer| [&mut layer.active_color, &mut layer.inactive_color]),
+            );
+        for color in colors {
+            color.to_d2d1_brush(&d2d_context, &window_rect, &brush_properties)?;
+        }
+        self.update_brush_opacities();
+        self.refresh_fade_progress();
+        self.apply_color_transition();
+
+        self.render_rect.radiusX = self.radius;
+        self.render_rect.radiusY = self.radius;
+
+        self.update_window_rect()?;
+
+        // Hidden borders are positioned and start their timer once they are shown again
+        if !self.pause {
+            self.update_position(None)?;
+            self.render()?;
+            self.animation_manager.set_timer(self.border_window)?;
+        }
+
+        Ok(())
+    }
+
     fn exit_border_thread(&mut self) {
         self.pause = true;
         self.animation_manager
@@ -973,6 +2404,7 @@ impl Border {
             .log_if_err();
         let mut borders_hashmap = window_borders();
         borders_hashmap.remove(&(self.tracking_window));
+        AppManager::get().forget_focus(self.tracking_window);
 
         drop(borders_hashmap);
         WindowsApi::post_quit_message(0);
@@ -999,15 +2431,29 @@ impl Border {
                     return LRESULT(0);
                 }
 
+                let was_hidden = self.is_hidden();
+                if self.update_hidden() {
+                    return LRESULT(0);
+                }
+
                 let old_rect = self.window_rect;
-                self.update_window_rect().log_if_err();
+                match self.update_window_rect() {
+                    Ok(true) => {}
+                    // The window is minimized, so there is nothing to move or render
+                    Ok(false) => return LRESULT(0),
+                    Err(err) => error!("{err:#}"),
+                }
 
-                if !self.window_rect.is_visible() {
-                    self.window_rect = old_rect;
-                    return LRESULT(0);
+                // Nothing was rendered while the border was hidden
+                if !self.window_rect.is_same_size_as(&old_rect) || was_hidden {
+                    should_render |= true;
                 }
 
-                if !self.window_rect.is_same_size_as(&old_rect) {
+                // The clip rect is relative to the window, so it changes whenever the window moves
+                if self.clip_to_monitor
+                    && (self.window_rect.left != old_rect.left
+                        || self.window_rect.top != old_rect.top)
+                {
                     should_render |= true;
                 }
 
@@ -1016,61 +2462,178 @@ impl Border {
                 self.update_position(update_pos_flags).log_if_err();
 
                 let new_monitor = WindowsApi::monitor_from_window(self.tracking_window);
+                let monitor_changed = new_monitor != self.current_monitor;
 
-                if new_monitor != self.current_monitor {
+                if monitor_changed {
                     self.current_monitor = new_monitor;
-                    self.render_resources
-                        .update(self.current_monitor, self.width, self.window_padding)
-                        .context("could not update render resources")
-                        .log_if_err();
 
-                    if let (Ok(d2d_context), Ok(border_bitmap), Ok(mask_bitmap)) = (
-                        self.render_resources.d2d_context(),
-                        self.render_resources.border_bitmap(),
-                        self.render_resources.mask_bitmap(),
-                    ) {
-                        self.effect_manager
-                            .create_command_lists_if_enabled(
-                                d2d_context,
-                                border_bitmap,
-                                mask_bitmap,
-                            )
-                            .log_if_err();
-                    } else {
-                        error!("could not get resources to create effects command list");
-                    }
-
-                    let new_dpi = match WindowsApi::get_dpi_for_window(self.tracking_window) {
-                        Ok(dpi) => dpi as f32,
-                        Err(err) => {
-                            error!("could not get dpi for window: {err}");
-                            self.exit_border_thread();
-                            return LRESULT(0);
-                        }
-                    };
+                    // Apply the new monitor's override first, since its width sizes the resources
+                    self.update_monitor_override()
+                        .context("could not update monitor override")
+                        .log_if_err();
+                    self.update_monitor_fps()
+                        .context("could not update animation fps")
+                        .log_if_err();
+                }
 
-                    if new_dpi != self.current_dpi {
-                        self.current_dpi = new_dpi;
-                        self.update_width_radius();
+                // The DPI is checked even if the monitor stayed the same, because apps resize
+                // themselves when the scale of their monitor is changed
+                let dpi_changed = match self.update_dpi() {
+                    Ok(dpi_changed) => dpi_changed,
+                    Err(err) => {
+                        error!("{err:#}");
+                        self.exit_border_thread();
+                        return LRESULT(0);
                     }
+                };
 
+                if monitor_changed || dpi_changed {
+                    self.update_monitor_resources(dpi_changed);
                     should_render |= true;
                 }
 
                 if should_render {
+                    self.schedule_location_render();
+                }
+            }
+            WM_TIMER if wparam.0 == LOCATION_RENDER_TIMER_ID => {
+                if self.location_render_pending {
+                    // render() kills the timer, so this fires only once per scheduled render
                     self.render().log_if_err();
                 }
             }
+            // WM_POWERBROADCAST (system resume / display turned back on)
+            WM_APP_RECREATE => {
+                // Recreate the render target up front instead of waiting for the next EndDraw()
+                // to fail, and reposition in case the desktop was rearranged while asleep.
+                self.current_monitor = WindowsApi::monitor_from_window(self.tracking_window);
+
+                if let Err(err) = self.recreate_render_resources() {
+                    error!("{err:#}; exiting thread");
+                    self.exit_border_thread();
+                    return LRESULT(0);
+                }
+
+                if self.pause {
+                    return LRESULT(0);
+                }
+
+                self.update_window_rect().log_if_err();
+                self.update_position(None).log_if_err();
+                self.render().log_if_err();
+            }
+            // force_window_state()
+            WM_APP_FORCE_STATE => {
+                self.forced_state = ForcedState::from_wparam(wparam);
+
+                self.update_color(None).log_if_err();
+                if !self.pause {
+                    self.render().log_if_err();
+                }
+            }
+            // border_statuses()
+            WM_APP_STATUS => {
+                let sender = unsafe { Box::from_raw(lparam.0 as *mut Sender<BorderStatus>) };
+                // The receiver is gone if we answered too late
+                let _ = sender.send(self.status());
+            }
+            // flash_window()
+            WM_APP_FLASH => {
+                // The flash fades out on the animation timer, which doesn't run while paused
+                if self.flash_color.is_none() || AppManager::get().animations_paused() {
+                    return LRESULT(0);
+                }
+
+                self.flash_progress = 1.0;
+
+                if !self.pause {
+                    self.animation_manager
+                        .set_timer(self.border_window)
+                        .log_if_err();
+                }
+            }
+            WM_APP_ANIMATIONS => {
+                if AppManager::get().animations_paused() {
+                    self.animation_manager
+                        .kill_timer(self.border_window)
+                        .log_if_err();
+
+                    // Finish the fades that run on the timer, so they don't stay half done
+                    self.trail_progress = 0.0;
+                    self.flash_progress = 0.0;
+                    self.transition_progress = 0.0;
+                    self.apply_color_transition();
+                    self.transition_from_active = None;
+                    self.transition_from_inactive = None;
+                    self.animation_manager.flags.should_fade = false;
+                    self.update_brush_opacities();
+                    self.refresh_fade_progress();
+
+                    if !self.pause && !self.is_hidden() {
+                        self.render().log_if_err();
+                    }
+                } else {
+                    // Don't count the time spent paused as animation time
+                    self.animation_manager.set_last_animation_time(None);
+
+                    // Hidden borders start their timer once they are shown again
+                    if !self.pause {
+                        self.animation_manager
+                            .set_timer(self.border_window)
+                            .log_if_err();
+                    }
+                }
+            }
+            // restyle_borders()
+            WM_APP_RESTYLE => {
+                self.restyle()
+                    .context("could not restyle border")
+                    .log_if_err();
+            }
             // EVENT_OBJECT_REORDER
             WM_APP_REORDER => {
                 // For apps like firefox, when you hover over a tab, a popup window spawns that
                 // changes the z-order and causes the border to sit under the tracking window. To
                 // remedy that, we just re-update the position/z-order when windows are reordered.
+                // With always_on_top, this also moves the border back above newer topmost windows.
                 self.update_position(None).log_if_err();
             }
             // EVENT_SYSTEM_FOREGROUND
             WM_APP_FOREGROUND => {
+                // Always-active and forced borders don't react to focus changes, so there's
+                // nothing to fade
+                if self.always_active || self.forced_state.is_some() {
+                    self.update_position(None).log_if_err();
+                    return LRESULT(0);
+                }
+
+                let was_active = self.is_window_active;
+
                 self.update_color(None).log_if_err();
+
+                // The trail fades out on the animation timer, which doesn't run while paused
+                if was_active
+                    && !self.is_window_active
+                    && self.trail_color.is_some()
+                    && !AppManager::get().animations_paused()
+                {
+                    self.trail_progress = 1.0;
+                } else if self.is_window_active {
+                    self.trail_progress = 0.0;
+                }
+
+                // Inactive borders may have had their timer stopped. Once they become inactive, the
+                // timer keeps running until they finished fading out (see WM_APP_TIMER).
+                if self.is_window_active && !self.pause {
+                    self.animation_manager
+                        .set_timer(self.border_window)
+                        .log_if_err();
+                }
+
+                if self.update_hidden() {
+                    return LRESULT(0);
+                }
+
                 self.update_position(None).log_if_err();
                 self.render().log_if_err();
             }
@@ -1078,16 +2641,15 @@ impl Border {
             WM_APP_SHOWUNCLOAKED => {
                 // With GlazeWM, if I switch to another workspace while a window is minimized and
                 // switch back, then we will receive this message even though the window is not yet
-                // visible. And, the window rect will be all weird. So, we apply the following fix.
-                let old_rect = self.window_rect;
-                self.update_window_rect().log_if_err();
-
-                if !self.window_rect.is_visible() {
-                    self.window_rect = old_rect;
-                    return LRESULT(0);
+                // visible. And, the window rect will be the collapsed minimized one, so we wait
+                // until the window is actually restored.
+                match self.update_window_rect() {
+                    Ok(true) => {}
+                    Ok(false) => return LRESULT(0),
+                    Err(err) => error!("{err:#}"),
                 }
 
-                if WindowsApi::has_native_border(self.tracking_window) {
+                if WindowsApi::has_native_border(self.tracking_window) && !self.update_hidden() {
                     self.update_position(Some(SWP_SHOWWINDOW)).log_if_err();
                     self.render().log_if_err();
                 }
@@ -1123,7 +2685,7 @@ impl Border {
             // When a window is about to be unminimized, hide the border and let the thread sleep
             // to wait for the window animation to finish, then show the border.
             WM_APP_MINIMIZEEND => {
-                thread::sleep(time::Duration::from_millis(self.unminimize_delay as u64));
+                self.wait_for_unminimize();
 
                 self.animation_manager.set_last_animation_time(None);
 
@@ -1141,7 +2703,7 @@ impl Border {
                 self.pause = false;
             }
             WM_APP_TIMER => {
-                if self.pause {
+                if self.pause || self.is_hidden() || AppManager::get().animations_paused() {
                     return LRESULT(0);
                 }
 
@@ -1155,16 +2717,32 @@ impl Border {
 
                 let mut animations_updated = false;
 
-                let current_animations = self.current_animations();
+                // Owned, so the animation progress below can be updated while iterating
+                let current_animations = self.current_animations().clone();
 
-                if current_animations.clone().is_empty() {
+                if current_animations.is_empty() {
                     self.active_color.set_transform(&Matrix3x2::identity());
                     self.inactive_color.set_transform(&Matrix3x2::identity());
                     animations_updated = false;
                 } else {
-                    for animation in current_animations.clone() {
+                    self.animation_manager.progress.state_elapsed +=
+                        animation_elapsed.as_millis_f32();
+
+                    for animation in current_animations {
+                        if !animation.is_ready(self) {
+                            continue;
+                        }
+
                         match animation.kind {
                             AnimationKind::Spiral | AnimationKind::ReverseSpiral => {
+                                if !self.animation_manager.flags.spiral_finished {
+                                    animation.play(self, &animation_elapsed);
+                                    animations_updated = true;
+                                }
+                            }
+                            AnimationKind::HueRotate
+                            | AnimationKind::Pulse
+                            | AnimationKind::DirectionSweep => {
                                 animation.play(self, &animation_elapsed);
                                 animations_updated = true;
                             }
@@ -1178,17 +2756,92 @@ impl Border {
                     }
                 }
 
+                if self.hover_only && self.update_hover_progress(&animation_elapsed) {
+                    animations_updated = true;
+                }
+
+                if self.update_trail_progress(&animation_elapsed) {
+                    animations_updated = true;
+                }
+
+                if self.update_flash_progress(&animation_elapsed) {
+                    animations_updated = true;
+                }
+
+                if self.update_transition_progress(&animation_elapsed) {
+                    animations_updated = true;
+                }
+
                 let interval = 1.0 / self.animation_manager.fps();
                 let diff = render_elapsed.as_secs_f32() - interval;
                 if animations_updated && (diff.abs() <= 0.001 || diff >= 0.0) {
                     self.render().log_if_err();
                 }
+
+                // Stop animating inactive borders once their focus transitions finished
+                if self.animation_manager.is_idle()
+                    && !self.animation_manager.flags.should_fade
+                    && self.trail_progress <= 0.0
+                    && self.flash_progress <= 0.0
+                    && self.transition_progress <= 0.0
+                {
+                    self.animation_manager
+                        .kill_timer(self.border_window)
+                        .log_if_err();
+                }
+            }
+            // The border window is usually on the tracking window's monitor, so it's notified when
+            // that monitor's scale changes, even if the tracking window doesn't move
+            WM_DPICHANGED | WM_DISPLAYCHANGE => {
+                match self.update_dpi() {
+                    Ok(true) => self.update_monitor_resources(true),
+                    Ok(false) => return LRESULT(0),
+                    Err(err) => {
+                        error!("{err:#}");
+                        return LRESULT(0);
+                    }
+                }
+
+                if self.pause {
+                    return LRESULT(0);
+                }
+
+                self.update_window_rect().log_if_err();
+                self.update_position(None).log_if_err();
+                self.render().log_if_err();
+            }
+            WM_DWMCOLORIZATIONCOLORCHANGED => {
+                self.refresh_accent_colors()
+                    .context("could not refresh accent colors")
+                    .log_if_err();
+            }
+            // Only received with debug_clickable. The border window covers the tracking window
+            // too, so let clicks inside the border's inner edge through to it.
+            WM_NCHITTEST => {
+                let x = (lparam.0 & 0xFFFF) as i16 as i32;
+                let y = ((lparam.0 >> 16) & 0xFFFF) as i16 as i32;
+                if self.is_inside_inner_edge(x, y) {
+                    return LRESULT(HTTRANSPARENT as isize);
+                }
+
+                return WindowsApi::def_window_proc_w(window.as_int(), message, wparam.0, lparam.0);
+            }
+            // Only received with debug_clickable, otherwise clicks pass through the border
+            WM_LBUTTONDOWN => {
+                info!(
+                    "Border: Clicked (Process: {}, Title: {:?}, Tracking Window ID: {:?}, Border Window ID: {:?})",
+                    self.process_name,
+                    WindowsApi::get_window_title(self.tracking_window).unwrap_or_default(),
+                    self.tracking_window.as_hwnd(),
+                    self.border_window.as_hwnd()
+                );
             }
             WM_PAINT => {
                 let _ = WindowsApi::validate_rect(Some(window.as_int()), None);
             }
             WM_NCDESTROY => {
                 WindowsApi::set_window_long_ptr_w(window.as_int(), GWLP_USERDATA, 0);
+                AppManager::get().remove_cached_window_rule(self.tracking_window);
                 self.exit_border_thread();
             }
             // Ignore these window position messages
@@ -1221,3 +2874,218 @@ impl Border {
         }
     }
 }
+
+/// Returns whether the window is on one of the given monitors, see `disabled_monitors` in the
+/// config.
+fn is_on_disabled_monitor(hwnd: isize, disabled_monitors: &[String]) -> bool {
+    if disabled_monitors.is_empty() {
+        return false;
+    }
+
+    let monitor = WindowsApi::monitor_from_window(hwnd);
+    WindowsApi::get_monitor_device_name(monitor).is_ok_and(|device_name| {
+        disabled_monitors
+            .iter()
+            .any(|key| monitor_key_matches(key, &device_name))
+    })
+}
+
+/// Creates the Direct2D stroke style for a line pattern, or returns `None` for patterns drawn
+/// without one. `width` is the stroke width in pixels, since dash lengths are relative to it.
+fn create_stroke_style(
+    stroke_style: &StrokeStyle,
+    width: f32,
+    scale: f32,
+) -> anyhow::Result<Option<ID2D1StrokeStyle1>> {
+    let width = width.max(1.0);
+    let relative = |length: f32| length * scale / width;
+
+    let (dash_style, dash_cap, dashes) = match stroke_style.kind {
+        StrokeKind::Solid | StrokeKind::Double => return Ok(None),
+        StrokeKind::Dashed => {
+            let dashes = match (stroke_style.dash, stroke_style.gap) {
+                (None, None) => None,
+                (dash, gap) => Some([dash.map_or(2.0, relative), gap.map_or(2.0, relative)]),
+            };
+            (D2D1_DASH_STYLE_DASH, D2D1_CAP_STYLE_FLAT, dashes)
+        }
+        // Dots are zero-length dashes with round caps, so each cap adds half a width to the
+        // dot and the spacing between dots has to account for a full width
+        StrokeKind::Dotted => {
+            let dashes = stroke_style.gap.map(|gap| [0.0, relative(gap) + 1.0]);
+            (D2D1_DASH_STYLE_DOT, D2D1_CAP_STYLE_ROUND, dashes)
+        }
+    };
+
+    let properties = D2D1_STROKE_STYLE_PROPERTIES1 {
+        startCap: D2D1_CAP_STYLE_FLAT,
+        endCap: D2D1_CAP_STYLE_FLAT,
+        dashCap: dash_cap,
+        lineJoin: D2D1_LINE_JOIN_MITER,
+        miterLimit: 10.0,
+        dashStyle: match dashes {
+            Some(_) => D2D1_DASH_STYLE_CUSTOM,
+            None => dash_style,
+        },
+        dashOffset: 0.0,
+        transformType: D2D1_STROKE_TRANSFORM_TYPE_NORMAL,
+    };
+
+    let stroke_style = unsafe {
+        AppManager::get()
+            .d2d_factory()
+            .CreateStrokeStyle(&properties, dashes.as_ref().map(|d| d.as_slice()))
+    }
+    .context("could not create stroke style")?;
+
+    Ok(Some(stroke_style))
+}
+
+/// Creates the outline of a rectangle whose corners each have their own radius. Radii are clamped
+/// to half the rectangle's size so adjacent corners never overlap.
+fn rounded_rect_geometry(
+    rect: &D2D_RECT_F,
+    radii: &CornerRadii,
+) -> windows::core::Result<ID2D1PathGeometry1> {
+    unsafe {
+        let geometry = AppManager::get().d2d_factory().CreatePathGeometry()?;
+        let sink = geometry.Open()?;
+        add_rounded_rect_figure(&sink, rect, radii);
+        sink.Close()?;
+
+        Ok(geometry)
+    }
+}
+
+/// Creates the area between two nested rounded rectangles, like `rounded_rect_geometry`.
+fn rounded_band_geometry(
+    outer: &D2D_RECT_F,
+    outer_radii: &CornerRadii,
+    inner: &D2D_RECT_F,
+    inner_radii: &CornerRadii,
+) -> windows::core::Result<ID2D1PathGeometry1> {
+    unsafe {
+        let geometry = AppManager::get().d2d_factory().CreatePathGeometry()?;
+        let sink = geometry.Open()?;
+        // Path geometries are filled in alternate mode, so the inner figure is left empty
+        add_rounded_rect_figure(&sink, outer, outer_radii);
+        add_rounded_rect_figure(&sink, inner, inner_radii);
+        sink.Close()?;
+
+        Ok(geometry)
+    }
+}
+
+fn add_rounded_rect_figure(sink: &ID2D1GeometrySink, rect: &D2D_RECT_F, radii: &CornerRadii) {
+    let max_radius = ((rect.right - rect.left).min(rect.bottom - rect.top) / 2.0).max(0.0);
+    let clamp = |radius: f32| radius.min(max_radius);
+    let (top_left, top_right) = (clamp(radii.top_left), clamp(radii.top_right));
+    let (bottom_right, bottom_left) = (clamp(radii.bottom_right), clamp(radii.bottom_left));
+
+    let point = |x: f32, y: f32| D2D_POINT_2F { x, y };
+    let arc = |end: D2D_POINT_2F, radius: f32| D2D1_ARC_SEGMENT {
+        point: end,
+        size: D2D_SIZE_F {
+            width: radius,
+            height: radius,
+        },
+        rotationAngle: 0.0,
+        sweepDirection: D2D1_SWEEP_DIRECTION_CLOCKWISE,
+        arcSize: D2D1_ARC_SIZE_SMALL,
+    };
+
+    unsafe {
+        // Go clockwise from the end of the top left corner, drawing each edge and then the arc of
+        // the corner following it
+        sink.BeginFigure(
+            point(rect.left + top_left, rect.top),
+            D2D1_FIGURE_BEGIN_FILLED,
+        );
+        sink.AddLine(point(rect.right - top_right, rect.top));
+        sink.AddArc(&arc(point(rect.right, rect.top + top_right), top_right));
+        sink.AddLine(point(rect.right, rect.bottom - bottom_right));
+        sink.AddArc(&arc(
+            point(rect.right - bottom_right, rect.bottom),
+            bottom_right,
+        ));
+        sink.AddLine(point(rect.left + bottom_left, rect.bottom));
+        sink.AddArc(&arc(
+            point(rect.left, rect.bottom - bottom_left),
+            bottom_left,
+        ));
+        sink.AddLine(point(rect.left, rect.top + top_left));
+        sink.AddArc(&arc(point(rect.left + top_left, rect.top), top_left));
+        sink.EndFigure(D2D1_FIGURE_END_CLOSED);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_squares_corners_between_edges_of_different_widths() {
+        let border = Border {
+            width: 4,
+            radius: 8.0,
+            edge_widths: Some(BorderWidth {
+                top: 4,
+                right: 4,
+                bottom: 2,
+                left: 4,
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            border.effective_corner_radii(),
+            Some(CornerRadii {
+                top_left: 8.0,
+                top_right: 8.0,
+                bottom_right: 0.0,
+                bottom_left: 0.0,
+            })
+        );
+
+        // The outer edge is half the border width out, and the inner edge a full edge width in
+        let (outer, inner) = border.edge_corner_radii(border.edge_widths.as_ref().unwrap());
+        assert_eq!((outer.top_left, inner.top_left), (10.0, 6.0));
+        assert_eq!((outer.bottom_right, inner.bottom_right), (0.0, 0.0));
+    }
+
+    #[test]
+    fn clip_rect_splits_window_spanning_two_monitors() {
+        let window_rect = RECT {
+            left: 1500,
+            top: 100,
+            right: 2500,
+            bottom: 800,
+        };
+        let left_monitor = RECT {
+            left: 0,
+            top: 0,
+            right: 1920,
+            bottom: 1080,
+        };
+        let right_monitor = RECT {
+            left: 1920,
+            top: 0,
+            right: 3840,
+            bottom: 1080,
+        };
+
+        // On the left monitor, everything right of x = 420 within the border window is clipped
+        let clip = Border::clip_rect_relative_to(&left_monitor, &window_rect);
+        assert_eq!(
+            (clip.left, clip.top, clip.right, clip.bottom),
+            (-1500.0, -100.0, 420.0, 980.0)
+        );
+
+        // On the right monitor, everything left of it is clipped instead
+        let clip = Border::clip_rect_relative_to(&right_monitor, &window_rect);
+        assert_eq!(
+            (clip.left, clip.top, clip.right, clip.bottom),
+            (420.0, -100.0, 2340.0, 980.0)
+        );
+    }
+}
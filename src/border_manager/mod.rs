@@ -2,21 +2,78 @@ mod border;
 
 use crate::app_manager::AppManager;
 use crate::error::LogIfErr;
+use crate::error::TackyError;
+use crate::windows_api::PointerConversion;
+use crate::windows_api::WM_APP_ANIMATIONS;
+use crate::windows_api::WM_APP_FLASH;
+use crate::windows_api::WM_APP_FORCE_STATE;
+use crate::windows_api::WM_APP_RESTYLE;
+use crate::windows_api::WM_APP_STATUS;
 use crate::windows_api::WindowsApi;
 use anyhow::Context;
+use anyhow::anyhow;
 pub use border::Border;
 #[cfg(feature = "fast-hash")]
 use fx_hash::FxHashMap as HashMap;
+use serde::Serialize;
 #[cfg(not(feature = "fast-hash"))]
 use std::collections::HashMap;
+use std::mem;
+use std::sync::Mutex;
 use std::sync::MutexGuard;
+use std::sync::mpsc;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
 use windows::Win32::Foundation::GetLastError;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::WPARAM;
 use windows::Win32::UI::WindowsAndMessaging::IDC_ARROW;
 use windows::Win32::UI::WindowsAndMessaging::LoadCursorW;
 use windows::Win32::UI::WindowsAndMessaging::RegisterClassW;
+use windows::Win32::UI::WindowsAndMessaging::UnregisterClassW;
 use windows::Win32::UI::WindowsAndMessaging::WNDCLASSW;
 use windows::core::w;
 
+/// How long `wait_for_border_threads` waits for the border threads to exit.
+const BORDER_THREADS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Threads that own a border window, see `spawn_border_thread`.
+static BORDER_THREADS: Mutex<Vec<JoinHandle<()>>> = Mutex::new(Vec::new());
+
+/// Spawns a thread that owns a border window, so `wait_for_border_threads` can wait for it.
+fn spawn_border_thread<F>(f: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    let mut threads = BORDER_THREADS.lock().unwrap_or_else(|e| e.into_inner());
+    threads.retain(|thread| !thread.is_finished());
+    threads.push(thread::spawn(f));
+}
+
+/// Waits until every border thread has exited. A border window is only destroyed once the thread
+/// that owns it exits, so this must be called after `destroy_all_borders` before the border class
+/// can be unregistered.
+pub fn wait_for_border_threads() -> anyhow::Result<()> {
+    let threads = mem::take(&mut *BORDER_THREADS.lock().unwrap_or_else(|e| e.into_inner()));
+    let deadline = Instant::now() + BORDER_THREADS_TIMEOUT;
+
+    for border_thread in threads {
+        while !border_thread.is_finished() {
+            if Instant::now() >= deadline {
+                return Err(anyhow!("border threads did not exit in time"));
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        // The main function returned, so this only waits for the thread to terminate
+        let _ = border_thread.join();
+    }
+
+    Ok(())
+}
+
 pub fn window_borders() -> MutexGuard<'static, HashMap<isize, Border>> {
     AppManager::get().borders()
 }
@@ -33,6 +90,138 @@ pub fn set_active_window(handle: isize) {
     AppManager::get().set_active_window(handle);
 }
 
+/// How long `border_statuses` waits for the borders to answer.
+const STATUS_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// A snapshot of a border's state, as reported by the `status` IPC command.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BorderStatus {
+    /// Handle of the window the border is drawn around.
+    pub tracking_window: isize,
+    pub process_name: String,
+    /// Whether the border uses its active color.
+    pub active: bool,
+    /// Whether the border is shown, as opposed to hidden with its minimized or cloaked window.
+    pub visible: bool,
+    /// Device name of the monitor the window is on (e.g., "\\.\DISPLAY1").
+    pub monitor: String,
+    /// Width of the border in pixels.
+    pub width: i32,
+    /// Whether the animation timer is running.
+    pub animating: bool,
+}
+
+/// Asks every border for a snapshot of its state. Borders that don't answer in time are skipped.
+pub fn border_statuses() -> Vec<BorderStatus> {
+    // Borders may need the borders lock while answering, so don't hold it while asking
+    let border_windows: Vec<isize> = window_borders()
+        .values()
+        .map(|border| border.border_window)
+        .collect();
+
+    // Each border gets its own sender and answers on its own thread. A border that answers after
+    // we stopped waiting just sends into a closed channel.
+    let (sender, receiver) = mpsc::channel();
+    let mut pending = 0;
+
+    for border_window in border_windows {
+        let sender = Box::into_raw(Box::new(sender.clone()));
+
+        match WindowsApi::post_message_w(
+            Some(border_window.as_hwnd()),
+            WM_APP_STATUS,
+            WPARAM(0),
+            LPARAM(sender as isize),
+        ) {
+            Ok(()) => pending += 1,
+            Err(err) => {
+                // The message was never queued, so the border won't free the sender
+                drop(unsafe { Box::from_raw(sender) });
+                debug!("could not query border status: {err}");
+            }
+        }
+    }
+
+    let deadline = Instant::now() + STATUS_TIMEOUT;
+    let mut statuses = Vec::with_capacity(pending);
+    while statuses.len() < pending {
+        match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(status) => statuses.push(status),
+            Err(_) => break,
+        }
+    }
+
+    statuses
+}
+
+/// A state that overrides a border's focus-driven active/inactive state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForcedState {
+    Active,
+    Inactive,
+}
+
+impl ForcedState {
+    fn to_wparam(state: Option<Self>) -> WPARAM {
+        match state {
+            None => WPARAM(0),
+            Some(ForcedState::Active) => WPARAM(1),
+            Some(ForcedState::Inactive) => WPARAM(2),
+        }
+    }
+
+    pub(crate) fn from_wparam(wparam: WPARAM) -> Option<Self> {
+        match wparam.0 {
+            1 => Some(ForcedState::Active),
+            2 => Some(ForcedState::Inactive),
+            _ => None,
+        }
+    }
+
+    /// Returns whether a border is drawn as active, given its forced state, its rule's
+    /// `always_active` and whether its window is the foreground window.
+    pub(crate) fn is_window_active(
+        state: Option<Self>,
+        always_active: bool,
+        is_foreground: bool,
+    ) -> bool {
+        match state {
+            Some(state) => state == ForcedState::Active,
+            None => always_active || is_foreground,
+        }
+    }
+}
+
+/// Pins the border of `hwnd` to the given state, or clears the override with `None`.
+///
+/// While a state is forced, focus changes no longer affect the border's colors; they take effect
+/// again once the override is cleared. This takes precedence over a rule's `always_active`.
+pub fn force_window_state(hwnd: isize, state: Option<ForcedState>) -> anyhow::Result<()> {
+    let border = window_border(hwnd).ok_or(TackyError::NoBorder(hwnd))?;
+
+    WindowsApi::post_message_w(
+        Some(border.border_window.as_hwnd()),
+        WM_APP_FORCE_STATE,
+        ForcedState::to_wparam(state),
+        LPARAM(0),
+    )
+    .context("could not post WM_APP_FORCE_STATE message")
+}
+
+/// Flashes the border of `hwnd` in the configured `flash_color`, which then fades back to the
+/// border's color.
+pub fn flash_window(hwnd: isize) -> anyhow::Result<()> {
+    let border = window_border(hwnd).ok_or(TackyError::NoBorder(hwnd))?;
+
+    WindowsApi::post_message_w(
+        Some(border.border_window.as_hwnd()),
+        WM_APP_FLASH,
+        WPARAM(0),
+        LPARAM(0),
+    )
+    .context("could not post WM_APP_FLASH message")
+}
+
 pub fn register_border_class() -> anyhow::Result<()> {
     unsafe {
         let wc = WNDCLASSW {
@@ -53,6 +242,14 @@ pub fn register_border_class() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Unregisters the border window class. All border windows must have been destroyed first.
+pub fn unregister_border_class() -> anyhow::Result<()> {
+    unsafe {
+        UnregisterClassW(w!("border"), Some(WindowsApi::module_handle_w()?.into()))
+            .context("could not unregister window border class")
+    }
+}
+
 pub fn destroy_all_borders() -> anyhow::Result<()> {
     let mut borders = window_borders();
     info!("[destroy_all_borders] Borders: Destroying");
@@ -69,10 +266,123 @@ pub fn destroy_all_borders() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Destroys all borders, or recreates them if they were toggled off before.
+pub fn toggle_borders() {
+    let app_manager = AppManager::get();
+    let enabled = !app_manager.borders_enabled();
+    app_manager.set_borders_enabled(enabled);
+
+    if enabled {
+        info!("[toggle_borders] Borders: Enabled");
+        WindowsApi::process_window_handles(&Border::create).log_if_err();
+    } else {
+        info!("[toggle_borders] Borders: Disabled");
+        destroy_all_borders().log_if_err();
+    }
+}
+
+/// Pauses the animations of all borders, or resumes them if they were paused before.
+pub fn toggle_animations() {
+    let app_manager = AppManager::get();
+    let paused = !app_manager.animations_paused();
+    app_manager.set_animations_paused(paused);
+    info!(
+        "[toggle_animations] Animations: {}",
+        if paused { "Paused" } else { "Resumed" }
+    );
+
+    for border in window_borders().values() {
+        WindowsApi::post_message_w(
+            Some(border.border_window.as_hwnd()),
+            WM_APP_ANIMATIONS,
+            WPARAM(0),
+            LPARAM(0),
+        )
+        .context("could not post WM_APP_ANIMATIONS message")
+        .log_if_err();
+    }
+}
+
+/// Applies the current config to the existing borders in place, instead of recreating them like
+/// `reload_borders`. Only suitable if the config changed in ways borders can apply on their own,
+/// see `UserConfig::differs_only_in_style`.
+pub fn restyle_borders() {
+    AppManager::get().clear_rule_cache();
+
+    for border in window_borders().values() {
+        WindowsApi::post_message_w(
+            Some(border.border_window.as_hwnd()),
+            WM_APP_RESTYLE,
+            WPARAM(0),
+            LPARAM(0),
+        )
+        .context("could not post WM_APP_RESTYLE message")
+        .log_if_err();
+    }
+
+    info!("[restyle_borders] Borders: Updated in place");
+}
+
+/// Toggles the border of the foreground window. The window keeps its override, even across
+/// reloads, until it is destroyed.
+pub fn toggle_foreground_border() {
+    let hwnd = WindowsApi::get_foreground_window();
+    if hwnd == 0 {
+        return;
+    }
+
+    let enabled = window_border(hwnd).is_none();
+    AppManager::get().set_window_override(hwnd, Some(enabled));
+    info!("[toggle_foreground_border] Border: {enabled} (Tracking Window ID: {hwnd:?})");
+
+    // Disabled windows have no border at all, so destroy it rather than hiding it; a hidden
+    // border would be shown again by the next restore or uncloak event
+    match window_border(hwnd) {
+        Some(border) if !enabled => border.destroy(),
+        _ => Border::show(hwnd),
+    }
+}
+
 pub fn reload_borders() {
+    // Rules may have changed, so resolve them again for every window. Refresh rates are queried
+    // again too, since reloading is the only way to pick up changed display settings.
+    AppManager::get().clear_rule_cache();
+    AppManager::get().clear_refresh_rates();
+
     if destroy_all_borders().is_ok() {
         info!("[reload_borders] Borders: Destroyed successfully");
 
         WindowsApi::process_window_handles(&Border::create).log_if_err();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn always_active_border_stays_active_on_blur() {
+        assert!(ForcedState::is_window_active(None, true, true));
+        assert!(ForcedState::is_window_active(None, true, false));
+    }
+
+    #[test]
+    fn border_follows_focus_without_always_active() {
+        assert!(ForcedState::is_window_active(None, false, true));
+        assert!(!ForcedState::is_window_active(None, false, false));
+    }
+
+    #[test]
+    fn forced_state_takes_precedence_over_always_active() {
+        assert!(!ForcedState::is_window_active(
+            Some(ForcedState::Inactive),
+            true,
+            true
+        ));
+        assert!(ForcedState::is_window_active(
+            Some(ForcedState::Active),
+            false,
+            false
+        ));
+    }
+}
@@ -0,0 +1,92 @@
+//! Command-line arguments that run a one-off task instead of starting the application.
+//!
+//! Supported arguments:
+//! - `--check`: Validates the config file and exits with a non-zero code if it is invalid.
+//! - `--dump-schema [path]`: Writes the JSON schema of the config file to `path`, or to stdout.
+
+use crate::user_config::UserConfig;
+use crate::windows_api::WindowsApi;
+#[cfg(feature = "json")]
+use anyhow::Context;
+#[cfg(feature = "json")]
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliCommand {
+    Check,
+    #[cfg(feature = "json")]
+    DumpSchema(Option<PathBuf>),
+}
+
+impl CliCommand {
+    /// Returns the first command found in `args`, ignoring any argument that isn't a command.
+    pub fn from_args(args: impl Iterator<Item = String>) -> Option<Self> {
+        let mut args = args.peekable();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--check" | "validate" => return Some(CliCommand::Check),
+                #[cfg(feature = "json")]
+                "--dump-schema" => {
+                    let path = args.next_if(|next| !next.starts_with("--"));
+                    return Some(CliCommand::DumpSchema(path.map(PathBuf::from)));
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    /// Runs the command and returns the process exit code.
+    pub fn run(&self) -> i32 {
+        // Release builds have no console of their own; failing to attach just means that nobody
+        // is there to read the output, so the exit code is all that's left
+        let _ = WindowsApi::attach_parent_console();
+
+        match self {
+            CliCommand::Check => match UserConfig::check() {
+                Ok(config_file) => {
+                    println!("config is valid: {}", config_file.display());
+                    0
+                }
+                Err(err) => {
+                    println!("error: {err:#}");
+                    1
+                }
+            },
+            #[cfg(feature = "json")]
+            CliCommand::DumpSchema(path) => match dump_schema(path.as_ref()) {
+                Ok(()) => 0,
+                Err(err) => {
+                    println!("error: {err:#}");
+                    1
+                }
+            },
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+fn dump_schema(path: Option<&PathBuf>) -> anyhow::Result<()> {
+    let schema = schema_jsonrs::schema_for!(UserConfig);
+    let schema =
+        serde_jsonc2::to_string_pretty(&schema).context("could not serialize config schema")?;
+
+    match path {
+        Some(path) => std::fs::write(path, schema)
+            .with_context(|| format!("could not write schema to {}", path.display())),
+        None => {
+            println!("{schema}");
+            Ok(())
+        }
+    }
+}
+
+/// Runs the command given on the command line, if any, and returns its exit code.
+///
+/// Must be called before the logger is initialized, as that would truncate the log file of an
+/// already running instance.
+pub fn run_cli_command() -> Option<i32> {
+    CliCommand::from_args(std::env::args().skip(1)).map(|command| command.run())
+}
@@ -0,0 +1,466 @@
+//! This module implements color functions that are evaluated before a color string is handed to
+//! `colorparser_css`. Each function call is replaced with the equivalent `#RRGGBBAA` hex color, so
+//! they can be used anywhere a color is accepted, including inside gradients.
+
+use anyhow::anyhow;
+use regex::Regex;
+use std::borrow::Cow;
+use std::sync::LazyLock;
+use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
+
+use super::Color;
+use super::parser::parse_color_string;
+
+const COLOR_FUNCTION_PATTERN: &str = r"(?i)\b(mix|saturate|desaturate|lighten|darken|alpha)\s*\(";
+static COLOR_FUNCTION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(COLOR_FUNCTION_PATTERN).unwrap());
+
+/// Replaces every supported color function call in `s` with its hex representation.
+///
+/// # Arguments
+///
+/// - `s`: A color string which may contain color function calls.
+///
+/// # Returns
+///
+/// - `Ok(Cow<str>)`: The input with all color functions evaluated, borrowed if there were none.
+/// - `Err(anyhow::Error)`: An error if a function call is malformed or has invalid arguments.
+///
+/// # Examples
+///
+/// ```rust
+/// let expanded = expand_color_functions("gradient(mix(#ff0000, #0000ff), #000)")?;
+/// assert_eq!(expanded, "gradient(#800080ff, #000)");
+/// ```
+pub fn expand_color_functions(s: &str) -> anyhow::Result<Cow<'_, str>> {
+    if !COLOR_FUNCTION_REGEX.is_match(s) {
+        return Ok(Cow::Borrowed(s));
+    }
+
+    let mut expanded = s.to_string();
+
+    while let Some(caps) = COLOR_FUNCTION_REGEX.captures(&expanded) {
+        let whole = caps.get(0).unwrap();
+        let name = caps[1].to_ascii_lowercase();
+
+        let args_start = whole.end();
+        let args_end = find_closing_paren(&expanded, args_start)
+            .ok_or_else(|| anyhow!("unclosed parenthesis in color function: {s}"))?;
+
+        let args = split_args(&expanded[args_start..args_end]);
+        let color = evaluate(&name, &args)
+            .map_err(|e| anyhow!("invalid {name}() in color string '{s}': {e}"))?;
+
+        expanded.replace_range(whole.start()..=args_end, &to_hex(&color));
+    }
+
+    Ok(Cow::Owned(expanded))
+}
+
+fn evaluate(name: &str, args: &[&str]) -> anyhow::Result<D2D1_COLOR_F> {
+    match name {
+        "mix" => parse_mix(args),
+        "saturate" => parse_saturation_adjustment(args, 1.0),
+        "desaturate" => parse_saturation_adjustment(args, -1.0),
+        "lighten" => parse_lightness_adjustment(args, 1.0),
+        "darken" => parse_lightness_adjustment(args, -1.0),
+        "alpha" => parse_alpha_override(args),
+        _ => Err(anyhow!("unknown color function")),
+    }
+}
+
+/// Returns the index of the parenthesis closing the one opened right before `start`.
+fn find_closing_paren(s: &str, start: usize) -> Option<usize> {
+    let mut depth = 1;
+
+    for (i, c) in s[start..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(start + i);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Splits function arguments on commas that aren't nested in parentheses.
+pub fn split_args(s: &str) -> Vec<&str> {
+    let mut args = Vec::new();
+    let mut depth = 0;
+    let mut arg_start = 0;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                args.push(s[arg_start..i].trim());
+                arg_start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    args.push(s[arg_start..].trim());
+    args
+}
+
+/// Parses `mix(color1, color2, weight%)` arguments into a color.
+///
+/// The weight is how much of the second color is mixed into the first (default: `50%`), so `0%`
+/// returns the first color and `100%` the second.
+fn parse_mix(args: &[&str]) -> anyhow::Result<D2D1_COLOR_F> {
+    let (first, second, weight) = match args {
+        [first, second] => (first, second, 0.5),
+        [first, second, weight] => (first, second, parse_percentage(weight)?),
+        _ => return Err(anyhow!("expected 2 or 3 arguments, got {}", args.len())),
+    };
+
+    let first = resolve_color(first)?;
+    let second = resolve_color(second)?;
+    let lerp = |from: f32, to: f32| from * (1.0 - weight) + to * weight;
+
+    Ok(D2D1_COLOR_F {
+        r: lerp(first.r, second.r),
+        g: lerp(first.g, second.g),
+        b: lerp(first.b, second.b),
+        a: lerp(first.a, second.a),
+    })
+}
+
+/// Parses `saturate(color, amount%)` or `desaturate(color, amount%)` arguments into a color.
+///
+/// The amount is added to (or, with a negative `sign`, subtracted from) the color's HSL
+/// saturation, which is then clamped to [0%, 100%]. Hue, lightness and alpha are preserved.
+fn parse_saturation_adjustment(args: &[&str], sign: f32) -> anyhow::Result<D2D1_COLOR_F> {
+    let [color, amount] = args else {
+        return Err(anyhow!("expected 2 arguments, got {}", args.len()));
+    };
+
+    let color = resolve_color(color)?;
+    let amount = parse_percentage(amount)?;
+
+    Ok(adjust_saturation(&color, sign * amount))
+}
+
+/// Parses `lighten(color, amount%)` or `darken(color, amount%)` arguments into a color.
+///
+/// The amount is added to (or, with a negative `sign`, subtracted from) the color's HSL
+/// lightness, which is then clamped to [0%, 100%]. Hue, saturation and alpha are preserved.
+fn parse_lightness_adjustment(args: &[&str], sign: f32) -> anyhow::Result<D2D1_COLOR_F> {
+    let [color, amount] = args else {
+        return Err(anyhow!("expected 2 arguments, got {}", args.len()));
+    };
+
+    let color = resolve_color(color)?;
+    let amount = parse_percentage(amount)?;
+
+    Ok(adjust_lightness(&color, sign * amount))
+}
+
+/// Parses `alpha(color, alpha)` arguments into a color with only its alpha channel replaced.
+///
+/// The alpha may be a fraction (`0.5`) or a percentage (`50%`).
+fn parse_alpha_override(args: &[&str]) -> anyhow::Result<D2D1_COLOR_F> {
+    let [color, alpha] = args else {
+        return Err(anyhow!("expected 2 arguments, got {}", args.len()));
+    };
+
+    Ok(D2D1_COLOR_F {
+        a: parse_alpha(alpha)?,
+        ..resolve_color(color)?
+    })
+}
+
+/// Resolves a color function argument, which may be any solid color expression.
+fn resolve_color(s: &str) -> anyhow::Result<D2D1_COLOR_F> {
+    match parse_color_string(s)? {
+        Color::Solid(solid) => Ok(solid.color),
+        Color::Gradient(_) => Err(anyhow!("expected a solid color, got gradient '{s}'")),
+    }
+}
+
+/// Parses a percentage like `50%` into a fraction clamped to [0.0, 1.0].
+fn parse_percentage(s: &str) -> anyhow::Result<f32> {
+    let value = s
+        .strip_suffix('%')
+        .ok_or_else(|| anyhow!("expected a percentage, got '{s}'"))?
+        .trim()
+        .parse::<f32>()?;
+
+    Ok((value / 100.0).clamp(0.0, 1.0))
+}
+
+/// Parses an alpha value given as a fraction (`0.5`) or a percentage (`50%`).
+fn parse_alpha(s: &str) -> anyhow::Result<f32> {
+    match s.ends_with('%') {
+        true => parse_percentage(s),
+        false => Ok(s.parse::<f32>()?.clamp(0.0, 1.0)),
+    }
+}
+
+/// Converts HSL to RGB. The hue wraps around at 360 degrees, while saturation and lightness
+/// range from 0.0 to 1.0.
+pub fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> (f32, f32, f32) {
+    let hue = hue.rem_euclid(360.0);
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let m = lightness - chroma / 2.0;
+
+    let (r, g, b) = match hue {
+        h if h < 60.0 => (chroma, x, 0.0),
+        h if h < 120.0 => (x, chroma, 0.0),
+        h if h < 180.0 => (0.0, chroma, x),
+        h if h < 240.0 => (0.0, x, chroma),
+        h if h < 300.0 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    (r + m, g + m, b + m)
+}
+
+/// Converts RGB to HSL. The hue is in degrees from 0 to 360, while saturation and lightness
+/// range from 0.0 to 1.0.
+pub fn rgb_to_hsl(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let lightness = (max + min) / 2.0;
+
+    if delta == 0.0 {
+        return (0.0, 0.0, lightness);
+    }
+
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = match max {
+        m if m == r => 60.0 * ((g - b) / delta).rem_euclid(6.0),
+        m if m == g => 60.0 * ((b - r) / delta + 2.0),
+        _ => 60.0 * ((r - g) / delta + 4.0),
+    };
+
+    (hue, saturation, lightness)
+}
+
+/// Rotates the hue of a color by the given number of degrees, preserving its alpha.
+pub fn rotate_hue(color: &D2D1_COLOR_F, degrees: f32) -> D2D1_COLOR_F {
+    let (hue, saturation, lightness) = rgb_to_hsl(color.r, color.g, color.b);
+    let (r, g, b) = hsl_to_rgb(hue + degrees, saturation, lightness);
+
+    D2D1_COLOR_F {
+        r,
+        g,
+        b,
+        a: color.a,
+    }
+}
+
+/// Adjusts the HSL lightness of a color by `amount` (from -1.0 to 1.0), preserving its alpha.
+pub fn adjust_lightness(color: &D2D1_COLOR_F, amount: f32) -> D2D1_COLOR_F {
+    let (hue, saturation, lightness) = rgb_to_hsl(color.r, color.g, color.b);
+    let (r, g, b) = hsl_to_rgb(hue, saturation, (lightness + amount).clamp(0.0, 1.0));
+
+    D2D1_COLOR_F {
+        r,
+        g,
+        b,
+        a: color.a,
+    }
+}
+
+/// Adjusts the HSL saturation of a color by `amount` (from -1.0 to 1.0), preserving its alpha.
+pub fn adjust_saturation(color: &D2D1_COLOR_F, amount: f32) -> D2D1_COLOR_F {
+    let (hue, saturation, lightness) = rgb_to_hsl(color.r, color.g, color.b);
+    let (r, g, b) = hsl_to_rgb(hue, (saturation + amount).clamp(0.0, 1.0), lightness);
+
+    D2D1_COLOR_F {
+        r,
+        g,
+        b,
+        a: color.a,
+    }
+}
+
+/// Converts an sRGB color to OKLab, returning its `[L, a, b]` coordinates. Alpha is ignored.
+///
+/// ```rust
+/// let [l, a, b] = srgb_to_oklab(&D2D1_COLOR_F { r: 1.0, g: 1.0, b: 1.0, a: 1.0 });
+/// // White has a lightness of 1 and no chroma: [1.0, 0.0, 0.0]
+/// ```
+pub fn srgb_to_oklab(color: &D2D1_COLOR_F) -> [f64; 3] {
+    let linear = |c: f32| {
+        let c = c as f64;
+        match c <= 0.04045 {
+            true => c / 12.92,
+            false => ((c + 0.055) / 1.055).powf(2.4),
+        }
+    };
+    let (r, g, b) = (linear(color.r), linear(color.g), linear(color.b));
+
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+/// Converts OKLab `[L, a, b]` coordinates back to an sRGB color with the given alpha. Colors
+/// outside of the sRGB gamut are clamped.
+///
+/// ```rust
+/// let red = oklab_to_srgb([0.627955, 0.224863, 0.125846], 1.0);
+/// // ≈ D2D1_COLOR_F { r: 1.0, g: 0.0, b: 0.0, a: 1.0 }
+/// ```
+pub fn oklab_to_srgb([lightness, a, b]: [f64; 3], alpha: f32) -> D2D1_COLOR_F {
+    let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+    let channel = |c: f64| {
+        let c = match c <= 0.0031308 {
+            true => 12.92 * c,
+            false => 1.055 * c.powf(1.0 / 2.4) - 0.055,
+        };
+        c.clamp(0.0, 1.0) as f32
+    };
+
+    D2D1_COLOR_F {
+        r: channel(4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s),
+        g: channel(-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s),
+        b: channel(-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s),
+        a: alpha,
+    }
+}
+
+/// Interpolates between two colors in OKLab, where `t` ranges from 0.0 (`from`) to 1.0 (`to`).
+/// Alpha is interpolated linearly.
+pub fn mix_oklab(from: &D2D1_COLOR_F, to: &D2D1_COLOR_F, t: f32) -> D2D1_COLOR_F {
+    let (from_lab, to_lab) = (srgb_to_oklab(from), srgb_to_oklab(to));
+    let lerp = |start: f64, end: f64| start + (end - start) * t as f64;
+
+    oklab_to_srgb(
+        [
+            lerp(from_lab[0], to_lab[0]),
+            lerp(from_lab[1], to_lab[1]),
+            lerp(from_lab[2], to_lab[2]),
+        ],
+        from.a + (to.a - from.a) * t,
+    )
+}
+
+/// Interpolates linearly between two colors, where `t` ranges from 0.0 (`from`) to 1.0 (`to`).
+pub fn interpolate_d2d1_colors(from: &D2D1_COLOR_F, to: &D2D1_COLOR_F, t: f32) -> D2D1_COLOR_F {
+    let lerp = |start: f32, end: f32| start + (end - start) * t;
+
+    D2D1_COLOR_F {
+        r: lerp(from.r, to.r),
+        g: lerp(from.g, to.g),
+        b: lerp(from.b, to.b),
+        a: lerp(from.a, to.a),
+    }
+}
+
+/// Formats a color as an `#RRGGBBAA` hex string.
+pub fn to_hex(color: &D2D1_COLOR_F) -> String {
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+    format!(
+        "#{:02x}{:02x}{:02x}{:02x}",
+        channel(color.r),
+        channel(color.g),
+        channel(color.b),
+        channel(color.a)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(r: u8, g: u8, b: u8) -> D2D1_COLOR_F {
+        D2D1_COLOR_F {
+            r: r as f32 / 255.0,
+            g: g as f32 / 255.0,
+            b: b as f32 / 255.0,
+            a: 1.0,
+        }
+    }
+
+    #[test]
+    fn desaturating_fully_yields_gray_of_same_lightness() {
+        let color = rgb(0x34, 0x98, 0xdb);
+        let gray = adjust_saturation(&color, -1.0);
+
+        assert!((gray.r - gray.g).abs() < 1e-6 && (gray.g - gray.b).abs() < 1e-6);
+        // HSL lightness is the mean of the largest and smallest channel
+        let lightness = (0x34 as f32 + 0xdb as f32) / 2.0 / 255.0;
+        assert!((gray.r - lightness).abs() < 1e-6);
+        assert_eq!(gray.a, color.a);
+    }
+
+    #[test]
+    fn srgb_to_oklab_matches_reference_values() {
+        // Reference values from https://bottosson.github.io/posts/oklab/
+        let cases = [
+            (rgb(255, 255, 255), [1.0, 0.0, 0.0]),
+            (rgb(0, 0, 0), [0.0, 0.0, 0.0]),
+            (rgb(255, 0, 0), [0.627955, 0.224863, 0.125846]),
+            (rgb(0, 255, 0), [0.866440, -0.233888, 0.179498]),
+            (rgb(0, 0, 255), [0.452014, -0.032457, -0.311528]),
+        ];
+
+        for (color, expected) in cases {
+            let lab = srgb_to_oklab(&color);
+            for (actual, expected) in lab.iter().zip(expected) {
+                assert!(
+                    (actual - expected).abs() < 1e-4,
+                    "{color:?}: got {lab:?}, expected {expected:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn interpolates_each_channel_linearly() {
+        let from = rgb(0, 0x80, 0xff);
+        let to = D2D1_COLOR_F {
+            a: 0.0,
+            ..rgb(0xff, 0x80, 0)
+        };
+
+        assert_eq!(interpolate_d2d1_colors(&from, &to, 0.0), from);
+        assert_eq!(interpolate_d2d1_colors(&from, &to, 1.0), to);
+
+        let halfway = interpolate_d2d1_colors(&from, &to, 0.5);
+        assert!((halfway.r - 0.5).abs() < 1e-6);
+        assert!((halfway.g - from.g).abs() < 1e-6);
+        assert!((halfway.b - 0.5).abs() < 1e-6);
+        assert!((halfway.a - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn oklab_round_trips_to_srgb() {
+        for color in [rgb(255, 0, 0), rgb(0x34, 0x98, 0xdb), rgb(0x80, 0x80, 0x80)] {
+            let round_trip = oklab_to_srgb(srgb_to_oklab(&color), color.a);
+            for (actual, expected) in [
+                (round_trip.r, color.r),
+                (round_trip.g, color.g),
+                (round_trip.b, color.b),
+            ] {
+                assert!(
+                    (actual - expected).abs() < 1e-4,
+                    "{color:?} -> {round_trip:?}"
+                );
+            }
+        }
+    }
+}
@@ -1,14 +1,27 @@
 use colorparser_css::GradientCoordinates;
 use schema_jsonrs::JsonSchema;
 use serde::Deserialize;
+use std::collections::HashMap;
 use windows::Win32::{
     Foundation::RECT,
     Graphics::Direct2D::{
         Common::{D2D1_GRADIENT_STOP, D2D_POINT_2F},
-        ID2D1LinearGradientBrush,
+        D2D1_EXTEND_MODE, D2D1_EXTEND_MODE_CLAMP, D2D1_EXTEND_MODE_MIRROR, D2D1_EXTEND_MODE_WRAP,
+        ID2D1Brush, ID2D1LinearGradientBrush, ID2D1RadialGradientBrush,
     },
 };
 
+/// The default center of a radial gradient, in normalized coordinates.
+pub const DEFAULT_RADIAL_CENTER: [f32; 2] = [0.5, 0.5];
+/// The default radius of a radial gradient, relative to the window's width and height.
+pub const DEFAULT_RADIAL_RADIUS: f32 = 0.5;
+/// How much of the gradient fades to transparent at each end when `fade_edges` is enabled.
+pub const FADE_EDGES_LENGTH: f32 = 0.1;
+/// Number of stops sampled between each pair of stops of an `oklab` gradient.
+pub const OKLAB_SAMPLES_PER_SEGMENT: usize = 8;
+/// Degrees between the hue rotations a gradient gets a brush for, see `HueBrushes`.
+pub const HUE_ROTATION_STEP: f32 = 2.0;
+
 /// Represents a gradient with a specific direction, gradient stops, and an optional brush.
 ///
 /// The `Gradient` struct defines a linear gradient that can be applied to render objects with
@@ -20,35 +33,137 @@ use windows::Win32::{
 ///   start and end points, each defined as normalized values ranging from 0.0 to 1.0. These values
 ///   are scaled based on the size of the window to determine the pixel positions of the gradient's
 ///   start and end points.
+/// - `configured_direction`: The direction from the config, which the direction-sweep animation
+///   turns from.
 /// - `gradient_stops`: A vector of `D2D1_GRADIENT_STOP` values, representing the color stops in the
 ///   gradient. These stops define the colors that the gradient transitions through.
-/// - `brush`: An optional `ID2D1LinearGradientBrush` used to render the gradient. If not initialized,
+/// - `kind`: Whether the gradient is linear or radial.
+/// - `center`: The center of a radial gradient in normalized coordinates. Unused for linear gradients.
+/// - `radius`: The radius of a radial gradient relative to the window's width and height. Unused for
+///   linear gradients.
+/// - `extend`: How the gradient is drawn outside of its start and end points.
+/// - `brush`: An optional `GradientBrush` used to render the gradient. If not initialized,
 ///   this value is `None`.
+/// - `hue_brushes`: Brushes for the hue rotations of the gradient, reused by the hue-rotate
+///   animation.
+/// - `accent`: Whether any of the colors was resolved from the Windows accent color.
 ///
 /// # Example
 /// ```rust
 /// use windows::Win32::Graphics::Direct2D::Common::D2D1_GRADIENT_STOP;
 /// let gradient = Gradient {
 ///     direction: GradientCoordinates { start: [0.0, 0.0], end: [1.0, 1.0] },
+///     configured_direction: GradientCoordinates { start: [0.0, 0.0], end: [1.0, 1.0] },
+///     kind: GradientKind::Linear,
+///     center: DEFAULT_RADIAL_CENTER,
+///     radius: DEFAULT_RADIAL_RADIUS,
+///     extend: GradientExtend::Clamp,
 ///     gradient_stops: vec![
 ///         D2D1_GRADIENT_STOP { position: 0.0, color: D2D1_COLOR_F { r: 1.0, g: 0.0, b: 0.0, a: 1.0 } },
 ///         D2D1_GRADIENT_STOP { position: 1.0, color: D2D1_COLOR_F { r: 0.0, g: 0.0, b: 1.0, a: 1.0 } },
 ///     ],
 ///     brush: None, // Brush will be initialized later
+///     hue_brushes: HueBrushes::default(),
+///     accent: false,
 /// };
 /// ```
 #[derive(Debug, Clone, PartialEq)]
 pub struct Gradient {
     /// The direction of the gradient, either as a string or as coordinates.
     pub direction: GradientCoordinates,
+    /// The direction the gradient was configured with, which `turn_direction` turns from.
+    pub configured_direction: GradientCoordinates,
+    /// Whether the gradient is linear or radial.
+    pub kind: GradientKind,
+    /// The center of a radial gradient, ranging from 0.0 to 1.0.
+    pub center: [f32; 2],
+    /// The radius of a radial gradient, relative to the window's width and height.
+    pub radius: f32,
+    /// How the gradient is drawn outside of its start and end points.
+    pub extend: GradientExtend,
     /// A list of gradient stops defining color stops in the gradient.
     pub gradient_stops: Vec<D2D1_GRADIENT_STOP>,
-    /// An optional linear gradient brush that can be used for rendering the gradient.
-    /// It represents the gradient with a direction and color stops, and may be `None` if not yet initialized.
-    pub brush: Option<ID2D1LinearGradientBrush>,
+    /// An optional gradient brush that can be used for rendering the gradient.
+    /// It represents the gradient with its geometry and color stops, and may be `None` if not yet initialized.
+    pub brush: Option<GradientBrush>,
+    /// Brushes for the hue rotations of the gradient, see `HueBrushes`.
+    pub hue_brushes: HueBrushes,
+    /// Whether any of the colors was resolved from the Windows accent color, in which case the
+    /// gradient is resolved again when the accent color changes.
+    pub accent: bool,
+}
+
+/// The Direct2D brush backing a `Gradient`, depending on its kind.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GradientBrush {
+    Linear(ID2D1LinearGradientBrush),
+    Radial(ID2D1RadialGradientBrush),
+}
+
+/// Brushes of a gradient with its hue rotated, keyed by the rotation in `HUE_ROTATION_STEP`s.
+///
+/// Gradient stop collections can't be modified, so every hue rotation needs its own brush. They
+/// are created the first time a rotation is reached and reused after that, so a running hue-rotate
+/// animation stops creating brushes after its first turn.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HueBrushes {
+    /// The rotation the gradient's current brush has, in `HUE_ROTATION_STEP`s.
+    pub current: usize,
+    /// The brushes of every rotation reached so far, including the current one.
+    pub brushes: HashMap<usize, GradientBrush>,
+}
+
+impl HueBrushes {
+    /// Returns the rotation in `HUE_ROTATION_STEP`s closest to `degrees`.
+    pub fn step(degrees: f32) -> usize {
+        let steps = (360.0 / HUE_ROTATION_STEP) as usize;
+        ((degrees / HUE_ROTATION_STEP).round() as i64).rem_euclid(steps as i64) as usize
+    }
+}
+
+impl GradientBrush {
+    /// Returns the brush as a generic `ID2D1Brush`.
+    pub fn as_brush(&self) -> &ID2D1Brush {
+        match self {
+            GradientBrush::Linear(id2d1_brush) => id2d1_brush.into(),
+            GradientBrush::Radial(id2d1_brush) => id2d1_brush.into(),
+        }
+    }
 }
 
 impl Gradient {
+    /// Updates the gradient's geometry based on the window's dimensions, according to its kind.
+    ///
+    /// # Parameters
+    /// - `window_rect`: The dimensions of the window as a `RECT`.
+    pub fn update_geometry(&self, window_rect: &RECT) {
+        match self.kind {
+            GradientKind::Linear => self.update_start_end_points(window_rect),
+            GradientKind::Radial => self.update_center_radius(window_rect),
+        }
+    }
+
+    /// Points a linear gradient in the direction of an angle, like a `"<angle>deg"` direction in
+    /// the config: `0deg` points right and `90deg` points up. The new start and end points are
+    /// applied on the next render.
+    pub fn set_direction_angle(&mut self, degrees: f32) {
+        let angle = format!("{}deg", degrees.rem_euclid(360.0));
+        if let Ok(direction) = GradientCoordinates::try_from(angle.as_str()) {
+            self.direction = direction;
+        }
+    }
+
+    /// Points a linear gradient `degrees` counterclockwise from the direction it was configured
+    /// with, see `set_direction_angle`.
+    pub fn turn_direction(&mut self, degrees: f32) {
+        let [start_x, start_y] = self.configured_direction.start;
+        let [end_x, end_y] = self.configured_direction.end;
+        // Angles turn counterclockwise, but the y axis points down
+        let configured_degrees = (start_y - end_y).atan2(end_x - start_x).to_degrees();
+
+        self.set_direction_angle(configured_degrees + degrees);
+    }
+
     /// Updates the start and end points of the gradient based on the window's dimensions.
     ///
     /// This method calculates the new start and end points of the gradient brush based on the
@@ -74,13 +189,85 @@ impl Gradient {
             y: self.direction.end[1] * height,
         };
 
-        if let Some(ref id2d1_brush) = self.brush {
+        if let Some(GradientBrush::Linear(ref id2d1_brush)) = self.brush {
             unsafe {
                 id2d1_brush.SetStartPoint(start_point);
                 id2d1_brush.SetEndPoint(end_point)
             };
         }
     }
+
+    /// Updates the center and radii of a radial gradient based on the window's dimensions.
+    ///
+    /// Like `update_start_end_points`, the normalized center and radius are scaled by the window's
+    /// width and height, so the gradient stretches into an ellipse on non-square windows.
+    ///
+    /// # Parameters
+    /// - `window_rect`: The dimensions of the window as a `RECT`, used to compute the pixel
+    ///   positions for the gradient's center and radii.
+    pub fn update_center_radius(&self, window_rect: &RECT) {
+        let width = (window_rect.right - window_rect.left) as f32;
+        let height = (window_rect.bottom - window_rect.top) as f32;
+
+        let center = D2D_POINT_2F {
+            x: self.center[0] * width,
+            y: self.center[1] * height,
+        };
+
+        if let Some(GradientBrush::Radial(ref id2d1_brush)) = self.brush {
+            unsafe {
+                id2d1_brush.SetCenter(center);
+                id2d1_brush.SetRadiusX(self.radius * width);
+                id2d1_brush.SetRadiusY(self.radius * height);
+            };
+        }
+    }
+}
+
+/// The color space a gradient interpolates its colors in.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum GradientSpace {
+    /// Interpolates the sRGB channels, which can pass through muddy colors between saturated
+    /// stops.
+    #[default]
+    Srgb,
+    /// Interpolates in OKLab, which keeps the midpoints as vivid as the stops.
+    Oklab,
+}
+
+/// Defines how a gradient is drawn outside of its start and end points.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum GradientExtend {
+    /// The colors at the ends of the gradient are extended.
+    #[default]
+    Clamp,
+    /// The gradient is repeated.
+    Repeat,
+    /// The gradient is repeated, alternating between normal and mirrored.
+    Reflect,
+}
+
+impl From<GradientExtend> for D2D1_EXTEND_MODE {
+    fn from(value: GradientExtend) -> Self {
+        match value {
+            GradientExtend::Clamp => D2D1_EXTEND_MODE_CLAMP,
+            GradientExtend::Repeat => D2D1_EXTEND_MODE_WRAP,
+            GradientExtend::Reflect => D2D1_EXTEND_MODE_MIRROR,
+        }
+    }
+}
+
+/// The shape of a gradient.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum GradientKind {
+    /// Colors transition along the line between the start and end points.
+    #[default]
+    Linear,
+    /// Colors radiate outward from the center.
+    Radial,
 }
 
 /// Enum representing different types of gradient directions.
@@ -94,6 +281,15 @@ pub enum GradientDirection {
     Coordinates(GradientCoordinates),
 }
 
+impl Default for GradientDirection {
+    fn default() -> Self {
+        Self::Coordinates(GradientCoordinates {
+            start: [0.0, 0.0],
+            end: [1.0, 0.0],
+        })
+    }
+}
+
 impl From<&str> for GradientDirection {
     fn from(s: &str) -> Self {
         Self::Direction(s.to_string())
@@ -103,17 +299,139 @@ impl From<&str> for GradientDirection {
 /// A structure that defines a gradient mapping, which contains a list of color stops and a direction.
 #[derive(Debug, Clone, Deserialize, PartialEq, JsonSchema)]
 pub struct ColorMapping {
-    /// A list of colors in the gradient, represented as hexadecimal color strings.
-    pub colors: Vec<String>,
-    /// The direction of the gradient, represented as a `GradientDirection`.
+    /// A list of colors in the gradient, optionally pinned to explicit positions.
+    pub colors: Vec<GradientStopConfig>,
+    /// The direction of the gradient, represented as a `GradientDirection`. Ignored for radial gradients.
+    #[serde(default)]
     pub direction: GradientDirection,
+    /// The shape of the gradient, either `linear` (default) or `radial`.
+    #[serde(default)]
+    pub kind: GradientKind,
+    /// The center of a radial gradient as normalized `[x, y]` coordinates (default: `[0.5, 0.5]`).
+    #[serde(default)]
+    pub center: Option<[f32; 2]>,
+    /// The radius of a radial gradient relative to the window's size (default: `0.5`).
+    #[serde(default)]
+    pub radius: Option<f32>,
+    /// How the gradient is drawn outside of its start and end points.
+    #[serde(default)]
+    pub extend: GradientExtend,
+    /// Whether the ends of the gradient fade to transparent.
+    #[serde(default)]
+    pub fade_edges: bool,
+    /// The color space the colors are interpolated in, either `srgb` (default) or `oklab`.
+    #[serde(default, alias = "gradient_space")]
+    pub space: GradientSpace,
 }
 
 impl ColorMapping {
     pub fn new(colors: &[&str], direction: GradientDirection) -> Self {
         Self {
-            colors: colors.iter().map(|&s| s.to_string()).collect(),
+            colors: colors
+                .iter()
+                .map(|&s| GradientStopConfig::Color(s.to_string()))
+                .collect(),
             direction,
+            kind: GradientKind::Linear,
+            center: None,
+            radius: None,
+            extend: GradientExtend::Clamp,
+            fade_edges: false,
+            space: GradientSpace::Srgb,
+        }
+    }
+}
+
+/// A single color in a `ColorMapping`.
+///
+/// Stops without a position are distributed evenly between their neighbours.
+#[derive(Debug, Clone, Deserialize, PartialEq, JsonSchema)]
+#[serde(untagged)]
+pub enum GradientStopConfig {
+    /// A color string, optionally followed by a position percentage (e.g. `"#89b4fa 25%"`).
+    Color(String),
+    /// A color pinned to a position between 0.0 and 1.0.
+    Stop { color: String, position: f32 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linear_gradient(direction: GradientCoordinates) -> Gradient {
+        Gradient {
+            direction: direction.clone(),
+            configured_direction: direction,
+            kind: GradientKind::Linear,
+            center: DEFAULT_RADIAL_CENTER,
+            radius: DEFAULT_RADIAL_RADIUS,
+            extend: GradientExtend::Clamp,
+            gradient_stops: Vec::new(),
+            brush: None,
+            hue_brushes: HueBrushes::default(),
+            accent: false,
         }
     }
+
+    fn assert_direction(gradient: &Gradient, start: [f32; 2], end: [f32; 2]) {
+        let direction = &gradient.direction;
+        let points = direction
+            .start
+            .iter()
+            .zip(start)
+            .chain(direction.end.iter().zip(end));
+        for (actual, expected) in points {
+            assert!(
+                (actual - expected).abs() < 1e-4,
+                "got {direction:?}, expected {start:?} -> {end:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn direction_angles_match_config_angles() {
+        let mut gradient = linear_gradient(GradientCoordinates::try_from("0deg").unwrap());
+
+        for degrees in [0.0, 45.0, 90.0, 135.0, 180.0, 270.0, 315.0] {
+            let expected = GradientCoordinates::try_from(format!("{degrees}deg").as_str()).unwrap();
+            gradient.set_direction_angle(degrees);
+            assert_direction(&gradient, expected.start, expected.end);
+        }
+
+        // 0deg points right and 90deg points up
+        gradient.set_direction_angle(0.0);
+        assert_direction(&gradient, [0.0, 0.5], [1.0, 0.5]);
+        gradient.set_direction_angle(90.0);
+        assert_direction(&gradient, [0.5, 1.0], [0.5, 0.0]);
+        gradient.set_direction_angle(450.0);
+        assert_direction(&gradient, [0.5, 1.0], [0.5, 0.0]);
+    }
+
+    #[test]
+    fn turning_starts_from_configured_direction() {
+        let to_bottom = GradientCoordinates {
+            start: [0.5, 0.0],
+            end: [0.5, 1.0],
+        };
+        let mut gradient = linear_gradient(to_bottom.clone());
+
+        gradient.turn_direction(0.0);
+        assert_direction(&gradient, to_bottom.start, to_bottom.end);
+        gradient.turn_direction(90.0);
+        assert_direction(&gradient, [0.0, 0.5], [1.0, 0.5]);
+        gradient.turn_direction(180.0);
+        assert_direction(&gradient, [0.5, 1.0], [0.5, 0.0]);
+    }
+
+    #[test]
+    fn hue_rotation_steps_wrap_around() {
+        assert_eq!(HueBrushes::step(0.0), 0);
+        assert_eq!(HueBrushes::step(HUE_ROTATION_STEP), 1);
+        assert_eq!(HueBrushes::step(360.0), 0);
+        assert_eq!(HueBrushes::step(359.5), 0);
+        assert_eq!(
+            HueBrushes::step(-HUE_ROTATION_STEP),
+            (360.0 / HUE_ROTATION_STEP) as usize - 1
+        );
+    }
 }
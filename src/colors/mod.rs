@@ -1,29 +1,49 @@
 #![allow(dead_code)]
 pub mod error;
+pub mod functions;
 pub mod gradient;
 pub mod parser;
 pub mod solid;
 
+use anyhow::anyhow;
+use functions::rotate_hue;
+use functions::to_hex;
 use gradient::ColorMapping;
 use gradient::Gradient;
+use gradient::GradientBrush;
+use gradient::GradientKind;
+use gradient::GradientStopConfig;
+use gradient::HUE_ROTATION_STEP;
+use gradient::HueBrushes;
 use parser::parse_color_mapping;
 use parser::parse_color_string;
+use regex::Captures;
+use regex::Regex;
 use schema_jsonrs::JsonSchema;
 use serde::Deserialize;
 use solid::Solid;
+use std::sync::LazyLock;
 use windows::Foundation::Numerics::Matrix3x2;
 use windows::Win32::Foundation::RECT;
 use windows::Win32::Graphics::Direct2D::Common::D2D_POINT_2F;
 use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
+use windows::Win32::Graphics::Direct2D::Common::D2D1_GRADIENT_STOP;
 use windows::Win32::Graphics::Direct2D::D2D1_BRUSH_PROPERTIES;
 use windows::Win32::Graphics::Direct2D::D2D1_BUFFER_PRECISION_8BPC_UNORM;
 use windows::Win32::Graphics::Direct2D::D2D1_COLOR_INTERPOLATION_MODE_STRAIGHT;
 use windows::Win32::Graphics::Direct2D::D2D1_COLOR_SPACE_SRGB;
-use windows::Win32::Graphics::Direct2D::D2D1_EXTEND_MODE_CLAMP;
 use windows::Win32::Graphics::Direct2D::D2D1_LINEAR_GRADIENT_BRUSH_PROPERTIES;
+use windows::Win32::Graphics::Direct2D::D2D1_RADIAL_GRADIENT_BRUSH_PROPERTIES;
 use windows::Win32::Graphics::Direct2D::ID2D1Brush;
 use windows::Win32::Graphics::Direct2D::ID2D1DeviceContext7;
 
+/// Matches the words of a color string. Dots and hyphens are part of a word, so the `active`
+/// keyword isn't found within theme keys like `border.active` or names like `active-blue`.
+static COLOR_WORD_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[\w.#-]+").unwrap());
+
+const ACTIVE_KEYWORD: &str = "active";
+const INACTIVE_KEYWORD: &str = "inactive";
+
 /// The `Color` enum represents different types of colors, including both solid colors and gradients.
 /// It can be either a solid color or a gradient, allowing flexibility in color representation.
 #[derive(Debug, Clone, PartialEq)]
@@ -55,6 +75,123 @@ pub enum GlobalColor {
     Mapping(ColorMapping),
 }
 
+impl GlobalColor {
+    /// Parses an active color, which can't reference the active or inactive color itself.
+    pub fn to_active_color(&self) -> anyhow::Result<Color> {
+        if self.references(ACTIVE_KEYWORD) || self.references(INACTIVE_KEYWORD) {
+            return Err(anyhow!(
+                "active_color can't reference the active or inactive color"
+            ));
+        }
+
+        self.to_color()
+    }
+
+    /// Replaces the `active` keyword with the given active color, so an inactive color can be
+    /// derived from the active one, e.g. `darken(active, 40%)`.
+    ///
+    /// Only a solid active color can be referenced.
+    fn resolve_active_reference(&self, active: &Color) -> anyhow::Result<GlobalColor> {
+        let active_hex = match active {
+            Color::Solid(solid) => to_hex(&solid.color),
+            Color::Gradient(_) => {
+                return Err(anyhow!("only a solid active_color can be referenced"));
+            }
+        };
+        let replace = |s: &str| {
+            COLOR_WORD_REGEX
+                .replace_all(s, |caps: &Captures| match &caps[0] {
+                    word if word.eq_ignore_ascii_case(ACTIVE_KEYWORD) => active_hex.clone(),
+                    word => word.to_string(),
+                })
+                .into_owned()
+        };
+
+        Ok(match self {
+            GlobalColor::String(s) => GlobalColor::String(replace(s)),
+            GlobalColor::Mapping(mapping) => {
+                let mut mapping = mapping.clone();
+                for stop in mapping.colors.iter_mut() {
+                    match stop {
+                        GradientStopConfig::Color(color) => *color = replace(color),
+                        GradientStopConfig::Stop { color, .. } => *color = replace(color),
+                    }
+                }
+                GlobalColor::Mapping(mapping)
+            }
+        })
+    }
+
+    /// Returns whether any of the color strings contains `keyword` as a word of its own.
+    fn references(&self, keyword: &str) -> bool {
+        let contains = |s: &str| {
+            COLOR_WORD_REGEX
+                .find_iter(s)
+                .any(|word| word.as_str().eq_ignore_ascii_case(keyword))
+        };
+
+        match self {
+            GlobalColor::String(s) => contains(s),
+            GlobalColor::Mapping(mapping) => mapping.colors.iter().any(|stop| match stop {
+                GradientStopConfig::Color(color) => contains(color),
+                GradientStopConfig::Stop { color, .. } => contains(color),
+            }),
+        }
+    }
+}
+
+/// An inactive color from the config. Colors referencing the `active` keyword are kept as written
+/// until the active color of a border is known, see `InactiveColor::resolve`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InactiveColor {
+    Color(Color),
+    ActiveReference(GlobalColor),
+}
+
+impl TryFrom<&GlobalColor> for InactiveColor {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &GlobalColor) -> Result<Self, Self::Error> {
+        if !value.references(ACTIVE_KEYWORD) {
+            return value.to_color().map(InactiveColor::Color);
+        }
+
+        // Parse it with a placeholder active color, so errors are reported with the config
+        value
+            .resolve_active_reference(&Color::default())?
+            .to_color()?;
+
+        Ok(InactiveColor::ActiveReference(value.clone()))
+    }
+}
+
+impl Default for InactiveColor {
+    fn default() -> Self {
+        InactiveColor::Color(Color::default())
+    }
+}
+
+impl InactiveColor {
+    /// Returns the inactive color, with the `active` keyword replaced by the given active color.
+    pub fn resolve(&self, active: &Color) -> anyhow::Result<Color> {
+        let color = match self {
+            InactiveColor::Color(color) => return Ok(color.clone()),
+            InactiveColor::ActiveReference(color) => color,
+        };
+
+        let mut resolved = color.resolve_active_reference(active)?.to_color()?;
+        // Derived from the accent color, so it's resolved again when the accent color changes
+        if active.is_accent() {
+            match &mut resolved {
+                Color::Solid(solid) => solid.accent = true,
+                Color::Gradient(gradient) => gradient.accent = true,
+            }
+        }
+
+        Ok(resolved)
+    }
+}
+
 impl Default for GlobalColor {
     fn default() -> Self {
         // The default global color is black (`#000000`).
@@ -129,6 +266,14 @@ pub trait ColorImpl {
     /// - `transform`: A reference to the `Matrix3x2` transformation matrix that will be applied.
     fn set_transform(&self, transform: &Matrix3x2);
 
+    /// Turns a linear gradient counterclockwise from the direction it was configured with.
+    ///
+    /// Solid colors and radial gradients are left untouched.
+    ///
+    /// # Parameters
+    /// - `degrees`: How far to turn the gradient, in degrees.
+    fn turn_direction(&mut self, degrees: f32);
+
     /// Converts the color to a Direct2D brush.
     ///
     /// This method creates a Direct2D brush (`ID2D1Brush`) from the color, which can be used for rendering
@@ -147,6 +292,27 @@ pub trait ColorImpl {
         window_rect: &RECT,
         brush_properties: &D2D1_BRUSH_PROPERTIES,
     ) -> windows::core::Result<()>;
+
+    /// Rotates the hue of the color's brush relative to the color it was created from.
+    ///
+    /// Solid colors update their brush in place. Gradient stop collections can't be modified, so
+    /// gradients switch to the brush of the closest rotation in their `HueBrushes`, creating it
+    /// with every stop rotated if the rotation wasn't reached before. The brush is given the
+    /// current brush's opacity and transform.
+    ///
+    /// # Parameters
+    /// - `degrees`: The hue rotation in degrees.
+    /// - `d2d_context`: The Direct2D render target used to create a new gradient brush.
+    /// - `window_rect`: The dimensions of the window, used to position a new gradient brush.
+    ///
+    /// # Returns
+    /// A `windows::core::Result<()>`, indicating success or failure.
+    fn set_hue_rotation(
+        &mut self,
+        degrees: f32,
+        d2d_context: &ID2D1DeviceContext7,
+        window_rect: &RECT,
+    ) -> windows::core::Result<()>;
 }
 
 pub trait GlobalColorImpl {
@@ -170,8 +336,8 @@ impl ColorImpl for Color {
     fn set_opacity(&self, opacity: f32) {
         match self {
             Color::Gradient(gradient) => {
-                if let Some(ref id2d1_brush) = gradient.brush {
-                    unsafe { id2d1_brush.SetOpacity(opacity) }
+                if let Some(ref gradient_brush) = gradient.brush {
+                    unsafe { gradient_brush.as_brush().SetOpacity(opacity) }
                 }
             }
             Color::Solid(solid) => {
@@ -191,7 +357,7 @@ impl ColorImpl for Color {
             Color::Gradient(gradient) => gradient
                 .brush
                 .as_ref()
-                .map(|id2d1_brush| unsafe { id2d1_brush.GetOpacity() }),
+                .map(|gradient_brush| unsafe { gradient_brush.as_brush().GetOpacity() }),
         }
     }
 
@@ -205,22 +371,28 @@ impl ColorImpl for Color {
                 }
             }
             Color::Gradient(gradient) => {
-                if let Some(ref id2d1_brush) = gradient.brush {
+                if let Some(ref gradient_brush) = gradient.brush {
                     unsafe {
-                        id2d1_brush.SetTransform(transform);
+                        gradient_brush.as_brush().SetTransform(transform);
                     }
                 }
             }
         }
     }
 
+    fn turn_direction(&mut self, degrees: f32) {
+        match self {
+            Color::Gradient(gradient) if gradient.kind == GradientKind::Linear => {
+                gradient.turn_direction(degrees)
+            }
+            _ => {}
+        }
+    }
+
     fn get_brush(&self) -> Option<&ID2D1Brush> {
         match self {
             Color::Solid(solid) => solid.brush.as_ref().map(|id2d1_brush| id2d1_brush.into()),
-            Color::Gradient(gradient) => gradient
-                .brush
-                .as_ref()
-                .map(|id2d1_brush| id2d1_brush.into()),
+            Color::Gradient(gradient) => gradient.brush.as_ref().map(GradientBrush::as_brush),
         }
     }
 
@@ -243,38 +415,163 @@ impl ColorImpl for Color {
                 let width = (window_rect.right - window_rect.left) as f32;
                 let height = (window_rect.bottom - window_rect.top) as f32;
 
-                let gradient_properties = D2D1_LINEAR_GRADIENT_BRUSH_PROPERTIES {
-                    startPoint: D2D_POINT_2F {
-                        x: gradient.direction.start[0] * width,
-                        y: gradient.direction.start[1] * height,
-                    },
-                    endPoint: D2D_POINT_2F {
-                        x: gradient.direction.end[0] * width,
-                        y: gradient.direction.end[1] * height,
-                    },
-                };
-
                 let gradient_stop_collection = d2d_context.CreateGradientStopCollection(
                     &gradient.gradient_stops,
                     D2D1_COLOR_SPACE_SRGB,
                     D2D1_COLOR_SPACE_SRGB,
                     D2D1_BUFFER_PRECISION_8BPC_UNORM,
-                    D2D1_EXTEND_MODE_CLAMP,
+                    gradient.extend.into(),
                     D2D1_COLOR_INTERPOLATION_MODE_STRAIGHT,
                 )?;
 
-                let id2d1_brush = d2d_context.CreateLinearGradientBrush(
-                    &gradient_properties,
-                    Some(brush_properties),
-                    &gradient_stop_collection,
-                )?;
+                let gradient_brush = match gradient.kind {
+                    GradientKind::Linear => {
+                        let gradient_properties = D2D1_LINEAR_GRADIENT_BRUSH_PROPERTIES {
+                            startPoint: D2D_POINT_2F {
+                                x: gradient.direction.start[0] * width,
+                                y: gradient.direction.start[1] * height,
+                            },
+                            endPoint: D2D_POINT_2F {
+                                x: gradient.direction.end[0] * width,
+                                y: gradient.direction.end[1] * height,
+                            },
+                        };
+
+                        GradientBrush::Linear(d2d_context.CreateLinearGradientBrush(
+                            &gradient_properties,
+                            Some(brush_properties),
+                            &gradient_stop_collection,
+                        )?)
+                    }
+                    GradientKind::Radial => {
+                        let gradient_properties = D2D1_RADIAL_GRADIENT_BRUSH_PROPERTIES {
+                            center: D2D_POINT_2F {
+                                x: gradient.center[0] * width,
+                                y: gradient.center[1] * height,
+                            },
+                            gradientOriginOffset: D2D_POINT_2F::default(),
+                            radiusX: gradient.radius * width,
+                            radiusY: gradient.radius * height,
+                        };
+
+                        GradientBrush::Radial(d2d_context.CreateRadialGradientBrush(
+                            &gradient_properties,
+                            Some(brush_properties),
+                            &gradient_stop_collection,
+                        )?)
+                    }
+                };
 
-                gradient.brush = Some(id2d1_brush);
+                gradient.brush = Some(gradient_brush);
+                // The cached rotations belong to the old brush's render target
+                gradient.hue_brushes = HueBrushes::default();
 
                 Ok(())
             },
         }
     }
+
+    fn set_hue_rotation(
+        &mut self,
+        degrees: f32,
+        d2d_context: &ID2D1DeviceContext7,
+        window_rect: &RECT,
+    ) -> windows::core::Result<()> {
+        match self {
+            Color::Solid(solid) => {
+                if let Some(ref id2d1_brush) = solid.brush {
+                    unsafe { id2d1_brush.SetColor(&rotate_hue(&solid.color, degrees)) };
+                }
+
+                Ok(())
+            }
+            Color::Gradient(gradient) => {
+                let step = HueBrushes::step(degrees);
+                let Some(ref gradient_brush) = gradient.brush else {
+                    return Ok(());
+                };
+                if step == gradient.hue_brushes.current {
+                    return Ok(());
+                }
+
+                let mut transform = Matrix3x2::default();
+                let brush_properties = unsafe {
+                    gradient_brush.as_brush().GetTransform(&mut transform);
+
+                    D2D1_BRUSH_PROPERTIES {
+                        opacity: gradient_brush.as_brush().GetOpacity(),
+                        transform,
+                    }
+                };
+
+                let current = gradient.hue_brushes.current;
+                gradient
+                    .hue_brushes
+                    .brushes
+                    .entry(current)
+                    .or_insert_with(|| gradient_brush.clone());
+
+                let rotated_brush = match gradient.hue_brushes.brushes.get(&step) {
+                    Some(rotated_brush) => {
+                        let id2d1_brush = rotated_brush.as_brush();
+                        unsafe {
+                            id2d1_brush.SetOpacity(brush_properties.opacity);
+                            id2d1_brush.SetTransform(&brush_properties.transform);
+                        }
+
+                        rotated_brush.clone()
+                    }
+                    None => {
+                        let degrees = step as f32 * HUE_ROTATION_STEP;
+                        let mut rotated = Color::Gradient(Gradient {
+                            gradient_stops: gradient
+                                .gradient_stops
+                                .iter()
+                                .map(|stop| D2D1_GRADIENT_STOP {
+                                    position: stop.position,
+                                    color: rotate_hue(&stop.color, degrees),
+                                })
+                                .collect(),
+                            brush: None,
+                            hue_brushes: HueBrushes::default(),
+                            ..gradient.clone()
+                        });
+                        rotated.to_d2d1_brush(d2d_context, window_rect, &brush_properties)?;
+
+                        let Color::Gradient(Gradient {
+                            brush: Some(rotated_brush),
+                            ..
+                        }) = rotated
+                        else {
+                            return Ok(());
+                        };
+
+                        gradient
+                            .hue_brushes
+                            .brushes
+                            .insert(step, rotated_brush.clone());
+                        rotated_brush
+                    }
+                };
+
+                gradient.brush = Some(rotated_brush);
+                gradient.hue_brushes.current = step;
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Color {
+    /// Returns whether the color was resolved from the Windows accent color, in which case it
+    /// must be resolved again when the accent color changes.
+    pub fn is_accent(&self) -> bool {
+        match self {
+            Color::Solid(solid) => solid.accent,
+            Color::Gradient(gradient) => gradient.accent,
+        }
+    }
 }
 
 impl Default for Color {
@@ -282,6 +579,65 @@ impl Default for Color {
         Color::Solid(Solid {
             color: D2D1_COLOR_F::default(),
             brush: None,
+            accent: false,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn color(s: &str) -> GlobalColor {
+        GlobalColor::String(s.to_string())
+    }
+
+    fn red() -> Color {
+        Color::Solid(Solid {
+            color: D2D1_COLOR_F {
+                r: 1.0,
+                g: 0.0,
+                b: 0.0,
+                a: 1.0,
+            },
+            brush: None,
+            accent: false,
+        })
+    }
+
+    #[test]
+    fn substitutes_active_keyword_with_active_color() {
+        assert_eq!(
+            color("darken(ACTIVE, 40%)")
+                .resolve_active_reference(&red())
+                .unwrap(),
+            color("darken(#ff0000ff, 40%)")
+        );
+        assert_eq!(
+            color("active").resolve_active_reference(&red()).unwrap(),
+            color("#ff0000ff")
+        );
+    }
+
+    #[test]
+    fn only_substitutes_standalone_active_keyword() {
+        for s in ["border.active", "active.border", "active-blue", "#active"] {
+            assert!(!color(s).references(ACTIVE_KEYWORD), "{s}");
+            assert_eq!(color(s).resolve_active_reference(&red()).unwrap(), color(s));
+        }
+
+        assert_eq!(
+            color("mix(active,border.active)")
+                .resolve_active_reference(&red())
+                .unwrap(),
+            color("mix(#ff0000ff,border.active)")
+        );
+    }
+
+    #[test]
+    fn active_color_cant_reference_active_or_inactive_color() {
+        for s in ["active", "darken(inactive, 10%)", "lighten(Active, 5%)"] {
+            assert!(color(s).to_active_color().is_err(), "{s}");
+        }
+    }
+}
@@ -3,21 +3,70 @@
 
 use anyhow::anyhow;
 use colorparser_css::Color as CssColor;
+use regex::Regex;
+use std::borrow::Cow;
+use std::sync::LazyLock;
 use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
 use windows::Win32::Graphics::Direct2D::Common::D2D1_GRADIENT_STOP;
 
 use crate::app_manager::AppManager;
+use crate::windows_api::WindowsApi;
 
 use super::Color;
 use super::error::Error;
 use super::error::ErrorKind;
 use super::error::Result;
+use super::functions::adjust_lightness;
+use super::functions::expand_color_functions;
+use super::functions::mix_oklab;
+use super::functions::split_args;
+use super::functions::to_hex;
 use super::gradient::ColorMapping;
+use super::gradient::DEFAULT_RADIAL_CENTER;
+use super::gradient::DEFAULT_RADIAL_RADIUS;
+use super::gradient::FADE_EDGES_LENGTH;
 use super::gradient::Gradient;
 use super::gradient::GradientDirection;
+use super::gradient::GradientExtend;
+use super::gradient::GradientKind;
+use super::gradient::GradientSpace;
+use super::gradient::GradientStopConfig;
+use super::gradient::HueBrushes;
+use super::gradient::OKLAB_SAMPLES_PER_SEGMENT;
 use super::solid::Solid;
 use colorparser_css::GradientCoordinates;
 
+const GRADIENT_ANCHORS_PATTERN: &str = r"(?i),\s*from\s*\(\s*([-+]?[0-9]*\.?[0-9]+)\s*,\s*([-+]?[0-9]*\.?[0-9]+)\s*\)\s*to\s*\(\s*([-+]?[0-9]*\.?[0-9]+)\s*,\s*([-+]?[0-9]*\.?[0-9]+)\s*\)\s*\)\s*$";
+static GRADIENT_ANCHORS_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(GRADIENT_ANCHORS_PATTERN).unwrap());
+
+const GRADIENT_ARGS_PATTERN: &str = r"(?is)^(\s*[a-z-]*gradient\s*\()(.*)\)\s*$";
+static GRADIENT_ARGS_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(GRADIENT_ARGS_PATTERN).unwrap());
+
+const STOP_POSITION_PATTERN: &str = r"^\s*(.*\S)\s+([-+]?[0-9]*\.?[0-9]+)%\s*$";
+static STOP_POSITION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(STOP_POSITION_PATTERN).unwrap());
+
+const GRADIENT_KIND_PATTERN: &str = r"(?i)^(\s*[a-z-]*gradient\s*\(\s*)radial\s*,\s*";
+static GRADIENT_KIND_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(GRADIENT_KIND_PATTERN).unwrap());
+
+const FADE_EDGES_PATTERN: &str =
+    r"(?i)^(\s*[a-z-]*gradient\s*\(\s*(?:radial\s*,\s*)?)fade-edges\s*,\s*";
+static FADE_EDGES_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(FADE_EDGES_PATTERN).unwrap());
+
+const ACCENT_PATTERN: &str = r"(?i)\baccent";
+static ACCENT_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(ACCENT_PATTERN).unwrap());
+
+const ACCENT_AUTO_PATTERN: &str = r"(?i)\baccent-auto\b";
+static ACCENT_AUTO_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(ACCENT_AUTO_PATTERN).unwrap());
+
+/// How much lighter and darker than the accent color the stops of `accent-auto` are.
+const ACCENT_AUTO_SHADE: f32 = 0.15;
+
 /// Parses a `ColorMapping` into a `Color`.
 ///
 /// # Arguments
@@ -34,8 +83,14 @@ use colorparser_css::GradientCoordinates;
 ///
 /// ```rust
 /// let mapping = ColorMapping {
-///     colors: vec!["#FF0000".to_string()],
-///     direction: GradientCoordinates::String("90deg".to_string())
+///     colors: vec![GradientStopConfig::Color("#FF0000".to_string())],
+///     direction: GradientCoordinates::String("90deg".to_string()),
+///     kind: GradientKind::Linear,
+///     center: None,
+///     radius: None,
+///     extend: GradientExtend::Clamp,
+///     fade_edges: false,
+///     space: GradientSpace::Srgb,
 /// };
 /// let color = parse_color_mapping(mapping, Some(false))?;
 /// ```
@@ -44,55 +99,84 @@ pub fn parse_color_mapping(s: ColorMapping) -> anyhow::Result<Color> {
         0 => Ok(Color::Solid(Solid {
             color: D2D1_COLOR_F::default(),
             brush: None,
+            accent: false,
         })),
         1 => {
-            let result = parse_color_string(&s.colors[0])?;
+            let (color, _) = split_stop_config(&s.colors[0]);
+            let result = parse_color_string(color)?;
             Ok(result)
         }
         _ => {
-            let gradient_stops = generate_gradient_stops(&s.colors)?;
+            let mut gradient_stops = generate_gradient_stops(&s.colors)?;
 
             if gradient_stops.is_empty() {
                 return Err(anyhow!("invalid color mapping: no valid colors found"));
             }
 
+            if s.space == GradientSpace::Oklab {
+                gradient_stops = sample_oklab_stops(&gradient_stops);
+            }
+
+            if s.fade_edges {
+                fade_gradient_edges(&mut gradient_stops);
+            }
+
             let direction = parse_gradient_direction(&s.direction)?;
 
+            let radius = s.radius.unwrap_or(DEFAULT_RADIAL_RADIUS);
+            if radius <= 0.0 {
+                return Err(anyhow!("invalid color mapping: radius must be positive"));
+            }
+
             Ok(Color::Gradient(Gradient {
                 gradient_stops,
-                direction,
+                direction: direction.clone(),
+                configured_direction: direction,
+                kind: s.kind,
+                center: s.center.unwrap_or(DEFAULT_RADIAL_CENTER),
+                radius,
+                extend: s.extend,
                 brush: None,
+                hue_brushes: HueBrushes::default(),
+                accent: s
+                    .colors
+                    .iter()
+                    .any(|stop| ACCENT_REGEX.is_match(split_stop_config(stop).0)),
             }))
         }
     }
 }
 
-/// Generates gradient stops from a list of color strings.
+/// Generates gradient stops from a list of color stops.
 ///
 /// # Arguments
 ///
-/// - `colors`: A slice of strings representing color values in CSS-compatible format.
+/// - `colors`: A slice of color stops whose colors are in CSS-compatible format.
 ///
 /// # Returns
 ///
 /// - `Ok(Vec<D2D1_GRADIENT_STOP>)`: A vector of gradient stops for use with Direct2D.
-/// - `Err(Error)`: An error if color parsing fails.
+/// - `Err(Error)`: An error if the stop positions are invalid.
 ///
 /// # Examples
 ///
 /// ```rust
-/// let stops = generate_gradient_stops(&vec!["#FF0000".to_string(), "#00FF00".to_string()])?;
+/// let stops = generate_gradient_stops(&[
+///     GradientStopConfig::Color("#FF0000".to_string()),
+///     GradientStopConfig::Color("#00FF00 80%".to_string()),
+/// ])?;
 /// ```
-fn generate_gradient_stops(colors: &[String]) -> Result<Vec<D2D1_GRADIENT_STOP>> {
-    let num_colors = colors.len();
-    let step = 1.0 / (num_colors - 1) as f32;
+fn generate_gradient_stops(colors: &[GradientStopConfig]) -> Result<Vec<D2D1_GRADIENT_STOP>> {
+    let (colors, positions): (Vec<&str>, Vec<Option<f32>>) =
+        colors.iter().map(split_stop_config).unzip();
+    let positions = resolve_stop_positions(&positions)?;
 
     let stops: Vec<D2D1_GRADIENT_STOP> = colors
-        .iter()
-        .enumerate()
-        .filter_map(|(i, hex)| match parse_color_string(hex).ok()? {
+        .into_iter()
+        .zip(positions)
+        .filter_map(|(hex, position)| match parse_color_string(hex).ok()? {
             Color::Solid(solid) => Some(D2D1_GRADIENT_STOP {
-                position: i as f32 * step,
+                position,
                 color: solid.color,
             }),
             _ => None, // Skip invalid colors
@@ -102,6 +186,209 @@ fn generate_gradient_stops(colors: &[String]) -> Result<Vec<D2D1_GRADIENT_STOP>>
     Ok(stops)
 }
 
+/// Adds `OKLAB_SAMPLES_PER_SEGMENT` stops between each pair of stops, interpolated in OKLab.
+///
+/// Direct2D only interpolates in sRGB, so the OKLab path between two stops is approximated by
+/// sampling it into several sRGB stops that Direct2D then interpolates between.
+fn sample_oklab_stops(stops: &[D2D1_GRADIENT_STOP]) -> Vec<D2D1_GRADIENT_STOP> {
+    let mut sampled = Vec::with_capacity(stops.len() * (OKLAB_SAMPLES_PER_SEGMENT + 1));
+
+    for pair in stops.windows(2) {
+        let (start, end) = (&pair[0], &pair[1]);
+        sampled.push(*start);
+
+        for i in 1..=OKLAB_SAMPLES_PER_SEGMENT {
+            let t = i as f32 / (OKLAB_SAMPLES_PER_SEGMENT + 1) as f32;
+            sampled.push(D2D1_GRADIENT_STOP {
+                position: start.position + (end.position - start.position) * t,
+                color: mix_oklab(&start.color, &end.color, t),
+            });
+        }
+    }
+
+    sampled.extend(stops.last());
+    sampled
+}
+
+/// Compresses the stops into the middle of the gradient and adds transparent stops at 0.0 and
+/// 1.0, so the gradient fades out over `FADE_EDGES_LENGTH` at each end.
+///
+/// The gradient is interpolated in straight alpha, so fading to transparent black would darken
+/// the edges. Each transparent stop instead reuses the color of the stop next to it.
+fn fade_gradient_edges(stops: &mut Vec<D2D1_GRADIENT_STOP>) {
+    let by_position =
+        |a: &&D2D1_GRADIENT_STOP, b: &&D2D1_GRADIENT_STOP| a.position.total_cmp(&b.position);
+    let (Some(first), Some(last)) = (
+        stops.iter().min_by(by_position).copied(),
+        stops.iter().max_by(by_position).copied(),
+    ) else {
+        return;
+    };
+
+    let scale = 1.0 - 2.0 * FADE_EDGES_LENGTH;
+    for stop in stops.iter_mut() {
+        stop.position = FADE_EDGES_LENGTH + stop.position.clamp(0.0, 1.0) * scale;
+    }
+
+    stops.insert(
+        0,
+        D2D1_GRADIENT_STOP {
+            position: 0.0,
+            color: D2D1_COLOR_F {
+                a: 0.0,
+                ..first.color
+            },
+        },
+    );
+    stops.push(D2D1_GRADIENT_STOP {
+        position: 1.0,
+        color: D2D1_COLOR_F {
+            a: 0.0,
+            ..last.color
+        },
+    });
+}
+
+/// Splits a color stop into its color string and optional position.
+fn split_stop_config(stop: &GradientStopConfig) -> (&str, Option<f32>) {
+    match stop {
+        GradientStopConfig::Color(color) => split_stop_position(color),
+        GradientStopConfig::Stop { color, position } => (color.as_str(), Some(*position)),
+    }
+}
+
+/// Splits a trailing position percentage off a color, e.g. `"#00ff00 80%"`.
+fn split_stop_position(s: &str) -> (&str, Option<f32>) {
+    STOP_POSITION_REGEX
+        .captures(s)
+        .and_then(|caps| {
+            let position = caps[2].parse::<f32>().ok()?;
+            Some((caps.get(1)?.as_str(), Some(position / 100.0)))
+        })
+        .unwrap_or((s.trim(), None))
+}
+
+/// Resolves optional stop positions into a position for every stop.
+///
+/// Specified positions are clamped to [0.0, 1.0] and must be non-decreasing. The first and last
+/// stops default to 0.0 and 1.0, and any other unspecified positions are spread evenly between
+/// the nearest specified ones.
+///
+/// # Examples
+///
+/// ```rust
+/// let positions = resolve_stop_positions(&[None, Some(0.8), None])?;
+/// assert_eq!(positions, vec![0.0, 0.8, 1.0]);
+/// ```
+fn resolve_stop_positions(positions: &[Option<f32>]) -> Result<Vec<f32>> {
+    let last = positions.len().saturating_sub(1);
+
+    let mut resolved: Vec<Option<f32>> = positions
+        .iter()
+        .enumerate()
+        .map(|(i, position)| match position {
+            Some(position) => Some(position.clamp(0.0, 1.0)),
+            None if i == 0 => Some(0.0),
+            None if i == last => Some(1.0),
+            None => None,
+        })
+        .collect();
+
+    let mut previous = 0.0;
+    for &position in resolved.iter().flatten() {
+        if position < previous {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Gradient stop positions must be in non-decreasing order",
+            ));
+        }
+        previous = position;
+    }
+
+    let mut i = 1;
+    while i < resolved.len() {
+        if resolved[i].is_some() {
+            i += 1;
+            continue;
+        }
+
+        // The first and last stops always have a position, so both bounds exist
+        let start = i - 1;
+        let end = (i..resolved.len())
+            .find(|&j| resolved[j].is_some())
+            .unwrap_or(last);
+        let from = resolved[start].unwrap_or_default();
+        let to = resolved[end].unwrap_or(1.0);
+        let span = (end - start) as f32;
+
+        for (offset, position) in resolved[i..end].iter_mut().enumerate() {
+            *position = Some(from + (to - from) * (i - start + offset) as f32 / span);
+        }
+
+        i = end + 1;
+    }
+
+    Ok(resolved
+        .into_iter()
+        .map(Option::unwrap_or_default)
+        .collect())
+}
+
+/// Strips position percentages off the color stops of a gradient string.
+///
+/// # Returns
+///
+/// - `(Cow<str>, Some(Vec<Option<f32>>))`: The gradient without positions, and the position of
+///   each argument (`None` for arguments without one, such as the direction).
+/// - `(Cow<str>, None)`: The untouched input if no argument has a position.
+///
+/// # Examples
+///
+/// ```rust
+/// let (s, positions) = split_gradient_stop_positions("gradient(#f00 0%, #0f0 80%, #00f)");
+/// assert_eq!(s, "gradient(#f00, #0f0, #00f)");
+/// ```
+fn split_gradient_stop_positions(s: &str) -> (Cow<'_, str>, Option<Vec<Option<f32>>>) {
+    let Some(caps) = GRADIENT_ARGS_REGEX.captures(s) else {
+        return (Cow::Borrowed(s), None);
+    };
+
+    let (args, positions): (Vec<&str>, Vec<Option<f32>>) = split_args(&caps[2])
+        .into_iter()
+        .map(split_stop_position)
+        .unzip();
+
+    if positions.iter().all(Option::is_none) {
+        return (Cow::Borrowed(s), None);
+    }
+
+    let stripped = format!("{}{})", &caps[1], args.join(", "));
+
+    (Cow::Owned(stripped), Some(positions))
+}
+
+/// Applies positions from `split_gradient_stop_positions` to a parsed gradient.
+///
+/// The color stops are the trailing arguments of a gradient, so the positions are matched up
+/// from the end, skipping leading arguments like the direction.
+fn apply_stop_positions(gradient: &mut Gradient, positions: &[Option<f32>]) -> Result<()> {
+    let num_stops = gradient.gradient_stops.len();
+    if positions.len() < num_stops {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Gradient stop positions don't match the number of colors",
+        ));
+    }
+
+    let positions = resolve_stop_positions(&positions[positions.len() - num_stops..])?;
+
+    for (stop, position) in gradient.gradient_stops.iter_mut().zip(positions) {
+        stop.position = position;
+    }
+
+    Ok(())
+}
+
 /// Parses a gradient direction into `GradientCoordinates`.
 ///
 /// # Arguments
@@ -122,7 +409,9 @@ fn generate_gradient_stops(colors: &[String]) -> Result<Vec<D2D1_GRADIENT_STOP>>
 fn parse_gradient_direction(direction: &GradientDirection) -> Result<GradientCoordinates> {
     match direction {
         GradientDirection::Direction(dir) => {
-            GradientCoordinates::try_from(dir.as_str()).map_err(|e| {
+            // The string form splits its direction off with `split_gradient_direction`, which
+            // calls this too, so `"45deg"` here and `gradient(45deg, ...)` produce the same result
+            GradientCoordinates::try_from(dir.trim()).map_err(|e| {
                 Error::new(
                     ErrorKind::InvalidData,
                     format!("Invalid gradient direction: {}", e),
@@ -133,9 +422,49 @@ fn parse_gradient_direction(direction: &GradientDirection) -> Result<GradientCoo
     }
 }
 
+/// Splits a direction argument, such as `45deg` or `to right`, off a gradient string.
+///
+/// The direction may be the first or the last argument of the gradient. It is parsed with
+/// `parse_gradient_direction`, so it behaves exactly like the `direction` of a gradient mapping.
+///
+/// # Returns
+///
+/// - `(Cow<str>, Some(GradientCoordinates))`: The input without the direction and its coordinates.
+/// - `(Cow<str>, None)`: The untouched input if no argument is a direction.
+///
+/// # Examples
+///
+/// ```rust
+/// let (s, direction) = split_gradient_direction("gradient(45deg, #000, #fff)");
+/// assert_eq!(s, "gradient(#000, #fff)");
+/// ```
+fn split_gradient_direction(s: &str) -> (Cow<'_, str>, Option<GradientCoordinates>) {
+    let Some(caps) = GRADIENT_ARGS_REGEX.captures(s) else {
+        return (Cow::Borrowed(s), None);
+    };
+
+    let mut args = split_args(&caps[2]);
+    let parse =
+        |arg: &str| parse_gradient_direction(&GradientDirection::Direction(arg.to_string())).ok();
+
+    let Some((index, direction)) = [0, args.len() - 1]
+        .into_iter()
+        .find_map(|i| parse(args[i]).map(|direction| (i, direction)))
+    else {
+        return (Cow::Borrowed(s), None);
+    };
+
+    args.remove(index);
+    let stripped = format!("{}{})", &caps[1], args.join(", "));
+
+    (Cow::Owned(stripped), Some(direction))
+}
+
 /// Parses a CSS color string into a `Color`.
 ///
-/// This function supports solid colors and gradients in CSS-compatible formats.
+/// This function supports solid colors and gradients in CSS-compatible formats. Gradients may
+/// end with a `from (x, y) to (x, y)` clause to set their start and end anchors explicitly.
+/// Color functions such as `mix()` are evaluated first, see [`expand_color_functions`].
 ///
 /// # Arguments
 ///
@@ -150,19 +479,185 @@ fn parse_gradient_direction(direction: &GradientDirection) -> Result<GradientCoo
 ///
 /// ```rust
 /// let color = parse_color_string("#FF0000")?;
+/// let gradient = parse_color_string("gradient(#000, #fff, from (0.2, 0.0) to (0.8, 1.0))")?;
 /// ```
 pub fn parse_color_string(s: &str) -> anyhow::Result<Color> {
-    let config = AppManager::get().config().clone();
-    let theme = config.theme;
+    let expanded = expand_accent_auto(s)?;
+    let expanded = expand_color_functions(&expanded)?;
+    let (unfaded, fade_edges) = split_fade_edges(&expanded);
+    let (unkinded, kind) = split_gradient_kind(&unfaded);
+    let (unanchored, anchors) = split_gradient_anchors(&unkinded)?;
+    let (undirected, direction) = split_gradient_direction(&unanchored);
+    let (stripped, positions) = split_gradient_stop_positions(&undirected);
+
+    let mut color = parse_css_color_string(&stripped)?;
+
+    if let (Color::Gradient(gradient), Some(positions)) = (&mut color, positions) {
+        apply_stop_positions(gradient, &positions)?;
+    }
+
+    if let (Color::Gradient(gradient), true) = (&mut color, fade_edges) {
+        fade_gradient_edges(&mut gradient.gradient_stops);
+    }
+
+    // Remember colors using the accent color, so they can be resolved again once it changes
+    if ACCENT_REGEX.is_match(s) {
+        match &mut color {
+            Color::Solid(solid) => solid.accent = true,
+            Color::Gradient(gradient) => gradient.accent = true,
+        }
+    }
+
+    match (color, anchors.or(direction)) {
+        (Color::Gradient(gradient), Some(direction)) => Ok(Color::Gradient(Gradient {
+            direction: direction.clone(),
+            configured_direction: direction,
+            kind,
+            ..gradient
+        })),
+        (Color::Gradient(gradient), None) => Ok(Color::Gradient(Gradient { kind, ..gradient })),
+        (Color::Solid(_), Some(_)) => Err(anyhow!(
+            "gradient anchors are only supported for gradients: {s}"
+        )),
+        (color, None) => Ok(color),
+    }
+}
+
+/// Replaces `accent-auto` with two gradient stops derived from the Windows accent color: one
+/// lighter and one darker by `ACCENT_AUTO_SHADE`.
+///
+/// # Examples
+///
+/// ```rust
+/// let expanded = expand_accent_auto("gradient(accent-auto)")?;
+/// // With an accent color of #0078d4
+/// assert_eq!(expanded, "gradient(#229fffff, #004d88ff)");
+/// ```
+fn expand_accent_auto(s: &str) -> anyhow::Result<Cow<'_, str>> {
+    if !ACCENT_AUTO_REGEX.is_match(s) {
+        return Ok(Cow::Borrowed(s));
+    }
+
+    let accent = WindowsApi::get_accent_color()?;
+    let stops = format!(
+        "{}, {}",
+        to_hex(&adjust_lightness(&accent, ACCENT_AUTO_SHADE)),
+        to_hex(&adjust_lightness(&accent, -ACCENT_AUTO_SHADE))
+    );
+
+    Ok(Cow::Owned(
+        ACCENT_AUTO_REGEX
+            .replace_all(s, regex::NoExpand(&stops))
+            .into_owned(),
+    ))
+}
+
+/// Strips a leading `fade-edges` argument off a gradient string. It may follow `radial`.
+///
+/// # Examples
+///
+/// ```rust
+/// let (s, fade_edges) = split_fade_edges("gradient(radial, fade-edges, #fff, #000)");
+/// assert_eq!(s, "gradient(radial, #fff, #000)");
+/// ```
+fn split_fade_edges(s: &str) -> (Cow<'_, str>, bool) {
+    match FADE_EDGES_REGEX.is_match(s) {
+        true => (FADE_EDGES_REGEX.replace(s, "$1"), true),
+        false => (Cow::Borrowed(s), false),
+    }
+}
+
+/// Strips a leading `radial` argument off a gradient string.
+///
+/// # Returns
+///
+/// - `(Cow<str>, GradientKind::Radial)`: The input without the `radial` argument.
+/// - `(Cow<str>, GradientKind::Linear)`: The untouched input if it isn't a radial gradient.
+///
+/// # Examples
+///
+/// ```rust
+/// let (s, kind) = split_gradient_kind("gradient(radial, #fff, #000)");
+/// assert_eq!(s, "gradient(#fff, #000)");
+/// ```
+fn split_gradient_kind(s: &str) -> (Cow<'_, str>, GradientKind) {
+    match GRADIENT_KIND_REGEX.is_match(s) {
+        true => (GRADIENT_KIND_REGEX.replace(s, "$1"), GradientKind::Radial),
+        false => (Cow::Borrowed(s), GradientKind::Linear),
+    }
+}
+
+/// Splits a trailing `from (x, y) to (x, y)` clause off a gradient string.
+///
+/// The clause must be the last argument of the gradient, e.g.
+/// `gradient(#000, #fff, from (0.2, 0.0) to (0.8, 1.0))`. Each coordinate is a
+/// normalized value in the range [0.0, 1.0], matching `GradientCoordinates`.
+///
+/// # Returns
+///
+/// - `Ok((Cow<str>, Some(GradientCoordinates)))`: The input without the clause and the parsed anchors.
+/// - `Ok((Cow<str>, None))`: The untouched input if no clause was found.
+/// - `Err(Error)`: An error if a coordinate is outside of [0.0, 1.0].
+///
+/// # Examples
+///
+/// ```rust
+/// let (s, anchors) = split_gradient_anchors("gradient(#000, #fff, from (0, 0) to (1, 1))")?;
+/// assert_eq!(s, "gradient(#000, #fff)");
+/// ```
+fn split_gradient_anchors(s: &str) -> Result<(Cow<'_, str>, Option<GradientCoordinates>)> {
+    let Some(caps) = GRADIENT_ANCHORS_REGEX.captures(s) else {
+        return Ok((Cow::Borrowed(s), None));
+    };
 
-    let css_color = match theme.path() {
-        Some(theme) => CssColor::from_html_with_theme(s, theme).map_err(|e| {
+    let mut values = [0.0f32; 4];
+    for (i, value) in values.iter_mut().enumerate() {
+        let raw = caps.get(i + 1).map(|m| m.as_str()).unwrap_or_default();
+        *value = raw.parse::<f32>().map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid gradient anchor coordinate: {raw}"),
+            )
+        })?;
+
+        if !(0.0..=1.0).contains(value) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Gradient anchor coordinate {raw} must be within [0, 1]"),
+            ));
+        }
+    }
+
+    // The regex also consumes the gradient's closing parenthesis, so put it back
+    let whole = caps.get(0).unwrap();
+    let stripped = format!("{})", &s[..whole.start()]);
+
+    let direction = GradientCoordinates {
+        start: [values[0], values[1]],
+        end: [values[2], values[3]],
+    };
+
+    Ok((Cow::Owned(stripped), Some(direction)))
+}
+
+/// Parses a CSS color string with `colorparser_css`, applying the configured theme.
+fn parse_css_color_string(s: &str) -> anyhow::Result<Color> {
+    let theme = AppManager::get().active_theme();
+
+    let css_color = match (theme.substitute_colors(s), theme.path()) {
+        (Some(s), _) => CssColor::from_html(s).map_err(|e| {
             Error::new(
                 ErrorKind::InvalidInput,
                 format!("CSS parsing failed: {}", e),
             )
         })?,
-        None => CssColor::from_html(s).map_err(|e| {
+        (None, Some(theme)) => CssColor::from_html_with_theme(s, theme).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("CSS parsing failed: {}", e),
+            )
+        })?,
+        (None, None) => CssColor::from_html(s).map_err(|e| {
             Error::new(
                 ErrorKind::InvalidInput,
                 format!("CSS parsing failed: {}", e),
@@ -202,7 +697,11 @@ fn parse_solid_color(css_color: &CssColor) -> anyhow::Result<Color> {
         b: normalized_rgba.b,
         a: normalized_rgba.a,
     };
-    Ok(Color::Solid(Solid { color, brush: None }))
+    Ok(Color::Solid(Solid {
+        color,
+        brush: None,
+        accent: false,
+    }))
 }
 
 /// Parses a `CssColor` into a gradient `Color`.
@@ -254,8 +753,92 @@ fn parse_gradient(css_color: &CssColor) -> anyhow::Result<Color> {
     };
 
     Ok(Color::Gradient(Gradient {
-        direction,
+        direction: direction.clone(),
+        configured_direction: direction,
+        kind: GradientKind::Linear,
+        center: DEFAULT_RADIAL_CENTER,
+        radius: DEFAULT_RADIAL_RADIUS,
+        extend: GradientExtend::Clamp,
         gradient_stops,
         brush: None,
+        hue_brushes: HueBrushes::default(),
+        accent: false,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mapping_direction_matches_string_direction() {
+        for angle in ["0deg", "45deg", "90deg", "180deg", "270deg", "to right"] {
+            let mapping_form =
+                parse_gradient_direction(&GradientDirection::Direction(angle.to_string())).unwrap();
+
+            for gradient in [
+                format!("gradient({angle}, #000, #fff)"),
+                format!("gradient(#000, #fff, {angle})"),
+            ] {
+                let (stripped, string_form) = split_gradient_direction(&gradient);
+
+                assert_eq!(stripped, "gradient(#000, #fff)", "{gradient}");
+                assert_eq!(string_form, Some(mapping_form.clone()), "{gradient}");
+            }
+        }
+    }
+
+    #[test]
+    fn gradient_without_direction_is_untouched() {
+        let (stripped, direction) = split_gradient_direction("gradient(#000, red, #fff)");
+
+        assert_eq!(stripped, "gradient(#000, red, #fff)");
+        assert_eq!(direction, None);
+    }
+
+    #[test]
+    fn invalid_mapping_direction_is_rejected() {
+        let direction = GradientDirection::Direction("sideways".to_string());
+        assert!(parse_gradient_direction(&direction).is_err());
+    }
+    #[test]
+    fn fading_gradient_edges_compresses_stops_between_transparent_ends() {
+        let stop = |position: f32, r: f32, g: f32, b: f32| D2D1_GRADIENT_STOP {
+            position,
+            color: D2D1_COLOR_F { r, g, b, a: 1.0 },
+        };
+        // Stops aren't sorted yet, so the ends are picked by position
+        let mut stops = vec![
+            stop(0.5, 0.0, 1.0, 0.0),
+            stop(1.0, 0.0, 0.0, 1.0),
+            stop(0.0, 1.0, 0.0, 0.0),
+        ];
+
+        fade_gradient_edges(&mut stops);
+
+        let scale = 1.0 - 2.0 * FADE_EDGES_LENGTH;
+        let positions: Vec<f32> = stops.iter().map(|stop| stop.position).collect();
+        assert_eq!(
+            positions,
+            [
+                0.0,
+                FADE_EDGES_LENGTH + 0.5 * scale,
+                FADE_EDGES_LENGTH + scale,
+                FADE_EDGES_LENGTH,
+                1.0
+            ]
+        );
+
+        // The transparent ends keep the color of the stop next to them
+        let (first, last) = (stops[0].color, stops[4].color);
+        assert_eq!((first.r, first.g, first.b, first.a), (1.0, 0.0, 0.0, 0.0));
+        assert_eq!((last.r, last.g, last.b, last.a), (0.0, 0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn fading_gradient_edges_ignores_empty_gradients() {
+        let mut stops = Vec::new();
+        fade_gradient_edges(&mut stops);
+        assert!(stops.is_empty());
+    }
+}
@@ -10,6 +10,7 @@ use windows::Win32::Graphics::Direct2D::ID2D1SolidColorBrush;
 /// # Fields
 /// - `color`: A `D2D1_COLOR_F` struct that represents the color in RGBA format, with values for red, green, blue, and alpha (opacity) in the range [0.0, 1.0].
 /// - `brush`: An optional `ID2D1SolidColorBrush` that represents the color as a brush, used for rendering the solid color. It may be `None` if not initialized.
+/// - `accent`: Whether the color was resolved from the Windows accent color.
 ///
 /// # Example
 /// ```rust
@@ -17,6 +18,7 @@ use windows::Win32::Graphics::Direct2D::ID2D1SolidColorBrush;
 /// let solid_color = Solid {
 ///     color: D2D1_COLOR_F { r: 0.5, g: 0.0, b: 0.0, a: 1.0 },
 ///     brush: None,  // or Some(brush_instance) if a brush is initialized
+///     accent: false,
 /// };
 /// ```
 /// This creates a red color with full opacity and no associated brush.
@@ -24,4 +26,5 @@ use windows::Win32::Graphics::Direct2D::ID2D1SolidColorBrush;
 pub struct Solid {
     pub color: D2D1_COLOR_F,
     pub brush: Option<ID2D1SolidColorBrush>,
+    pub accent: bool,
 }
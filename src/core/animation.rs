@@ -6,10 +6,14 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::sync::LazyLock;
 
-const CUBIC_BEZIER_PATTERN: &str = r"(?i)^cubic[-_]?bezier\(([-+]?[0-9]*\.?[0-9]+),\s*([-+]?[0-9]*\.?[0-9]+),\s*([-+]?[0-9]*\.?[0-9]+),\s*([-+]?[0-9]*\.?[0-9]+)\)$";
+const CUBIC_BEZIER_PATTERN: &str = r"(?i)^\s*cubic[-_]?bezier\s*\(\s*([-+]?[0-9]*\.?[0-9]+)\s*,\s*([-+]?[0-9]*\.?[0-9]+)\s*,\s*([-+]?[0-9]*\.?[0-9]+)\s*,\s*([-+]?[0-9]*\.?[0-9]+)\s*\)\s*$";
 pub static CUBIC_BEZIER_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(CUBIC_BEZIER_PATTERN).unwrap());
 
+const STEPS_PATTERN: &str =
+    r"(?i)^\s*steps\s*\(\s*([0-9]+)\s*(?:,\s*(jump-start|jump-end|start|end)\s*)?\)\s*$";
+pub static STEPS_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(STEPS_PATTERN).unwrap());
+
 type CubicBezierPoints = [f32; 4];
 
 pub fn parse_cubic_bezier(input: &str) -> anyhow::Result<CubicBezierPoints> {
@@ -45,15 +49,56 @@ pub fn parse_cubic_bezier(input: &str) -> anyhow::Result<CubicBezierPoints> {
                 .parse::<f32>()
                 .map_err(|_| anyhow!("Failed to parse numeric value in: {}", input))?;
 
+            // The x coordinates are time, so they must stay within [0, 1] for the curve to be
+            // invertible. The y coordinates may overshoot to allow for bouncy easings.
+            if !(0.0..=1.0).contains(&x1) || !(0.0..=1.0).contains(&x2) {
+                return Err(anyhow!(
+                    "x1 and x2 in cubic bezier must be between 0 and 1: {input}"
+                ));
+            }
+
             Ok([x1, y1, x2, y2])
         })
 }
 
+pub fn parse_steps(input: &str) -> anyhow::Result<(u32, StepPosition)> {
+    let caps = STEPS_REGEX
+        .captures(input)
+        .ok_or_else(|| anyhow!("Invalid steps format: {input}"))?;
+
+    let count = caps[1]
+        .parse::<u32>()
+        .map_err(|_| anyhow!("Failed to parse step count in: {input}"))?;
+    if count < 1 {
+        return Err(anyhow!("Step count must be at least 1: {input}"));
+    }
+
+    let position = match caps.get(2).map(|m| m.as_str().to_ascii_lowercase()) {
+        Some(position) if position.ends_with("start") => StepPosition::Start,
+        _ => StepPosition::End,
+    };
+
+    Ok((count, position))
+}
+
+/// Where the jumps of a `steps()` easing happen, following CSS.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum StepPosition {
+    /// The first jump happens as soon as the animation starts.
+    Start,
+    /// The last jump happens when the animation ends.
+    #[default]
+    End,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
 pub enum AnimationKind {
     Spiral,
     Fade,
     ReverseSpiral,
+    HueRotate,
+    Pulse,
+    DirectionSweep,
 }
 
 impl FromStr for AnimationKind {
@@ -66,6 +111,11 @@ impl FromStr for AnimationKind {
             "reverse_spiral" | "reversespiral" | "reverse-spiral" => {
                 Ok(AnimationKind::ReverseSpiral)
             }
+            "hue_rotate" | "huerotate" | "hue-rotate" => Ok(AnimationKind::HueRotate),
+            "pulse" | "breathe" => Ok(AnimationKind::Pulse),
+            "direction_sweep" | "directionsweep" | "direction-sweep" => {
+                Ok(AnimationKind::DirectionSweep)
+            }
             _ => Err("Unknown animation type"),
         }
     }
@@ -111,6 +161,9 @@ pub enum AnimationEasing {
     EaseInOutCirc,
     EaseInOutBack,
 
+    // Steps
+    Steps(u32, StepPosition),
+
     #[serde(untagged)]
     CubicBezier([f32; 4]),
 }
@@ -192,7 +245,11 @@ impl FromStr for AnimationEasing {
                     .map_err(|err| format!("invalid cubic-bezier format: {}: {}", input, err))
             }
 
-            _ => Ok(AnimationEasing::default()),
+            _ if input.trim_start().to_ascii_lowercase().starts_with("steps") => parse_steps(input)
+                .map(|(count, position)| AnimationEasing::Steps(count, position))
+                .map_err(|err| format!("invalid steps format: {}: {}", input, err)),
+
+            _ => Err(format!("unknown easing function: {input}")),
         }
     }
 }
@@ -205,7 +262,8 @@ pub trait AnimationEasingImpl {
 impl AnimationEasingImpl for AnimationEasing {
     /// Converts the easing to a corresponding array of points.
     /// Linear and named easing variants will return predefined control points,
-    /// while CubicBezier returns its own array.
+    /// while CubicBezier returns its own array. Steps isn't a curve, so it falls back to linear
+    /// control points; `to_fn` handles it separately.
     fn evaluate(&self) -> [f32; 4] {
         match self {
             // Linear
@@ -244,12 +302,29 @@ impl AnimationEasingImpl for AnimationEasing {
             AnimationEasing::EaseInOutCirc => [0.85, 0.0, 0.15, 1.0],
             AnimationEasing::EaseInOutBack => [0.68, -0.6, 0.32, 1.6],
 
+            // Steps
+            AnimationEasing::Steps(..) => [0.0, 0.0, 1.0, 1.0],
+
             // CubicBezier variant returns its own points.
             AnimationEasing::CubicBezier(bezier) => *bezier,
         }
     }
 
     fn to_fn(&self) -> anyhow::Result<Arc<AnimationEasingCallback>> {
+        if let AnimationEasing::Steps(count, position) = *self {
+            let count = count as f32;
+
+            return Ok(Arc::new(move |x: f32| {
+                // Quantize the progress into `count` levels, jumping at the start or end of each
+                let step = match position {
+                    StepPosition::Start => (x * count).floor() + 1.0,
+                    StepPosition::End => (x * count).floor(),
+                };
+
+                Ok((step / count).clamp(0.0, 1.0))
+            }));
+        }
+
         let easing_points = self.evaluate();
 
         let easing_fn = bezier(
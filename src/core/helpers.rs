@@ -7,10 +7,6 @@ pub fn serde_default_u32<const V: u32>() -> u32 {
     V
 }
 
-pub fn serde_default_i32<const V: i32>() -> i32 {
-    V
-}
-
 pub fn serde_default_f32<const V: i32>() -> f32 {
     V as f32
 }
@@ -57,3 +53,82 @@ pub fn parse_duration_str(s: &str) -> Option<f64> {
 
     s.parse().ok()
 }
+
+/// A glob pattern, compiled once so matching doesn't allocate. `*` matches any run of characters
+/// (including none) and `?` matches exactly one character.
+#[derive(Debug, Clone)]
+pub struct GlobPattern {
+    pattern: String,
+    /// The characters of the pattern in ASCII lowercase.
+    chars: Vec<char>,
+}
+
+impl GlobPattern {
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            chars: pattern.chars().map(|c| c.to_ascii_lowercase()).collect(),
+        }
+    }
+
+    /// Returns the pattern as it was written.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Matches `value` against the pattern, ignoring ASCII case.
+    pub fn is_match(&self, value: &str) -> bool {
+        let pattern = &self.chars;
+        // `v` is a byte index into `value`, so it can be sliced without collecting its characters
+        let (mut p, mut v) = (0, 0);
+        // Position of the last `*` in the pattern and the value index it is currently matched up to
+        let mut backtrack: Option<(usize, usize)> = None;
+
+        while let Some(c) = value[v..].chars().next() {
+            match pattern.get(p) {
+                Some('*') => {
+                    backtrack = Some((p, v));
+                    p += 1;
+                }
+                Some(&pc) if pc == '?' || pc == c.to_ascii_lowercase() => {
+                    p += 1;
+                    v += c.len_utf8();
+                }
+                _ => match backtrack {
+                    // Let the last `*` swallow one more character and retry from there
+                    Some((star, matched)) => {
+                        let swallowed = value[matched..].chars().next().map_or(0, char::len_utf8);
+                        backtrack = Some((star, matched + swallowed));
+                        p = star + 1;
+                        v = matched + swallowed;
+                    }
+                    None => return false,
+                },
+            }
+        }
+
+        pattern[p..].iter().all(|&c| c == '*')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_matches_wildcards_ignoring_case() {
+        let exe = GlobPattern::new("*.exe");
+        assert!(exe.is_match("Code.EXE"));
+        assert!(exe.is_match(".exe"));
+        assert!(!exe.is_match("code.exe.bak"));
+
+        let studio = GlobPattern::new("Visual Studio*");
+        assert!(studio.is_match("visual studio 2022"));
+        assert!(!studio.is_match("Microsoft Visual Studio"));
+
+        let single = GlobPattern::new("n?tepad");
+        assert!(single.is_match("Notepad"));
+        assert!(single.is_match("nötepad"));
+        assert!(!single.is_match("ntepad"));
+    }
+}
@@ -96,6 +96,16 @@ zoom_and_enhance! {
         pub open_config: String,
         #[serde(default = "default_exit_key")]
         pub exit: String,
+        #[serde(default = "default_toggle_key")]
+        pub toggle: String,
+        #[serde(default = "default_toggle_window_key")]
+        pub toggle_window: String,
+        #[serde(default = "default_pause_animations_key")]
+        pub pause_animations: String,
+        #[serde(default = "default_next_theme_key")]
+        pub next_theme: String,
+        #[serde(default = "default_prev_theme_key")]
+        pub prev_theme: String,
     }
 }
 
@@ -111,12 +121,37 @@ fn default_exit_key() -> String {
     "f10".to_string()
 }
 
+fn default_toggle_key() -> String {
+    "ctrl+alt+f7".to_string()
+}
+
+fn default_toggle_window_key() -> String {
+    "ctrl+alt+f6".to_string()
+}
+
+fn default_pause_animations_key() -> String {
+    "ctrl+f9".to_string()
+}
+
+fn default_next_theme_key() -> String {
+    "ctrl+f7".to_string()
+}
+
+fn default_prev_theme_key() -> String {
+    "ctrl+shift+f7".to_string()
+}
+
 impl Default for Keybindings {
     fn default() -> Self {
         Self {
             reload: default_reload_key(),
             open_config: default_open_config_key(),
             exit: default_exit_key(),
+            toggle: default_toggle_key(),
+            toggle_window: default_toggle_window_key(),
+            pause_animations: default_pause_animations_key(),
+            next_theme: default_next_theme_key(),
+            prev_theme: default_prev_theme_key(),
         }
     }
 }
@@ -139,6 +174,31 @@ fn create_keybindings(value: &Keybindings) -> Vec<KeybindingConfig> {
             value.exit.as_str(),
             Some(SystemTrayEvent::Exit),
         ),
+        KeybindingConfig::new(
+            field_names.get_value("toggle").as_str(),
+            value.toggle.as_str(),
+            Some(SystemTrayEvent::ToggleBorders),
+        ),
+        KeybindingConfig::new(
+            field_names.get_value("toggle_window").as_str(),
+            value.toggle_window.as_str(),
+            Some(SystemTrayEvent::ToggleWindowBorder),
+        ),
+        KeybindingConfig::new(
+            field_names.get_value("pause_animations").as_str(),
+            value.pause_animations.as_str(),
+            Some(SystemTrayEvent::PauseAnimations),
+        ),
+        KeybindingConfig::new(
+            field_names.get_value("next_theme").as_str(),
+            value.next_theme.as_str(),
+            Some(SystemTrayEvent::NextTheme),
+        ),
+        KeybindingConfig::new(
+            field_names.get_value("prev_theme").as_str(),
+            value.prev_theme.as_str(),
+            Some(SystemTrayEvent::PrevTheme),
+        ),
     ];
     debug!("Keybindings: Created ({bindings:#?})");
     bindings
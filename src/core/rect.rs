@@ -18,13 +18,17 @@ impl From<Rect> for RECT {
 }
 
 impl Rect {
+    /// Coordinate Windows moves minimized windows to, far outside of any monitor.
+    pub const MINIMIZED_POSITION: i32 = -32000;
+
     pub fn is_same_size_as(&self, rhs: &Self) -> bool {
         self.0.right - self.0.left == rhs.0.right - rhs.0.left
             && self.0.bottom - self.0.top == rhs.0.bottom - rhs.0.top
     }
 
-    pub fn is_visible(&self) -> bool {
-        self.0.top >= 0 || self.0.left >= 0 || self.0.bottom >= 0 || self.0.right >= 0
+    /// Returns whether this is the collapsed rect Windows gives minimized windows.
+    pub fn is_minimized(&self) -> bool {
+        self.0.left <= Self::MINIMIZED_POSITION && self.0.top <= Self::MINIMIZED_POSITION
     }
 }
 
@@ -94,3 +98,30 @@ impl Rect {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(left: i32, top: i32, right: i32, bottom: i32) -> Rect {
+        Rect(RECT {
+            left,
+            top,
+            right,
+            bottom,
+        })
+    }
+
+    #[test]
+    fn minimized_rect_is_detected() {
+        // The rect GetWindowRect returns for a minimized window
+        assert!(rect(-32000, -32000, -31840, -31972).is_minimized());
+    }
+
+    #[test]
+    fn rect_on_a_monitor_is_not_minimized() {
+        assert!(!rect(100, 100, 900, 700).is_minimized());
+        // Monitors left of or above the primary monitor have negative coordinates
+        assert!(!rect(-1920, -200, -1000, 500).is_minimized());
+    }
+}
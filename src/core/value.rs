@@ -7,12 +7,16 @@ use serde::de::Visitor;
 use super::helpers::parse_duration_str;
 use super::helpers::parse_length_str;
 
-/// Enum representing a `Value` that can either be a finite number or a non-empty string.
+/// Enum representing a `Value` that can either be a finite number, a percentage or a non-empty
+/// string.
 #[derive(Clone, PartialEq, Debug, JsonSchema)]
 pub enum Value {
     /// A finite number (f64).
     Number(f64),
 
+    /// A percentage (e.g., `"5%"` is stored as `5.0`), relative to a size known only at runtime.
+    Percentage(f64),
+
     /// A non-empty string.
     String(String),
 }
@@ -63,6 +67,8 @@ impl Visitor<'_> for ValueVisitor {
     {
         if value.trim().is_empty() {
             Err(E::custom("string cannot be empty"))
+        } else if let Some(percentage) = parse_percentage(value) {
+            Ok(Value::Percentage(percentage))
         } else {
             Ok(Value::String(value.to_owned()))
         }
@@ -74,6 +80,8 @@ impl Visitor<'_> for ValueVisitor {
     {
         if value.trim().is_empty() {
             Err(E::custom("string cannot be empty"))
+        } else if let Some(percentage) = parse_percentage(&value) {
+            Ok(Value::Percentage(percentage))
         } else {
             Ok(Value::String(value))
         }
@@ -102,6 +110,17 @@ where
     }
 }
 
+/// Parses a percentage string like `"5%"` into its number, if it is one.
+fn parse_percentage(value: &str) -> Option<f64> {
+    value
+        .trim()
+        .strip_suffix('%')?
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|percentage| percentage.is_finite())
+}
+
 macro_rules! as_type {
     ($val:expr, $target_type:ty) => {
         // Safely convert using `as` for primitive types
@@ -122,12 +141,15 @@ pub trait ValueConversion {
     fn as_duration_i64(&self) -> Option<i64>;
     fn as_duration_u32(&self) -> Option<u32>;
     fn as_duration_u64(&self) -> Option<u64>;
+    /// Returns a percentage as a fraction (e.g., `0.05` for `"5%"`), or `None` for other values.
+    fn as_percentage_f32(&self) -> Option<f32>;
 }
 
 impl ValueConversion for Value {
     fn as_length_f32(&self) -> Option<f32> {
         match self {
             Value::Number(num) => Some(as_type!(*num, f32)),
+            Value::Percentage(_) => None,
             Value::String(s) => parse_length_str(s).map(|n| as_type!(n, f32)),
         }
     }
@@ -135,6 +157,7 @@ impl ValueConversion for Value {
     fn as_length_i32(&self) -> Option<i32> {
         match self {
             Value::Number(num) => Some(as_type!(*num, i32)),
+            Value::Percentage(_) => None,
             Value::String(s) => parse_length_str(s).map(|n| as_type!(n, i32)),
         }
     }
@@ -142,6 +165,7 @@ impl ValueConversion for Value {
     fn as_length_f64(&self) -> Option<f64> {
         match self {
             Value::Number(num) => Some(as_type!(*num, f64)),
+            Value::Percentage(_) => None,
             Value::String(s) => parse_length_str(s).map(|n| as_type!(n, f64)),
         }
     }
@@ -149,6 +173,7 @@ impl ValueConversion for Value {
     fn as_length_i64(&self) -> Option<i64> {
         match self {
             Value::Number(num) => Some(as_type!(*num, i64)),
+            Value::Percentage(_) => None,
             Value::String(s) => parse_length_str(s).map(|n| as_type!(n, i64)),
         }
     }
@@ -156,6 +181,7 @@ impl ValueConversion for Value {
     fn as_length_u32(&self) -> Option<u32> {
         match self {
             Value::Number(num) => Some(as_type!(*num, u32)),
+            Value::Percentage(_) => None,
             Value::String(s) => parse_length_str(s).map(|n| as_type!(n, u32)),
         }
     }
@@ -163,6 +189,7 @@ impl ValueConversion for Value {
     fn as_length_u64(&self) -> Option<u64> {
         match self {
             Value::Number(num) => Some(as_type!(*num, u64)),
+            Value::Percentage(_) => None,
             Value::String(s) => parse_length_str(s).map(|n| as_type!(n, u64)),
         }
     }
@@ -170,6 +197,7 @@ impl ValueConversion for Value {
     fn as_duration_f32(&self) -> Option<f32> {
         match self {
             Value::Number(num) => Some(as_type!(*num, f32)),
+            Value::Percentage(_) => None,
             Value::String(s) => parse_duration_str(s).map(|n| as_type!(n, f32)),
         }
     }
@@ -177,6 +205,7 @@ impl ValueConversion for Value {
     fn as_duration_i32(&self) -> Option<i32> {
         match self {
             Value::Number(num) => Some(as_type!(*num, i32)),
+            Value::Percentage(_) => None,
             Value::String(s) => parse_duration_str(s).map(|n| as_type!(n, i32)),
         }
     }
@@ -184,6 +213,7 @@ impl ValueConversion for Value {
     fn as_duration_f64(&self) -> Option<f64> {
         match self {
             Value::Number(num) => Some(as_type!(*num, f64)),
+            Value::Percentage(_) => None,
             Value::String(s) => parse_duration_str(s).map(|n| as_type!(n, f64)),
         }
     }
@@ -191,6 +221,7 @@ impl ValueConversion for Value {
     fn as_duration_i64(&self) -> Option<i64> {
         match self {
             Value::Number(num) => Some(as_type!(*num, i64)),
+            Value::Percentage(_) => None,
             Value::String(s) => parse_duration_str(s).map(|n| as_type!(n, i64)),
         }
     }
@@ -198,6 +229,7 @@ impl ValueConversion for Value {
     fn as_duration_u32(&self) -> Option<u32> {
         match self {
             Value::Number(num) => Some(as_type!(*num, u32)),
+            Value::Percentage(_) => None,
             Value::String(s) => parse_duration_str(s).map(|n| as_type!(n, u32)),
         }
     }
@@ -205,9 +237,17 @@ impl ValueConversion for Value {
     fn as_duration_u64(&self) -> Option<u64> {
         match self {
             Value::Number(num) => Some(as_type!(*num, u64)),
+            Value::Percentage(_) => None,
             Value::String(s) => parse_duration_str(s).map(|n| as_type!(n, u64)),
         }
     }
+
+    fn as_percentage_f32(&self) -> Option<f32> {
+        match self {
+            Value::Percentage(percentage) => Some(as_type!(*percentage / 100.0, f32)),
+            Value::Number(_) | Value::String(_) => None,
+        }
+    }
 }
 
 impl<T: ValueConversion> ValueConversion for Option<T> {
@@ -258,4 +298,8 @@ impl<T: ValueConversion> ValueConversion for Option<T> {
     fn as_duration_u64(&self) -> Option<u64> {
         self.as_ref()?.as_duration_u64()
     }
+
+    fn as_percentage_f32(&self) -> Option<f32> {
+        self.as_ref()?.as_percentage_f32()
+    }
 }
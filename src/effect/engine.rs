@@ -1,10 +1,13 @@
 use super::{EffectConfig, EffectTranslationConfig};
+use crate::colors::Color;
+use crate::colors::parser::parse_color_string;
 use crate::core::helpers::parse_length_str;
 use crate::core::value::{Value, ValueConversion};
 use anyhow::anyhow;
 use schema_jsonrs::JsonSchema;
 use serde::Deserialize;
 use std::str::FromStr;
+use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
 
 /// Represents an effect applied to an object, such as a custom window border, including the effect's type,
 /// standard deviation (for blur or spread effects), opacity, and translation in 2D space.
@@ -37,12 +40,36 @@ pub struct EffectEngine {
     /// This specifies how much the effect should be shifted in the x and y directions relative to the object.
     /// For example, a shadow might be translated downward to create the illusion that it is cast beneath the border.
     pub translation: EffectTranslation,
+
+    /// Optional per-edge spread of the effect.
+    ///
+    /// When present, the effect is rendered with one directional blur per edge instead of a single
+    /// uniform Gaussian blur. See [`EffectSpread`].
+    pub spread: Option<EffectSpread>,
+
+    /// Optional color of the effect.
+    ///
+    /// Shadows are drawn in this color, while glows are tinted with it. When `None`, glows keep the border's
+    /// colors and shadows stay black.
+    pub color: Option<D2D1_COLOR_F>,
+}
+
+impl EffectEngine {
+    /// Returns the largest standard deviation used by this effect on any edge.
+    ///
+    /// This is what determines how much padding the border window needs to fit the effect.
+    pub fn max_standard_deviation(&self) -> f32 {
+        match self.spread {
+            Some(spread) => spread.max(),
+            None => self.standard_deviation,
+        }
+    }
 }
 
 /// Defines the different kinds of effects that can be applied to custom window borders, such as a glow effect or a shadow effect.
 ///
 /// This enum allows you to specify what kind of visual effect should be applied. The available options are:
-/// `Glow` for a halo-like effect and `Shadow` for a depth-enhancing effect.
+/// `Glow` for a halo-like effect, `Shadow` for a depth-enhancing effect and `InnerGlow` for a halo inside the border.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum EffectKind {
     /// A glowing effect, often used to create a halo or highlight around the object.
@@ -55,6 +82,19 @@ pub enum EffectKind {
     /// The `Shadow` effect creates the illusion of the object floating above the surface, with a darker or more
     /// diffused shadow based on the chosen parameters.
     Shadow,
+
+    /// A glowing effect that spreads toward the inside of the border instead of outward.
+    ///
+    /// An `InnerGlow` effect is clipped to the area inside the border, so it lights up the edge of the window
+    /// itself and doesn't need any extra room around the border.
+    InnerGlow,
+}
+
+impl EffectKind {
+    /// Returns `true` if the effect is drawn inside the border rather than around it.
+    pub fn is_inner(&self) -> bool {
+        matches!(self, EffectKind::InnerGlow)
+    }
 }
 
 impl FromStr for EffectKind {
@@ -64,6 +104,7 @@ impl FromStr for EffectKind {
         match s.to_lowercase().as_str() {
             "glow" => Ok(EffectKind::Glow),
             "shadow" => Ok(EffectKind::Shadow),
+            "inner_glow" | "innerglow" | "inner-glow" => Ok(EffectKind::InnerGlow),
             _ => Err("Unknown effect type"),
         }
     }
@@ -109,15 +150,57 @@ impl TryFrom<EffectConfig> for EffectEngine {
             }
         };
 
+        let spread = value.spread.map(|spread| EffectSpread {
+            top: spread.top.as_length_f32().unwrap_or(standard_deviation),
+            right: spread.right.as_length_f32().unwrap_or(standard_deviation),
+            bottom: spread.bottom.as_length_f32().unwrap_or(standard_deviation),
+            left: spread.left.as_length_f32().unwrap_or(standard_deviation),
+        });
+
+        let color = match value.color {
+            Some(ref color) => match parse_color_string(color)? {
+                Color::Solid(solid) => Some(solid.color),
+                Color::Gradient(_) => {
+                    return Err(anyhow!("effect color must be a solid color: {color}"));
+                }
+            },
+            None => None,
+        };
+
         Ok(Self {
             kind,
             standard_deviation,
             opacity: value.opacity,
             translation,
+            spread,
+            color,
         })
     }
 }
 
+/// Per-edge standard deviations used when an effect has an explicit spread.
+///
+/// Top and bottom are blurred vertically, left and right horizontally, so each edge only spreads
+/// outward by its own amount.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct EffectSpread {
+    /// Standard deviation along the top edge.
+    pub top: f32,
+    /// Standard deviation along the right edge.
+    pub right: f32,
+    /// Standard deviation along the bottom edge.
+    pub bottom: f32,
+    /// Standard deviation along the left edge.
+    pub left: f32,
+}
+
+impl EffectSpread {
+    /// Returns the largest standard deviation of all four edges.
+    pub fn max(&self) -> f32 {
+        self.top.max(self.right).max(self.bottom).max(self.left)
+    }
+}
+
 /// Represents the translation of an effect in 2D space, defined by `x` and `y` coordinates.
 ///
 /// This struct is used for simpler translation cases, where both coordinates are of type `f32`.
@@ -2,19 +2,31 @@ use anyhow::Context;
 use windows::{
     Foundation::Numerics::Matrix3x2,
     Win32::Graphics::Direct2D::{
-        CLSID_D2D1Composite, CLSID_D2D1GaussianBlur, CLSID_D2D1Opacity, CLSID_D2D1Shadow,
-        CLSID_D2D12DAffineTransform,
-        Common::{D2D1_COMPOSITE_MODE_DESTINATION_OUT, D2D1_COMPOSITE_MODE_SOURCE_OVER},
-        D2D1_2DAFFINETRANSFORM_PROP_TRANSFORM_MATRIX, D2D1_DIRECTIONALBLUR_OPTIMIZATION_SPEED,
-        D2D1_GAUSSIANBLUR_PROP_OPTIMIZATION, D2D1_GAUSSIANBLUR_PROP_STANDARD_DEVIATION,
-        D2D1_INTERPOLATION_MODE_LINEAR, D2D1_OPACITY_PROP_OPACITY, D2D1_PROPERTY_TYPE_ENUM,
-        D2D1_PROPERTY_TYPE_FLOAT, D2D1_PROPERTY_TYPE_MATRIX_3X2,
-        D2D1_SHADOW_PROP_BLUR_STANDARD_DEVIATION, D2D1_SHADOW_PROP_OPTIMIZATION, ID2D1Bitmap1,
-        ID2D1CommandList, ID2D1DeviceContext7, ID2D1Effect,
+        CLSID_D2D1ColorMatrix, CLSID_D2D1Composite, CLSID_D2D1Crop, CLSID_D2D1DirectionalBlur,
+        CLSID_D2D1GaussianBlur, CLSID_D2D1Opacity, CLSID_D2D1Shadow, CLSID_D2D12DAffineTransform,
+        Common::{
+            D2D_VECTOR_4F, D2D1_COLOR_F, D2D1_COMPOSITE_MODE_DESTINATION_OUT,
+            D2D1_COMPOSITE_MODE_SOURCE_IN, D2D1_COMPOSITE_MODE_SOURCE_OVER,
+        },
+        D2D1_2DAFFINETRANSFORM_PROP_TRANSFORM_MATRIX, D2D1_COLORMATRIX_PROP_COLOR_MATRIX,
+        D2D1_COMPOSITE_PROP_MODE, D2D1_CROP_PROP_RECT, D2D1_DIRECTIONALBLUR_OPTIMIZATION_SPEED,
+        D2D1_DIRECTIONALBLUR_PROP_ANGLE, D2D1_DIRECTIONALBLUR_PROP_OPTIMIZATION,
+        D2D1_DIRECTIONALBLUR_PROP_STANDARD_DEVIATION, D2D1_GAUSSIANBLUR_PROP_OPTIMIZATION,
+        D2D1_GAUSSIANBLUR_PROP_STANDARD_DEVIATION, D2D1_INTERPOLATION_MODE_LINEAR,
+        D2D1_OPACITY_PROP_OPACITY, D2D1_PROPERTY_TYPE_ENUM, D2D1_PROPERTY_TYPE_FLOAT,
+        D2D1_PROPERTY_TYPE_MATRIX_3X2, D2D1_PROPERTY_TYPE_MATRIX_5X4, D2D1_PROPERTY_TYPE_VECTOR4,
+        D2D1_SHADOW_PROP_BLUR_STANDARD_DEVIATION, D2D1_SHADOW_PROP_COLOR,
+        D2D1_SHADOW_PROP_OPTIMIZATION, ID2D1Bitmap1, ID2D1CommandList, ID2D1DeviceContext7,
+        ID2D1Effect,
     },
+    core::Interface,
 };
 
-use super::{EffectsConfig, engine::EffectKind, wrapper::EffectEngineVec};
+use super::{
+    EffectsConfig,
+    engine::{EffectKind, EffectSpread},
+    wrapper::EffectEngineVec,
+};
 
 /// Manages effects for custom window borders created using Direct2D.
 ///
@@ -47,9 +59,26 @@ pub struct EffectManager {
     ///
     /// This list contains the operations for effects that are not active but can be used when switched to active.
     inactive_command_list: Option<ID2D1CommandList>,
+
+    /// Identifies the device context and bitmaps the command lists were recorded with.
+    ///
+    /// Recording the command lists is expensive with large blurs, so they are only recorded again
+    /// when one of these changes. The effects themselves are part of the manager, which is
+    /// replaced on config reloads, so they don't need to be part of the key.
+    command_lists_key: Option<CommandListsKey>,
 }
 
+/// The raw pointers of the device context, border bitmap and mask bitmap. The command lists hold
+/// references to these, so the pointers can't be reused by new objects while the lists exist.
+type CommandListsKey = (usize, usize, usize);
+
 impl EffectManager {
+    /// Picks the effects a border uses: a window rule's effects replace the global ones entirely,
+    /// even if the rule disables them.
+    pub fn for_window_rule<'a>(window_rule: Option<&'a Self>, global: &'a Self) -> &'a Self {
+        window_rule.unwrap_or(global)
+    }
+
     /// Returns a reference to the active effects engine vector.
     pub fn active(&self) -> &EffectEngineVec {
         &self.active
@@ -66,6 +95,14 @@ impl EffectManager {
         !self.active.is_empty() || !self.inactive.is_empty()
     }
 
+    /// Returns the padding (in pixels) the border window needs, so effects drawn outside of the
+    /// border aren't cut off. This is 0 if effects are disabled.
+    pub fn window_padding(&self) -> i32 {
+        max_effect_padding(&self.active)
+            .max(max_effect_padding(&self.inactive))
+            .ceil() as i32
+    }
+
     /// Creates command lists for active and inactive effects if any are enabled.
     /// Command lists are used to record drawing operations for effects to be applied to bitmaps.
     ///
@@ -89,6 +126,18 @@ impl EffectManager {
             return Ok(());
         }
 
+        let key = (
+            d2d_context.as_raw() as usize,
+            border_bitmap.as_raw() as usize,
+            mask_bitmap.as_raw() as usize,
+        );
+        if self.command_lists_key == Some(key)
+            && self.active_command_list.is_some()
+            && self.inactive_command_list.is_some()
+        {
+            return Ok(());
+        }
+
         let create_single_list =
             |effect_params_vec: &EffectEngineVec| -> anyhow::Result<ID2D1CommandList> {
                 unsafe {
@@ -101,10 +150,40 @@ impl EffectManager {
                     d2d_context.SetTarget(&command_list);
 
                     let mut effects_vec: Vec<ID2D1Effect> = Vec::new();
+                    let mut inner_effects_vec: Vec<ID2D1Effect> = Vec::new();
 
                     for effect_params in effect_params_vec.iter() {
-                        let effect = match effect_params.kind {
-                            EffectKind::Glow => {
+                        let effect = match (effect_params.kind, effect_params.spread) {
+                            (EffectKind::Glow | EffectKind::InnerGlow, Some(spread)) => {
+                                create_spread_effect(d2d_context, border_bitmap, &spread)?
+                            }
+                            (EffectKind::Shadow, Some(spread)) => {
+                                let spread_effect =
+                                    create_spread_effect(d2d_context, border_bitmap, &spread)?;
+
+                                // The blur is already done per edge, so the shadow effect is only
+                                // used to turn the spread into a shadow color.
+                                let shadow_effect = d2d_context
+                                    .CreateEffect(&CLSID_D2D1Shadow)
+                                    .context("shadow_effect")?;
+                                shadow_effect.SetInput(
+                                    0,
+                                    &spread_effect
+                                        .GetOutput()
+                                        .context("could not get spread_effect output")?,
+                                    false,
+                                );
+                                shadow_effect
+                                    .SetValue(
+                                        D2D1_SHADOW_PROP_BLUR_STANDARD_DEVIATION.0 as u32,
+                                        D2D1_PROPERTY_TYPE_FLOAT,
+                                        &0.0f32.to_le_bytes(),
+                                    )
+                                    .context("shadow_effect.SetValue() std deviation")?;
+
+                                shadow_effect
+                            }
+                            (EffectKind::Glow | EffectKind::InnerGlow, None) => {
                                 let blur_effect = d2d_context
                                     .CreateEffect(&CLSID_D2D1GaussianBlur)
                                     .context("blur_effect")?;
@@ -126,7 +205,7 @@ impl EffectManager {
 
                                 blur_effect
                             }
-                            EffectKind::Shadow => {
+                            (EffectKind::Shadow, None) => {
                                 let shadow_effect = d2d_context
                                     .CreateEffect(&CLSID_D2D1Shadow)
                                     .context("shadow_effect")?;
@@ -150,6 +229,32 @@ impl EffectManager {
                             }
                         };
 
+                        let effect = match (effect_params.kind, effect_params.color) {
+                            (EffectKind::Shadow, Some(color)) => {
+                                let color_vector = D2D_VECTOR_4F {
+                                    x: color.r,
+                                    y: color.g,
+                                    z: color.b,
+                                    w: color.a,
+                                };
+                                let color_bytes: &[u8] = std::slice::from_raw_parts(
+                                    &color_vector as *const D2D_VECTOR_4F as *const u8,
+                                    size_of::<D2D_VECTOR_4F>(),
+                                );
+                                effect
+                                    .SetValue(
+                                        D2D1_SHADOW_PROP_COLOR.0 as u32,
+                                        D2D1_PROPERTY_TYPE_VECTOR4,
+                                        color_bytes,
+                                    )
+                                    .context("shadow_effect.SetValue() color")?;
+
+                                effect
+                            }
+                            (_, Some(color)) => create_tint_effect(d2d_context, &effect, &color)?,
+                            (_, None) => effect,
+                        };
+
                         let full_opacities_count = effect_params.opacity as u32; // Full opacity effects (e.g., 2 for opacity 2.5)
                         let remainder_opacity = effect_params.opacity - full_opacities_count as f32; // Remainder opacity (e.g., 0.5 for 2.5)
                         let mut effect_opacity_vec = Vec::new();
@@ -207,7 +312,10 @@ impl EffectManager {
                                 )
                                 .context("effect_with_opacity_translation.SetValue()")?;
 
-                            effects_vec.push(effect_with_opacity_translation);
+                            match effect_params.kind.is_inner() {
+                                true => inner_effects_vec.push(effect_with_opacity_translation),
+                                false => effects_vec.push(effect_with_opacity_translation),
+                            }
                         }
                     }
 
@@ -254,6 +362,23 @@ impl EffectManager {
                         D2D1_COMPOSITE_MODE_DESTINATION_OUT,
                     );
 
+                    // Inner effects are drawn after masking, and clipped to the mask, so they
+                    // only show up inside the border
+                    if !inner_effects_vec.is_empty() {
+                        let inner_effect =
+                            create_masked_effect(d2d_context, &inner_effects_vec, mask_bitmap)?;
+
+                        d2d_context.DrawImage(
+                            &inner_effect
+                                .GetOutput()
+                                .context("could not get inner_effect output")?,
+                            None,
+                            None,
+                            D2D1_INTERPOLATION_MODE_LINEAR,
+                            D2D1_COMPOSITE_MODE_SOURCE_OVER,
+                        );
+                    }
+
                     d2d_context.EndDraw(None, None)?;
 
                     // Close the command list to tell it we are done recording
@@ -270,6 +395,7 @@ impl EffectManager {
 
         self.active_command_list = Some(active_command_list);
         self.inactive_command_list = Some(inactive_command_list);
+        self.command_lists_key = Some(key);
 
         Ok(())
     }
@@ -303,21 +429,36 @@ impl TryFrom<EffectsConfig> for EffectManager {
     type Error = anyhow::Error;
 
     fn try_from(value: EffectsConfig) -> Result<Self, Self::Error> {
-        let active = EffectEngineVec::try_from(value.active)?;
-        let inactive = EffectEngineVec::try_from(value.inactive)?;
-
-        if value.enabled {
-            Ok(EffectManager {
-                active,
-                inactive,
-                ..Default::default()
-            })
-        } else {
-            Ok(EffectManager::default())
+        // A disabled config has no effects at all, so it overrides the global effects of a window
+        // rule instead of falling back to them, and its effects aren't even validated
+        if !value.enabled {
+            return Ok(EffectManager::default());
         }
+
+        Ok(EffectManager {
+            active: EffectEngineVec::try_from(value.active)?,
+            inactive: EffectEngineVec::try_from(value.inactive)?,
+            ..Default::default()
+        })
     }
 }
 
+/// Returns the largest padding any of `effects` needs around the border.
+fn max_effect_padding(effects: &EffectEngineVec) -> f32 {
+    effects
+        .iter()
+        // Inner effects are drawn inside the border, so they don't need any padding
+        .filter(|params| !params.kind.is_inner())
+        .map(|params| {
+            let max_std_dev = params.max_standard_deviation();
+            let max_translation = (params.translation.x).max(params.translation.y);
+
+            // 3 standard deviations gets us 99.7% coverage, which should be good enough
+            (max_std_dev * 3.0).ceil() + max_translation.ceil()
+        })
+        .fold(0.0, f32::max)
+}
+
 /// Creates an opacity effect for a given Direct2D effect and opacity level.
 /// The opacity effect applies transparency to an existing effect.
 ///
@@ -358,3 +499,308 @@ fn create_opacity_effect(
         Ok(effect_with_opacity)
     }
 }
+
+/// Creates a color matrix effect that paints an effect in a single color, keeping its alpha.
+///
+/// # Arguments
+///
+/// * `d2d_context` - A reference to the Direct2D device context.
+/// * `effect` - The effect to tint.
+/// * `color` - The color to paint the effect with. Its alpha scales the effect's alpha.
+///
+/// # Returns
+///
+/// * `Ok(ID2D1Effect)` if the tint effect is created successfully.
+/// * `Err(anyhow::Error)` if there is an error during creation.
+fn create_tint_effect(
+    d2d_context: &ID2D1DeviceContext7,
+    effect: &ID2D1Effect,
+    color: &D2D1_COLOR_F,
+) -> anyhow::Result<ID2D1Effect> {
+    unsafe {
+        let tint_effect = d2d_context
+            .CreateEffect(&CLSID_D2D1ColorMatrix)
+            .context("tint_effect")?;
+
+        tint_effect.SetInput(
+            0,
+            &effect.GetOutput().context("could not get effect output")?,
+            false,
+        );
+
+        // A 5x4 row-major matrix applied to [r, g, b, a, 1]. The input color is dropped, the
+        // input alpha is scaled by the tint's alpha, and the last row adds the tint's color.
+        #[rustfmt::skip]
+        let color_matrix: [f32; 20] = [
+            0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, color.a,
+            color.r, color.g, color.b, 0.0,
+        ];
+        let color_matrix_bytes: &[u8] =
+            std::slice::from_raw_parts(color_matrix.as_ptr() as *const u8, size_of::<[f32; 20]>());
+
+        tint_effect
+            .SetValue(
+                D2D1_COLORMATRIX_PROP_COLOR_MATRIX.0 as u32,
+                D2D1_PROPERTY_TYPE_MATRIX_5X4,
+                color_matrix_bytes,
+            )
+            .context("tint_effect.SetValue()")?;
+
+        Ok(tint_effect)
+    }
+}
+
+/// Composites the given effects and clips the result to the inside of the mask.
+///
+/// The mask covers the area inside the border, so this is used for effects like `InnerGlow` that
+/// should only be visible within the window's edge.
+///
+/// # Arguments
+///
+/// * `d2d_context` - A reference to the Direct2D device context.
+/// * `effects` - The effects to composite.
+/// * `mask_bitmap` - A reference to the bitmap used as a mask.
+///
+/// # Returns
+///
+/// * `Ok(ID2D1Effect)` if the masked effect is created successfully.
+/// * `Err(anyhow::Error)` if there is an error during creation.
+fn create_masked_effect(
+    d2d_context: &ID2D1DeviceContext7,
+    effects: &[ID2D1Effect],
+    mask_bitmap: &ID2D1Bitmap1,
+) -> anyhow::Result<ID2D1Effect> {
+    unsafe {
+        let composite_effect = d2d_context
+            .CreateEffect(&CLSID_D2D1Composite)
+            .context("inner_composite_effect")?;
+        composite_effect
+            .SetInputCount(effects.len() as u32)
+            .context("could not set inner composite effect input count")?;
+
+        for (index, effect) in effects.iter().enumerate() {
+            composite_effect.SetInput(
+                index as u32,
+                &effect
+                    .GetOutput()
+                    .context(format!("could not get inner effect output: {}", index))?,
+                false,
+            );
+        }
+
+        // SOURCE_IN keeps the source (the effects) only where the destination (the mask) is opaque
+        let masked_effect = d2d_context
+            .CreateEffect(&CLSID_D2D1Composite)
+            .context("masked_effect")?;
+        masked_effect
+            .SetValue(
+                D2D1_COMPOSITE_PROP_MODE.0 as u32,
+                D2D1_PROPERTY_TYPE_ENUM,
+                &D2D1_COMPOSITE_MODE_SOURCE_IN.0.to_le_bytes(),
+            )
+            .context("masked_effect.SetValue() mode")?;
+        masked_effect.SetInput(0, mask_bitmap, false);
+        masked_effect.SetInput(
+            1,
+            &composite_effect
+                .GetOutput()
+                .context("could not get inner_composite_effect output")?,
+            false,
+        );
+
+        Ok(masked_effect)
+    }
+}
+
+/// Creates an effect that spreads the border outward by a different amount on each edge.
+///
+/// Top and bottom edges are blurred vertically and left and right edges horizontally using
+/// directional blurs. Each blur is cropped to the part of the border bitmap closest to its edge so
+/// the edges don't bleed into each other, and the results are composited into a single effect.
+///
+/// This is considerably more expensive than a single Gaussian blur, so it is only used when an
+/// effect explicitly configures a spread.
+///
+/// # Arguments
+///
+/// * `d2d_context` - A reference to the Direct2D device context.
+/// * `border_bitmap` - A reference to the bitmap representing the border.
+/// * `spread` - The standard deviation to use for each edge.
+///
+/// # Returns
+///
+/// * `Ok(ID2D1Effect)` if the spread effect is created successfully.
+/// * `Err(anyhow::Error)` if there is an error during creation.
+fn create_spread_effect(
+    d2d_context: &ID2D1DeviceContext7,
+    border_bitmap: &ID2D1Bitmap1,
+    spread: &EffectSpread,
+) -> anyhow::Result<ID2D1Effect> {
+    unsafe {
+        let size = border_bitmap.GetSize();
+        let (width, height) = (size.width, size.height);
+        let (half_width, half_height) = (width / 2.0, height / 2.0);
+
+        // The crop regions must not overlap, or the overlapping pixels are drawn twice. Each
+        // corner is a square split in half between its two edges, and the rest of the bitmap goes
+        // to the closest edge. One of each edge's inner regions is empty, depending on whether the
+        // bitmap is wider or taller.
+        let corner = half_width.min(half_height);
+        let half_corner = corner / 2.0;
+        let rect = |left: f32, top: f32, right: f32, bottom: f32| D2D_VECTOR_4F {
+            x: left,
+            y: top,
+            z: right,
+            w: bottom,
+        };
+
+        // (standard deviation, blur angle in degrees, crop regions as left/top/right/bottom)
+        let edges = [
+            (
+                spread.top,
+                90.0f32,
+                [
+                    rect(0.0, 0.0, width, half_corner),
+                    rect(corner, half_corner, width - corner, half_height),
+                ],
+            ),
+            (
+                spread.bottom,
+                90.0,
+                [
+                    rect(0.0, height - half_corner, width, height),
+                    rect(corner, half_height, width - corner, height - half_corner),
+                ],
+            ),
+            (
+                spread.left,
+                0.0,
+                [
+                    rect(0.0, half_corner, corner, height - half_corner),
+                    rect(corner, corner, half_width, height - corner),
+                ],
+            ),
+            (
+                spread.right,
+                0.0,
+                [
+                    rect(width - corner, half_corner, width, height - half_corner),
+                    rect(half_width, corner, width - corner, height - corner),
+                ],
+            ),
+        ];
+
+        let composite_effect = d2d_context
+            .CreateEffect(&CLSID_D2D1Composite)
+            .context("spread_composite_effect")?;
+        let crop_rect_count = edges
+            .iter()
+            .map(|(_, _, crop_rects)| crop_rects.len())
+            .sum::<usize>();
+        composite_effect
+            .SetInputCount(crop_rect_count as u32)
+            .context("could not set spread composite effect input count")?;
+
+        let mut input_index = 0;
+        for (standard_deviation, angle, crop_rects) in edges.iter() {
+            let blur_effect = d2d_context
+                .CreateEffect(&CLSID_D2D1DirectionalBlur)
+                .context("directional_blur_effect")?;
+            blur_effect.SetInput(0, border_bitmap, false);
+            blur_effect
+                .SetValue(
+                    D2D1_DIRECTIONALBLUR_PROP_STANDARD_DEVIATION.0 as u32,
+                    D2D1_PROPERTY_TYPE_FLOAT,
+                    &standard_deviation.to_le_bytes(),
+                )
+                .context("directional_blur_effect.SetValue() std deviation")?;
+            blur_effect
+                .SetValue(
+                    D2D1_DIRECTIONALBLUR_PROP_ANGLE.0 as u32,
+                    D2D1_PROPERTY_TYPE_FLOAT,
+                    &angle.to_le_bytes(),
+                )
+                .context("directional_blur_effect.SetValue() angle")?;
+            blur_effect
+                .SetValue(
+                    D2D1_DIRECTIONALBLUR_PROP_OPTIMIZATION.0 as u32,
+                    D2D1_PROPERTY_TYPE_ENUM,
+                    &D2D1_DIRECTIONALBLUR_OPTIMIZATION_SPEED.0.to_le_bytes(),
+                )
+                .context("directional_blur_effect.SetValue() optimization")?;
+
+            let blur_output = blur_effect
+                .GetOutput()
+                .context("could not get directional_blur_effect output")?;
+
+            for crop_rect in crop_rects {
+                let crop_effect = d2d_context
+                    .CreateEffect(&CLSID_D2D1Crop)
+                    .context("crop_effect")?;
+                crop_effect.SetInput(0, &blur_output, false);
+                let crop_rect_bytes: &[u8] = std::slice::from_raw_parts(
+                    crop_rect as *const D2D_VECTOR_4F as *const u8,
+                    size_of::<D2D_VECTOR_4F>(),
+                );
+                crop_effect
+                    .SetValue(
+                        D2D1_CROP_PROP_RECT.0 as u32,
+                        D2D1_PROPERTY_TYPE_VECTOR4,
+                        crop_rect_bytes,
+                    )
+                    .context("crop_effect.SetValue()")?;
+
+                composite_effect.SetInput(
+                    input_index,
+                    &crop_effect
+                        .GetOutput()
+                        .context("could not get crop_effect output")?,
+                    false,
+                );
+                input_index += 1;
+            }
+        }
+
+        Ok(composite_effect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::value::Value;
+    use crate::effect::EffectConfig;
+
+    fn glow_config(enabled: bool) -> EffectsConfig {
+        let glow = EffectConfig {
+            kind: "glow".to_string(),
+            standard_deviation: Some(Value::Number(8.0)),
+            opacity: 1.0,
+            translation: Default::default(),
+            spread: None,
+            color: None,
+        };
+
+        EffectsConfig {
+            active: vec![glow.clone()],
+            inactive: vec![glow],
+            enabled,
+        }
+    }
+
+    #[test]
+    fn disabled_window_rule_overrides_global_effects() {
+        let global = EffectManager::try_from(glow_config(true)).unwrap();
+        let window_rule = Some(EffectManager::try_from(glow_config(false)).unwrap());
+
+        let effect_manager = EffectManager::for_window_rule(window_rule.as_ref(), &global);
+
+        assert!(global.is_enabled());
+        assert!(global.window_padding() > 0);
+        assert!(!effect_manager.is_enabled());
+        assert_eq!(effect_manager.window_padding(), 0);
+    }
+}
@@ -73,6 +73,41 @@ pub struct EffectConfig {
     /// in various directions (e.g., translating a shadow effect).
     #[serde(default)]
     pub translation: EffectTranslationConfig,
+
+    /// Optional per-edge spread of the effect (advanced).
+    ///
+    /// When set, the effect is no longer a single uniform Gaussian blur. Instead, each edge of the
+    /// border is blurred separately with a directional blur using its own standard deviation, which
+    /// allows e.g. a stronger shadow along the bottom edge. Edges left unspecified fall back to
+    /// `standard_deviation`.
+    ///
+    /// This builds four blur effects instead of one, so it is noticeably more expensive to render
+    /// than the default uniform path. Leave it unset unless you need it.
+    #[serde(default)]
+    pub spread: Option<EffectSpreadConfig>,
+
+    /// Optional color of the effect (e.g., `"#000000"` for a black drop shadow under a white border).
+    ///
+    /// Accepts any solid color string, including color functions. When unset, glows use the border's
+    /// colors and shadows use Direct2D's default black.
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+/// Per-edge standard deviations for an effect.
+///
+/// Each value can be a number (e.g., `8`) or a string with units (e.g., `"8px"`).
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct EffectSpreadConfig {
+    /// Spread along the top edge.
+    pub top: Option<Value>,
+    /// Spread along the right edge.
+    pub right: Option<Value>,
+    /// Spread along the bottom edge.
+    pub bottom: Option<Value>,
+    /// Spread along the left edge.
+    pub left: Option<Value>,
 }
 
 /// Enum representing the configuration for translating an effect.
@@ -2,6 +2,7 @@ use regex::Regex;
 
 use crate::{user_config::UserConfig, windows_api::WindowsApi};
 use std::borrow::Cow;
+use std::collections::HashSet;
 
 use super::AsRefStrExt;
 use super::OStringExt;
@@ -60,6 +61,30 @@ fn user_config_dir() -> Option<XString> {
     config.try_into_string().map(|s| s.replace('/', "\\"))
 }
 
+fn exe_dir() -> Option<XString> {
+    let exe = std::env::current_exe().ok()?;
+    exe.parent()?
+        .to_path_buf()
+        .try_into_string()
+        .map(|s| s.replace('/', "\\"))
+}
+
+/// Resolves the variables provided by tacky-borders itself, which take precedence over
+/// environment variables of the same name. Names are matched case-insensitively.
+///
+/// - `USERCONFIG` / `CONFIGDIR`: The directory containing the config file.
+/// - `EXEDIR`: The directory containing the running executable.
+///
+/// Returns `None` if `var_name` isn't a built-in or it couldn't be resolved, so the caller can
+/// fall back to the environment and the default value.
+fn builtin_var(var_name: &str) -> Option<XString> {
+    match var_name.to_ascii_uppercase().as_str() {
+        "USERCONFIG" | "CONFIGDIR" => user_config_dir(),
+        "EXEDIR" => exe_dir(),
+        _ => None,
+    }
+}
+
 pub fn env<SI>(input: &SI) -> anyhow::Result<Cow<str>>
 where
     SI: AsRef<str> + ?Sized,
@@ -87,8 +112,8 @@ where
 
             let lookup =
                 |var_name: &str, default: Option<&str>| -> anyhow::Result<Option<String>> {
-                    if var_name.eq_ignore_ascii_case("USERCONFIG") {
-                        return Ok(user_config_dir());
+                    if let Some(value) = builtin_var(var_name) {
+                        return Ok(Some(value));
                     }
                     match std::env::var(var_name) {
                         Ok(value) => Ok(Some(value)),
@@ -190,8 +215,8 @@ pub fn check_env<SI: AsRef<str> + ?Sized>(path: &SI) -> anyhow::Result<Cow<'stat
 
     // Your lookup closure remains unchanged
     let lookup = |var_name: &str, default: Option<&str>| -> Option<String> {
-        if var_name.eq_ignore_ascii_case("USERCONFIG") {
-            return user_config_dir();
+        if let Some(value) = builtin_var(var_name) {
+            return Some(value);
         }
         match std::env::var(var_name) {
             Ok(value) => Some(value),
@@ -224,16 +249,39 @@ pub fn check_env<SI: AsRef<str> + ?Sized>(path: &SI) -> anyhow::Result<Cow<'stat
     Ok(result.into_owned().as_ocow())
 }
 
+/// The maximum number of passes `resolve_env_vars` makes over its input.
+const MAX_EXPANSION_PASSES: usize = 8;
+
+/// Expands environment variables in `input`, including variables whose values reference other
+/// variables (e.g. `${FOO}` where `FOO=%BAR%`).
+///
+/// Expansion is repeated until the result stops changing, a previous result reappears (i.e. the
+/// variables reference each other in a cycle) or `MAX_EXPANSION_PASSES` is reached.
 pub fn resolve_env_vars<SI: AsRef<str> + ?Sized>(input: &SI) -> anyhow::Result<Cow<'static, str>> {
-    let base_input_str = input.as_ref();
-    let input_str = base_input_str.replace('/', "\\");
+    let mut result = expand_env_vars_once(input.as_ref())?.into_owned();
+    let mut seen = HashSet::from([result.clone()]);
+
+    for _ in 1..MAX_EXPANSION_PASSES {
+        let expanded = expand_env_vars_once(&result)?.into_owned();
+        if !seen.insert(expanded.clone()) {
+            // Either nothing changed or we're going around in a cycle
+            break;
+        }
+        result = expanded;
+    }
+
+    Ok(result.as_ocow())
+}
+
+fn expand_env_vars_once(input: &str) -> anyhow::Result<Cow<'static, str>> {
+    let input_str = input.replace('/', "\\");
 
     let mut result = String::with_capacity(input_str.len() * 2);
     let mut chars = input_str.char_indices().peekable();
 
     let lookup_var = |var_name: &str, default: Option<&str>| -> anyhow::Result<Option<String>> {
-        if var_name.eq_ignore_ascii_case("USERCONFIG") {
-            return Ok(user_config_dir());
+        if let Some(value) = builtin_var(var_name) {
+            return Ok(Some(value));
         }
         match std::env::var(var_name) {
             Ok(value) => Ok(Some(value)),
@@ -487,3 +535,91 @@ fn tilde<P: AsRef<str> + ?Sized>(path_user_input: &P) -> Cow<str> {
         p
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_variable_referencing_another_variable() {
+        // SAFETY: the variables are only used by this test
+        unsafe {
+            std::env::set_var("TACKY_TEST_NESTED_INNER", "borders");
+            std::env::set_var("TACKY_TEST_NESTED_OUTER", "%TACKY_TEST_NESTED_INNER%");
+        }
+
+        assert_eq!(
+            resolve_env_vars("${TACKY_TEST_NESTED_OUTER}\\config").unwrap(),
+            "borders\\config"
+        );
+        assert_eq!(
+            resolve_env_vars("$TACKY_TEST_NESTED_OUTER").unwrap(),
+            "borders"
+        );
+    }
+
+    #[test]
+    fn resolves_nested_variable_in_default_value() {
+        // SAFETY: the variables are only used by this test
+        unsafe {
+            std::env::set_var("TACKY_TEST_DEFAULT_INNER", "themes");
+        }
+
+        assert_eq!(
+            resolve_env_vars("${TACKY_TEST_DEFAULT_MISSING:%TACKY_TEST_DEFAULT_INNER%}").unwrap(),
+            "themes"
+        );
+        assert_eq!(
+            resolve_env_vars("%TACKY_TEST_DEFAULT_MISSING=$TACKY_TEST_DEFAULT_INNER%").unwrap(),
+            "themes"
+        );
+    }
+
+    #[test]
+    fn stops_expanding_cyclic_variables() {
+        // SAFETY: the variables are only used by this test
+        unsafe {
+            std::env::set_var("TACKY_TEST_CYCLE_A", "%TACKY_TEST_CYCLE_B%");
+            std::env::set_var("TACKY_TEST_CYCLE_B", "%TACKY_TEST_CYCLE_A%");
+        }
+
+        let resolved = resolve_env_vars("%TACKY_TEST_CYCLE_A%").unwrap();
+        assert!(resolved.starts_with("%TACKY_TEST_CYCLE_"));
+    }
+    #[test]
+    fn resolves_exedir_in_every_syntax() {
+        let exe_dir = exe_dir().unwrap();
+        let inputs = [
+            "%EXEDIR%",
+            "%exedir%",
+            "$ExeDir",
+            "${EXEDIR}",
+            "${EXEDIR:C:\\fallback}",
+            "%EXEDIR=C:\\fallback%",
+        ];
+
+        for input in inputs {
+            assert_eq!(env(input).unwrap(), exe_dir, "env({input})");
+            assert_eq!(check_env(input).unwrap(), exe_dir, "check_env({input})");
+            assert_eq!(
+                resolve_env_vars(input).unwrap(),
+                exe_dir,
+                "resolve_env_vars({input})"
+            );
+        }
+    }
+
+    #[test]
+    fn only_resolves_known_builtin_vars() {
+        let cases = [
+            ("EXEDIR", true),
+            ("ExeDir", true),
+            ("EXE_DIR", false),
+            ("TACKY_TEST_NOT_BUILTIN", false),
+        ];
+
+        for (name, is_builtin) in cases {
+            assert_eq!(builtin_var(name).is_some(), is_builtin, "{name}");
+        }
+    }
+}
@@ -2,6 +2,55 @@
 extern crate windows;
 
 use std::fmt::Debug;
+use std::path::PathBuf;
+
+/// Errors that callers may want to react to individually. Everything else is still reported
+/// through `anyhow`, which `TackyError` converts into with `?`.
+#[derive(Debug, thiserror::Error)]
+pub enum TackyError {
+    /// No config file was found in the config directory.
+    #[error("no config file found in {}", .0.display())]
+    ConfigNotFound(PathBuf),
+    /// The config file has an extension that isn't supported (or whose feature is disabled).
+    #[error("unsupported config format: {0}")]
+    UnsupportedConfigFormat(String),
+    /// The config file could not be read.
+    #[error("could not read {}", path.display())]
+    ConfigIo {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    /// The config file could not be deserialized.
+    #[error("failed to deserialize {format}")]
+    ConfigParse {
+        format: &'static str,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// A Direct2D, DXGI or DirectComposition call failed. `context` names the failed resource.
+    #[error("{context}")]
+    Direct2D {
+        context: &'static str,
+        #[source]
+        source: windows::core::Error,
+    },
+    /// The monitor handle doesn't refer to a connected monitor.
+    #[error("invalid monitor handle: {0:#x}")]
+    InvalidMonitor(isize),
+    /// The window handle doesn't refer to a window with a border.
+    #[error("no border found for window: {0:#x}")]
+    NoBorder(isize),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl TackyError {
+    /// Returns a closure that wraps a failed Direct2D call, for use with `map_err`.
+    pub fn direct2d(context: &'static str) -> impl FnOnce(windows::core::Error) -> Self {
+        move |source| Self::Direct2D { context, source }
+    }
+}
 
 pub trait LogIfErr {
     fn log_if_err(&self);
@@ -190,3 +239,48 @@ where
         })
     }
 }
+
+impl<T> LogIfErr for Result<T, TackyError>
+where
+    T: Debug,
+{
+    fn log_if_err(&self) {
+        if let Err(e) = self {
+            error!("{e:#}");
+        }
+    }
+
+    fn log_if_err_message_pretty(&self, message: &str, unknown: bool) {
+        if let Err(e) = self {
+            if unknown {
+                error!("{message}: {e:#?}");
+            } else {
+                error!("{message}: {e:#}");
+            }
+        }
+    }
+
+    fn log_if_err_message(&self, message: &str, unknown: bool) {
+        if let Err(e) = self {
+            if unknown {
+                error!("{message}: {e:?}");
+            } else {
+                error!("{message}: {e}");
+            }
+        }
+    }
+
+    fn map_err_with_log(self) -> Self {
+        self.map_err(|err| {
+            error!("{err:?}");
+            err
+        })
+    }
+
+    fn map_err_with_log_pretty(self) -> Self {
+        self.map_err(|err| {
+            error!("{err:#?}");
+            err
+        })
+    }
+}
@@ -0,0 +1,140 @@
+use crate::app_manager::AppManager;
+use crate::border_manager::Border;
+use crate::border_manager::window_borders;
+use crate::windows_api::PointerConversion;
+use crate::windows_api::WindowsApi;
+use schema_jsonrs::JsonSchema;
+use serde::Deserialize;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+/// The currently running game, if any.
+static GAME_MODE: LazyLock<Mutex<Option<GameModeState>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Suppresses borders while one of the configured applications runs fullscreen in the foreground.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameModeConfig {
+    /// Process names (with or without `.exe`) that trigger game mode when fullscreen and focused.
+    pub processes: Vec<String>,
+    /// Whether to suppress borders on every monitor or only on the game's monitor.
+    pub scope: GameModeScope,
+}
+
+/// Defines which borders are suppressed while game mode is active.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum GameModeScope {
+    /// Suppress all borders.
+    #[default]
+    Global,
+    /// Only suppress borders on the monitor the game is running on.
+    Monitor,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GameModeState {
+    window: isize,
+    monitor: isize,
+    scope: GameModeScope,
+}
+
+impl GameModeState {
+    fn suppresses(&self, hwnd: isize) -> bool {
+        match self.scope {
+            GameModeScope::Global => true,
+            GameModeScope::Monitor => {
+                WindowsApi::monitor_from_window(hwnd).0.as_int() == self.monitor
+            }
+        }
+    }
+}
+
+impl GameModeConfig {
+    fn matches_process(&self, hwnd: isize) -> bool {
+        let Ok(process_name) = WindowsApi::get_process_name(hwnd) else {
+            return false;
+        };
+
+        self.processes.iter().any(|process| {
+            let process = process.trim().to_ascii_lowercase();
+            let process = process.strip_suffix(".exe").unwrap_or(&process);
+            process.eq_ignore_ascii_case(&process_name)
+        })
+    }
+}
+
+/// Returns whether the border of the given window is currently suppressed by game mode.
+pub fn is_suppressed(hwnd: isize) -> bool {
+    GAME_MODE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .is_some_and(|state| state.suppresses(hwnd))
+}
+
+/// Returns whether the given window is the game that started game mode.
+///
+/// This only compares the window handle, so it is cheap enough to call on every location change.
+pub fn is_game_window(hwnd: isize) -> bool {
+    GAME_MODE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .is_some_and(|state| state.window == hwnd)
+}
+
+/// Re-evaluates game mode for the current foreground window.
+///
+/// This should be called whenever the foreground window changes, and on location changes of the
+/// game window (see `is_game_window`) so game mode stops once the game leaves fullscreen.
+pub fn update_game_mode(active_window: isize) {
+    let config = AppManager::get().config().game_mode.clone();
+    let mut game_mode = GAME_MODE.lock().unwrap_or_else(|e| e.into_inner());
+
+    if let Some(state) = *game_mode {
+        // Still the same game in fullscreen, so there's nothing to do.
+        if state.window == active_window
+            && !config.processes.is_empty()
+            && WindowsApi::is_window_fullscreen(active_window)
+        {
+            return;
+        }
+
+        *game_mode = None;
+        drop(game_mode);
+
+        info!("[update_game_mode] Game Mode: Stopped");
+        // Show the borders again. Their hidden state is re-evaluated once the message arrives.
+        let tracking_windows: Vec<isize> = window_borders().keys().copied().collect();
+        for tracking_window in tracking_windows {
+            if WindowsApi::is_window_visible_on_screen(tracking_window) {
+                Border::show(tracking_window);
+            }
+        }
+
+        game_mode = GAME_MODE.lock().unwrap_or_else(|e| e.into_inner());
+    }
+
+    if config.processes.is_empty()
+        || !WindowsApi::is_window_fullscreen(active_window)
+        || !config.matches_process(active_window)
+    {
+        return;
+    }
+
+    let state = GameModeState {
+        window: active_window,
+        monitor: WindowsApi::monitor_from_window(active_window).0.as_int(),
+        scope: config.scope,
+    };
+    *game_mode = Some(state);
+    drop(game_mode);
+
+    info!("[update_game_mode] Game Mode: Started ({:?})", state.scope);
+
+    let tracking_windows: Vec<isize> = window_borders().keys().copied().collect();
+    for tracking_window in tracking_windows {
+        if state.suppresses(tracking_window) {
+            Border::hide(tracking_window);
+        }
+    }
+}
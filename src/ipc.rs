@@ -0,0 +1,335 @@
+//! A named-pipe server that lets other tools control tacky-borders at runtime.
+//!
+//! Clients connect to `\\.\pipe\tacky-borders` and send one command per line. Each command is
+//! answered with a line containing either `ok` or `error: <message>`, or the result of a query.
+//!
+//! Supported commands:
+//! - `reload`: Reloads the configuration file.
+//! - `open-config`: Opens the configuration file in the default editor.
+//! - `open-logs`: Opens the log file in the default editor.
+//! - `exit`: Exits the application.
+//! - `toggle-borders`: Hides all borders, or shows them again.
+//! - `toggle-window-border`: Toggles the border of the foreground window.
+//! - `pause-animations`: Pauses all animations, or resumes them.
+//! - `next-theme` / `prev-theme`: Switches to the next or previous theme in `themes`.
+//! - `set-active-color <color>`: Changes the global active color until the next reload.
+//! - `flash`: Flashes the border of the foreground window in the configured `flash_color`.
+//! - `status`: Answers with a JSON array describing every border (requires the `json` feature).
+
+use crate::app_manager::AppManager;
+use crate::border_manager::border_statuses;
+use crate::border_manager::flash_window;
+use crate::border_manager::reload_borders;
+use crate::colors::GlobalColor;
+use crate::colors::GlobalColorImpl;
+use crate::error::LogIfErr;
+use crate::sys_tray::SystemTrayEvent;
+use crate::windows_api::WM_APP_IPC_EVENT;
+use crate::windows_api::WindowsApi;
+use anyhow::Context;
+use anyhow::anyhow;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::thread::JoinHandle;
+use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::Foundation::ERROR_PIPE_CONNECTED;
+use windows::Win32::Foundation::GetLastError;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::Storage::FileSystem::CreateFileW;
+use windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_NORMAL;
+use windows::Win32::Storage::FileSystem::FILE_GENERIC_READ;
+use windows::Win32::Storage::FileSystem::FILE_SHARE_NONE;
+use windows::Win32::Storage::FileSystem::OPEN_EXISTING;
+use windows::Win32::Storage::FileSystem::PIPE_ACCESS_DUPLEX;
+use windows::Win32::Storage::FileSystem::ReadFile;
+use windows::Win32::Storage::FileSystem::WriteFile;
+use windows::Win32::System::Pipes::ConnectNamedPipe;
+use windows::Win32::System::Pipes::CreateNamedPipeW;
+use windows::Win32::System::Pipes::DisconnectNamedPipe;
+use windows::Win32::System::Pipes::PIPE_READMODE_BYTE;
+use windows::Win32::System::Pipes::PIPE_REJECT_REMOTE_CLIENTS;
+use windows::Win32::System::Pipes::PIPE_TYPE_BYTE;
+use windows::Win32::System::Pipes::PIPE_WAIT;
+use windows::Win32::System::Pipes::WaitNamedPipeW;
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::WindowsAndMessaging::PostThreadMessageW;
+use windows::core::PCWSTR;
+use windows::core::w;
+
+const PIPE_NAME: PCWSTR = w!(r"\\.\pipe\tacky-borders");
+const PIPE_BUFFER_SIZE: u32 = 4096;
+
+/// How often `IpcServer::stop` tries to connect to the pipe to wake up the server thread, e.g.
+/// while a client is still connected.
+const STOP_CONNECT_ATTEMPTS: u32 = 10;
+
+/// How long `IpcServer::stop` waits for the pipe to become available between attempts.
+const STOP_CONNECT_TIMEOUT_MS: u32 = 100;
+
+/// Background thread serving the named pipe, see `start_ipc_server`.
+#[derive(Debug)]
+pub struct IpcServer {
+    thread: JoinHandle<()>,
+    stopping: Arc<AtomicBool>,
+}
+
+impl IpcServer {
+    /// Stops serving commands and joins the server thread.
+    ///
+    /// The thread is blocked waiting for a client, so we wake it up by connecting to the pipe
+    /// ourselves. If another client stays connected, the thread is left to exit once it
+    /// disconnects.
+    pub fn stop(self) -> anyhow::Result<()> {
+        self.stopping.store(true, Ordering::SeqCst);
+
+        if !wake_server_thread(&self.thread) {
+            warn!("ipc server is still serving a client; it will stop once the client disconnects");
+            return Ok(());
+        }
+
+        self.thread
+            .join()
+            .map_err(|_| anyhow!("ipc server thread panicked"))
+    }
+}
+
+/// Connects to the pipe and disconnects right away, so the server thread stops waiting for a
+/// client. Returns `false` if the pipe stayed busy.
+fn wake_server_thread(thread: &JoinHandle<()>) -> bool {
+    for _ in 0..STOP_CONNECT_ATTEMPTS {
+        if thread.is_finished() {
+            return true;
+        }
+
+        let pipe = unsafe {
+            CreateFileW(
+                PIPE_NAME,
+                FILE_GENERIC_READ.0,
+                FILE_SHARE_NONE,
+                None,
+                OPEN_EXISTING,
+                FILE_ATTRIBUTE_NORMAL,
+                None,
+            )
+        };
+
+        match pipe {
+            Ok(pipe) => {
+                unsafe { CloseHandle(pipe) }.log_if_err();
+                return true;
+            }
+            // The pipe is busy with another client, or the thread is between two pipe instances
+            Err(_) => {
+                let _ = unsafe { WaitNamedPipeW(PIPE_NAME, STOP_CONNECT_TIMEOUT_MS) };
+            }
+        }
+    }
+
+    false
+}
+
+/// Starts the IPC server on a background thread.
+///
+/// Must be called from the thread running the application's message loop, because commands like
+/// `exit` are handed back to that thread as `WM_APP_IPC_EVENT` messages.
+pub fn start_ipc_server() -> anyhow::Result<IpcServer> {
+    let main_thread_id = unsafe { GetCurrentThreadId() };
+    let stopping = Arc::new(AtomicBool::new(false));
+
+    let thread = thread::Builder::new()
+        .name("ipc".to_string())
+        .spawn({
+            let stopping = stopping.clone();
+            move || {
+                while !stopping.load(Ordering::SeqCst) {
+                    if let Err(err) = serve_client(main_thread_id, &stopping) {
+                        error!("ipc server stopped: {err:#}");
+                        break;
+                    }
+                }
+            }
+        })
+        .context("could not spawn ipc thread")?;
+
+    info!("ipc server listening on \\\\.\\pipe\\tacky-borders");
+
+    Ok(IpcServer { thread, stopping })
+}
+
+/// Waits for a client to connect, then handles its commands until it disconnects.
+fn serve_client(main_thread_id: u32, stopping: &AtomicBool) -> anyhow::Result<()> {
+    let pipe = unsafe {
+        CreateNamedPipeW(
+            PIPE_NAME,
+            PIPE_ACCESS_DUPLEX,
+            PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+            1,
+            PIPE_BUFFER_SIZE,
+            PIPE_BUFFER_SIZE,
+            0,
+            None,
+        )
+    };
+    if pipe.is_invalid() {
+        return Err(anyhow!("could not create named pipe: {:?}", unsafe {
+            GetLastError()
+        }));
+    }
+
+    // ERROR_PIPE_CONNECTED means the client connected before we started waiting
+    match unsafe { ConnectNamedPipe(pipe, None) } {
+        Err(err) if err.code() != ERROR_PIPE_CONNECTED.to_hresult() => {
+            unsafe { CloseHandle(pipe) }.log_if_err();
+            return Err(err).context("could not connect named pipe");
+        }
+        _ => {}
+    }
+
+    // Once stopping, the client is most likely `IpcServer::stop` waking us up
+    if !stopping.load(Ordering::SeqCst) {
+        read_commands(pipe, main_thread_id, stopping);
+    }
+
+    unsafe {
+        DisconnectNamedPipe(pipe).log_if_err();
+        CloseHandle(pipe).log_if_err();
+    }
+
+    Ok(())
+}
+
+/// Reads and answers line-based commands until the client disconnects.
+fn read_commands(pipe: HANDLE, main_thread_id: u32, stopping: &AtomicBool) {
+    let mut buffer = [0u8; PIPE_BUFFER_SIZE as usize];
+    let mut pending = String::new();
+
+    loop {
+        let mut bytes_read = 0;
+        if unsafe { ReadFile(pipe, Some(&mut buffer), Some(&mut bytes_read), None) }.is_err()
+            || bytes_read == 0
+        {
+            // The client disconnected
+            return;
+        }
+
+        pending.push_str(&String::from_utf8_lossy(&buffer[..bytes_read as usize]));
+
+        while let Some(end) = pending.find('\n') {
+            let line = pending[..end].trim().to_string();
+            pending.drain(..=end);
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if stopping.load(Ordering::SeqCst) {
+                return;
+            }
+
+            let response = match handle_command(&line, main_thread_id) {
+                Ok(None) => "ok\n".to_string(),
+                Ok(Some(answer)) => format!("{answer}\n"),
+                Err(err) => {
+                    warn!("ipc command '{line}' failed: {err:#}");
+                    format!("error: {err:#}\n")
+                }
+            };
+
+            if unsafe { WriteFile(pipe, Some(response.as_bytes()), None, None) }.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Executes a command. Queries return their answer, other commands `None`.
+fn handle_command(line: &str, main_thread_id: u32) -> anyhow::Result<Option<String>> {
+    let (command, argument) = match line.split_once(char::is_whitespace) {
+        Some((command, argument)) => (command, argument.trim()),
+        None => (line, ""),
+    };
+
+    match command.to_ascii_lowercase().as_str() {
+        "status" => return status().map(Some),
+        "set-active-color" => set_active_color(argument),
+        "reload" => post_event(SystemTrayEvent::ReloadConfig, main_thread_id),
+        "flash" => flash_foreground_window(),
+        // Every other command is named after its tray event, e.g. `open-config`
+        command => {
+            let event = SystemTrayEvent::from_str(&command.replace('-', "_"))
+                .map_err(|_| anyhow!("unknown command '{command}'"))?;
+            post_event(event, main_thread_id)
+        }
+    }?;
+
+    Ok(None)
+}
+
+/// Describes every border as a single line of JSON.
+#[cfg(feature = "json")]
+fn status() -> anyhow::Result<String> {
+    serde_jsonc2::to_string(&border_statuses()).context("could not serialize border status")
+}
+
+#[cfg(not(feature = "json"))]
+fn status() -> anyhow::Result<String> {
+    Err(anyhow!("the status command requires the json feature"))
+}
+
+/// Hands the event to the main thread, which executes it like a tray menu or keybinding event.
+fn post_event(event: SystemTrayEvent, main_thread_id: u32) -> anyhow::Result<()> {
+    let event = Box::into_raw(Box::new(event));
+
+    if let Err(err) = unsafe {
+        PostThreadMessageW(
+            main_thread_id,
+            WM_APP_IPC_EVENT,
+            WPARAM(0),
+            LPARAM(event as isize),
+        )
+    } {
+        // The message was never queued, so the main thread won't free the event
+        drop(unsafe { Box::from_raw(event) });
+        return Err(err).context("could not post event to the main thread");
+    }
+
+    Ok(())
+}
+
+/// Executes an event posted by `post_event`. Called by the main thread's message loop.
+pub fn execute_posted_event(lparam: LPARAM) {
+    let event = unsafe { Box::from_raw(lparam.0 as *mut SystemTrayEvent) };
+    debug!("ipc: executing {event}");
+    event.execute();
+}
+
+fn flash_foreground_window() -> anyhow::Result<()> {
+    if AppManager::get().config().global_rule.flash_color.is_none() {
+        return Err(anyhow!("no flash_color is configured"));
+    }
+
+    flash_window(WindowsApi::get_foreground_window())
+}
+
+fn set_active_color(color: &str) -> anyhow::Result<()> {
+    if color.is_empty() {
+        return Err(anyhow!("expected a color, e.g. 'set-active-color #89b4fa'"));
+    }
+
+    let color = GlobalColor::String(color.to_string());
+    color.to_color().context("invalid color")?;
+
+    let app_manager = AppManager::get();
+    let mut config = app_manager.config().clone();
+    config.global_rule.active_color = color;
+    app_manager.set_config(config);
+
+    reload_borders();
+
+    Ok(())
+}
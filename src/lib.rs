@@ -9,14 +9,28 @@ extern crate tacky_borders_logger;
 use anyhow::anyhow;
 use app_manager::AppManager;
 use border_manager::Border;
+pub use border_manager::ForcedState;
+use border_manager::destroy_all_borders;
+pub use border_manager::force_window_state;
 use border_manager::register_border_class;
+use border_manager::set_active_window;
+use border_manager::unregister_border_class;
+use border_manager::wait_for_border_threads;
+pub use cli::run_cli_command;
 use core::keybindings::KeybindingConfig;
 pub use env::check_env;
 pub use env::env;
 pub use env::resolve_env_vars;
 use error::LogIfErr;
+use ipc::IpcServer;
+use ipc::execute_posted_event;
+use ipc::start_ipc_server;
 use keyboard_hook::KEYBOARD_HOOK;
 use keyboard_hook::KeyboardHook;
+use power_monitor::PowerMonitor;
+use power_monitor::start_power_monitor;
+use shell_hook::ShellHook;
+use shell_hook::start_shell_hook;
 use sp_log2::ColorChoice;
 use sp_log2::CombinedLogger;
 use sp_log2::ConfigBuilder;
@@ -25,27 +39,37 @@ use sp_log2::Format;
 use sp_log2::LevelFilter;
 use sp_log2::TermLogger;
 use sp_log2::TerminalMode;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::OnceLock;
 use sys_tray::SystemTray;
+use user_config::LogLevel;
 use user_config::UserConfig;
 use window_event_hook::WIN_EVENT_HOOK;
 use window_event_hook::WindowEventHook;
 use windows::Win32::Foundation::GetLastError;
 use windows::Win32::UI::WindowsAndMessaging::MSG;
 use windows::Win32::UI::WindowsAndMessaging::WM_QUIT;
+use windows_api::WM_APP_IPC_EVENT;
 use windows_api::WindowsApi;
 
 mod animation;
 mod app_manager;
 mod border_manager;
+mod cli;
 mod colors;
 mod config_watcher;
 mod core;
 mod effect;
 mod env;
 mod error;
+mod game_mode;
+mod ipc;
 mod keyboard_hook;
 mod parsed_config;
+mod power_monitor;
 mod render_resources;
+mod shell_hook;
 mod sys_tray;
 mod theme_manager;
 mod user_config;
@@ -53,35 +77,144 @@ mod window_event_hook;
 pub mod windows_api;
 mod windows_callback;
 
-pub fn start_application() -> anyhow::Result<()> {
-    if !WindowsApi::imm_disable_ime().as_bool() {
-        error!("could not disable ime!");
+/// A running instance of tacky-borders, for embedding border management in another application.
+///
+/// The thread calling [`BordersHandle::start`] owns the hooks and the tray icon, so it must run a
+/// message loop and pass each message to [`BordersHandle::handle_message`] before dispatching it.
+/// The window event and keyboard hooks can only be installed once per process, so a stopped handle
+/// can't be started again.
+pub struct BordersHandle {
+    window_event_hook: Arc<WindowEventHook>,
+    /// `None` if `keybindings_enabled` is off.
+    keyboard_hook: Option<Arc<KeyboardHook>>,
+    _sys_tray: Option<SystemTray>,
+    power_monitor: Option<PowerMonitor>,
+    shell_hook: Option<ShellHook>,
+    /// `None` if `ipc_enabled` is off.
+    ipc_server: Option<IpcServer>,
+}
+
+impl BordersHandle {
+    /// Installs the hooks and creates borders for all existing windows.
+    pub fn start() -> anyhow::Result<Self> {
+        if !WindowsApi::imm_disable_ime().as_bool() {
+            error!("could not disable ime!");
+        }
+
+        WindowsApi::set_process_dpi_awareness_context()
+            .log_if_err_message("could not make process dpi aware", false);
+
+        let config = AppManager::get().config().clone();
+        let window_event_hook = WindowEventHook::new().map_err_with_log()?;
+
+        // The tray menu and IPC commands keep working without the hook
+        let keyboard_hook = match config.keybindings_enabled {
+            true => {
+                let bindings = Vec::<KeybindingConfig>::from(&config.keybindings);
+                let keyboard_hook = KeyboardHook::new(&bindings).map_err_with_log()?;
+                keyboard_hook.start().log_if_err();
+                Some(keyboard_hook)
+            }
+            false => {
+                info!("keybindings are disabled; not installing the keyboard hook");
+                None
+            }
+        };
+
+        window_event_hook.start().log_if_err();
+
+        let sys_tray = SystemTray::new();
+        sys_tray.log_if_err_message_pretty("could not create tray icon", true);
+
+        register_border_class().log_if_err();
+
+        let power_monitor = start_power_monitor();
+        power_monitor.log_if_err_message("could not start power monitor", false);
+        let shell_hook = start_shell_hook();
+        shell_hook.log_if_err_message("could not start shell hook", false);
+
+        let ipc_server = match config.ipc_enabled {
+            true => {
+                let ipc_server = start_ipc_server();
+                ipc_server.log_if_err_message("could not start ipc server", false);
+                ipc_server.ok()
+            }
+            false => None,
+        };
+
+        // The active window is seeded when the AppManager is created, but focus may have moved
+        // before the event hook was started. Re-seed it so the borders created below reflect the
+        // real foreground window from their first paint.
+        set_active_window(WindowsApi::get_foreground_window());
+
+        WindowsApi::process_window_handles(&Border::create).log_if_err();
+
+        debug!("Application: Started");
+
+        Ok(Self {
+            window_event_hook,
+            keyboard_hook,
+            _sys_tray: sys_tray.ok(),
+            power_monitor: power_monitor.ok(),
+            shell_hook: shell_hook.ok(),
+            ipc_server,
+        })
     }
 
-    WindowsApi::set_process_dpi_awareness_context()
-        .log_if_err_message("could not make process dpi aware", false);
+    /// Handles the thread messages tacky-borders posts to the thread that started it, like IPC
+    /// commands. Returns `true` if the message was handled and must not be dispatched.
+    pub fn handle_message(&self, message: &MSG) -> bool {
+        // Thread messages have no window to be dispatched to
+        if message.message == WM_APP_IPC_EVENT {
+            execute_posted_event(message.lParam);
+            return true;
+        }
 
-    let config = AppManager::get().config().clone();
-    let bindings = Vec::<KeybindingConfig>::from(&config.keybindings);
-    let window_event_hook = WindowEventHook::new().map_err_with_log()?;
-    let keyboard_hook = KeyboardHook::new(&bindings).map_err_with_log()?;
+        false
+    }
 
-    keyboard_hook.start().log_if_err();
-    window_event_hook.start().log_if_err();
+    /// Unhooks the hooks, stops the IPC server, destroys all borders and the hidden windows, and
+    /// unregisters their window classes. Must be called from the thread that started the handle.
+    pub fn stop(self) -> anyhow::Result<()> {
+        debug!("Application: Stopping");
 
-    let sys_tray = SystemTray::new();
-    sys_tray.log_if_err_message_pretty("could not create tray icon", true);
+        if let Some(keyboard_hook) = self.keyboard_hook {
+            keyboard_hook.stop().log_if_err();
+        }
+        self.window_event_hook.stop().log_if_err();
+        AppManager::get().stop_config_watcher();
 
-    register_border_class().log_if_err();
+        if let Some(ipc_server) = self.ipc_server {
+            ipc_server.stop().log_if_err();
+        }
+        if let Some(shell_hook) = self.shell_hook {
+            shell_hook.stop().log_if_err();
+        }
+        if let Some(power_monitor) = self.power_monitor {
+            power_monitor.stop().log_if_err();
+        }
 
-    WindowsApi::process_window_handles(&Border::create).log_if_err();
+        destroy_all_borders()?;
+        wait_for_border_threads()?;
+        unregister_border_class()?;
 
-    debug!("Application: Started");
+        debug!("Application: Stopped");
+
+        Ok(())
+    }
+}
+
+pub fn start_application() -> anyhow::Result<()> {
+    let handle = BordersHandle::start()?;
 
     let mut message = MSG::default();
     loop {
         // Get the next message from the message queue
         if WindowsApi::get_message_w(&mut message, None, 0, 0).as_bool() {
+            if handle.handle_message(&message) {
+                continue;
+            }
+
             // Translate and dispatch the message
             let _ = WindowsApi::translate_message(&message);
             WindowsApi::dispatch_message_w(&message);
@@ -95,6 +228,8 @@ pub fn start_application() -> anyhow::Result<()> {
         }
     }
 
+    handle.stop().log_if_err();
+
     debug!("Application: Shut Down");
 
     Ok(())
@@ -115,9 +250,25 @@ pub fn exit_application() {
     WindowsApi::post_quit_message(0);
 }
 
+/// Path of the log file, set once `initialize_logger` succeeded.
+static LOG_FILE_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Opens the log file in the default editor, or shows an error if nothing is logged to a file.
+pub fn open_log_file() {
+    match LOG_FILE_PATH.get() {
+        Some(log_path) => win_open::that(log_path).log_if_err(),
+        None => WindowsApi::show_error_dialog("Logs", "Logging to a file is not enabled"),
+    }
+}
+
+/// Sets the most verbose level of messages that reach the terminal and the log file.
+pub fn set_log_level(level: LevelFilter) {
+    log::set_max_level(level);
+}
+
 pub fn initialize_logger() -> anyhow::Result<()> {
-    let log_path = UserConfig::get_config_dir()?.join("tacky-borders.log");
-    let Some(log_path) = log_path.to_str() else {
+    let log_file_path = UserConfig::get_config_dir()?.join("tacky-borders.log");
+    let Some(log_path) = log_file_path.to_str() else {
         return Err(anyhow!("could not convert log_path to str"));
     };
 
@@ -139,18 +290,24 @@ pub fn initialize_logger() -> anyhow::Result<()> {
 
     CombinedLogger::init(vec![
         TermLogger::new(
-            LevelFilter::Debug,
+            LevelFilter::Trace,
             config.clone(),
             TerminalMode::Mixed,
             ColorChoice::Auto,
         ),
         FileLogger::new(
-            LevelFilter::Info,
+            LevelFilter::Trace,
             config.clone(),
             log_path,
             Some(1024 * 1024),
         ),
     ])?;
 
+    // Both loggers accept everything, the actual level is set by `log_level` once the config is
+    // loaded
+    set_log_level(LogLevel::default().into());
+
+    let _ = LOG_FILE_PATH.set(log_file_path);
+
     Ok(())
 }
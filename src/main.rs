@@ -9,10 +9,15 @@ extern crate sp_log2;
 extern crate tacky_borders_logger;
 
 use tacky_borders::initialize_logger;
+use tacky_borders::run_cli_command;
 use tacky_borders::start_application;
 use tacky_borders::windows_api::WindowsApi;
 
 fn main() -> anyhow::Result<()> {
+    if let Some(exit_code) = run_cli_command() {
+        std::process::exit(exit_code);
+    }
+
     if let Err(e) = &initialize_logger() {
         error!("logger initialization failed: {e}");
     };
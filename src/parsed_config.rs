@@ -1,14 +1,34 @@
 use anyhow::Context;
+use anyhow::anyhow;
 use regex::Regex;
+use std::str::FromStr;
+use windows::Win32::UI::WindowsAndMessaging::{
+    WINDOW_EX_STYLE, WINDOW_STYLE, WS_BORDER, WS_CAPTION, WS_CHILD, WS_CLIPCHILDREN,
+    WS_CLIPSIBLINGS, WS_DISABLED, WS_DLGFRAME, WS_EX_ACCEPTFILES, WS_EX_APPWINDOW,
+    WS_EX_CLIENTEDGE, WS_EX_COMPOSITED, WS_EX_DLGMODALFRAME, WS_EX_LAYERED, WS_EX_LAYOUTRTL,
+    WS_EX_MDICHILD, WS_EX_NOACTIVATE, WS_EX_NOREDIRECTIONBITMAP, WS_EX_STATICEDGE,
+    WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_EX_TRANSPARENT, WS_EX_WINDOWEDGE, WS_HSCROLL, WS_MAXIMIZE,
+    WS_MAXIMIZEBOX, WS_MINIMIZE, WS_MINIMIZEBOX, WS_OVERLAPPEDWINDOW, WS_POPUP, WS_POPUPWINDOW,
+    WS_SYSMENU, WS_THICKFRAME, WS_VISIBLE, WS_VSCROLL,
+};
 
 use crate::{
     animation::manager::AnimationManager,
-    colors::{Color, GlobalColorImpl},
-    core::keybindings::Keybindings,
+    colors::{Color, GlobalColorImpl, InactiveColor},
+    core::{
+        helpers::GlobPattern,
+        keybindings::Keybindings,
+        value::{Value, ValueConversion},
+    },
     effect::manager::EffectManager,
+    game_mode::GameModeConfig,
+    render_resources::RenderConfig,
     theme_manager::ThemeManager,
     user_config::{
-        BorderStyle, GlobalRuleConfig, MatchKind, MatchStrategy, UserConfig, WindowRuleConfig,
+        BorderLayerConfig, BorderPlacement, BorderStyle, BorderWidthConfig, FocusTrailConfig,
+        ForcedCorners, GlobalRuleConfig, MatchConditionConfig, MatchKind, MatchStrategy,
+        MonitorOverrideConfig, StrokeKind, StrokeStyleConfig, UnminimizeDetection, UserConfig,
+        WindowRuleConfig,
     },
 };
 
@@ -19,12 +39,32 @@ pub struct ParsedConfig {
     pub global_rule: GlobalRule,
     /// Specific rules for individual windows.
     pub window_rules: Vec<WindowRule>,
+    /// Windows that never get a border, regardless of any window rule.
+    pub exclude: Vec<MatchCondition>,
+    /// Per-monitor overrides of the global and window rule settings.
+    pub monitors: Vec<MonitorOverride>,
+    /// Monitors that never get borders, by device name or display number.
+    pub disabled_monitors: Vec<String>,
     /// Application keybindings.
     pub keybindings: Keybindings,
     /// Enables monitoring for changes in the configuration file.
     pub monitor_config_changes: bool,
     /// Enable custom predefined theme
     pub theme: ThemeManager,
+    /// Rendering backend settings shared by all borders.
+    pub render: RenderConfig,
+    /// Suppresses borders while a configured application runs fullscreen.
+    pub game_mode: GameModeConfig,
+    /// Recreates and repositions all borders when the system resumes or the display wakes up.
+    pub refresh_on_wake: bool,
+    /// Hides borders while their window covers its entire monitor, e.g. in borderless fullscreen.
+    pub hide_on_fullscreen: bool,
+    /// Draws borders on maximized windows, along the inside of the monitor's work area.
+    pub border_maximized: bool,
+    /// Runs animations on borders of inactive windows.
+    pub animate_inactive: bool,
+    /// Keeps borders above all other topmost windows.
+    pub always_on_top: bool,
 }
 
 impl TryFrom<UserConfig> for ParsedConfig {
@@ -37,13 +77,33 @@ impl TryFrom<UserConfig> for ParsedConfig {
             .iter()
             .map(|rule| WindowRule::try_from(rule.clone()))
             .collect::<Result<Vec<WindowRule>, _>>()?;
+        let exclude = value
+            .exclude
+            .into_iter()
+            .map(MatchCondition::try_from)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let monitors = value
+            .monitors
+            .into_iter()
+            .map(MonitorOverride::try_from)
+            .collect::<anyhow::Result<Vec<_>>>()?;
 
         Ok(Self {
             global_rule,
             window_rules,
+            exclude,
+            monitors,
+            disabled_monitors: value.disabled_monitors,
             keybindings: value.keybindings,
             monitor_config_changes: value.monitor_config_changes,
             theme: value.theme,
+            render: value.render,
+            game_mode: value.game_mode,
+            refresh_on_wake: value.refresh_on_wake,
+            hide_on_fullscreen: value.hide_on_fullscreen,
+            border_maximized: value.border_maximized,
+            animate_inactive: value.animate_inactive,
+            always_on_top: value.always_on_top,
         })
     }
 }
@@ -51,15 +111,23 @@ impl TryFrom<UserConfig> for ParsedConfig {
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct GlobalRule {
     /// Default width of the window borders.
-    pub border_width: i32,
+    pub border_width: BorderWidth,
     /// Default offset for the window borders.
-    pub border_offset: i32,
+    pub border_offset: BorderOffset,
     /// Default border radius settings.
     pub border_style: BorderStyle,
+    /// Default line pattern of the window borders.
+    pub stroke_style: StrokeStyle,
+    /// Where borders are drawn relative to their window's edge.
+    pub placement: BorderPlacement,
+    /// Corner shape used by the "auto" border style instead of each window's corner preference.
+    pub force_corners: Option<ForcedCorners>,
+    /// Extra strokes drawn behind the borders, from back to front.
+    pub layers: Vec<BorderLayer>,
     /// Default color for active window borders.
     pub active_color: Color,
     /// Default color for inactive window borders.
-    pub inactive_color: Color,
+    pub inactive_color: InactiveColor,
     /// Animation manager for borders.
     pub animation_manager: AnimationManager,
     /// Effect manager for borders.
@@ -68,6 +136,217 @@ pub struct GlobalRule {
     pub initialize_delay: u32,
     /// Delay (in milliseconds) before applying borders after unminimizing.
     pub unminimize_delay: u32,
+    /// How borders wait for their window to finish restoring.
+    pub unminimize_detection: UnminimizeDetection,
+    /// Time (in milliseconds) a window must stay visible before its border is shown.
+    pub creation_grace_period: u32,
+    /// Only show borders while the cursor hovers over their window.
+    pub hover_only: bool,
+    /// Fading highlight left on a border after its window loses focus.
+    pub focus_trail: Option<FocusTrail>,
+    /// Color a border flashes when its window requests attention.
+    pub flash_color: Option<Color>,
+    /// Duration (in milliseconds) of the fade from the flash color back to the border color.
+    pub flash_duration: u32,
+    /// Duration (in milliseconds) of the fade to a border's new color after a config reload.
+    pub color_transition_duration: u32,
+    /// Clip borders and effects to the bounds of the monitor their window is on.
+    pub clip_to_monitor: bool,
+    /// Opacity multiplier (0.0 to 1.0) applied to active colors.
+    pub active_opacity: f32,
+    /// Opacity multiplier (0.0 to 1.0) applied to inactive colors.
+    pub inactive_opacity: f32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FocusTrail {
+    /// Color the border starts from before fading to its inactive color.
+    pub color: Color,
+    /// Duration (in milliseconds) of the fade.
+    pub duration: u32,
+}
+
+impl TryFrom<FocusTrailConfig> for FocusTrail {
+    type Error = anyhow::Error;
+
+    fn try_from(value: FocusTrailConfig) -> Result<Self, Self::Error> {
+        Ok(Self {
+            color: value.color.to_color()?,
+            duration: value.duration,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BorderLayer {
+    /// Color of the layer while the window is active.
+    pub active_color: Color,
+    /// Color of the layer while the window is inactive.
+    pub inactive_color: Color,
+    /// Width of the layer in pixels, before DPI scaling.
+    pub width: u32,
+    /// Distance in pixels between the window's edge and the inner edge of the layer, before DPI
+    /// scaling.
+    pub offset: i32,
+    /// Line pattern of the layer.
+    pub stroke_style: StrokeStyle,
+}
+
+impl TryFrom<BorderLayerConfig> for BorderLayer {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BorderLayerConfig) -> Result<Self, Self::Error> {
+        let active_color = value.active_color.to_active_color()?;
+        let inactive_color = match value.inactive_color {
+            Some(color) => InactiveColor::try_from(&color)?.resolve(&active_color)?,
+            None => active_color.clone(),
+        };
+
+        Ok(Self {
+            active_color,
+            inactive_color,
+            width: value.width,
+            offset: value.offset,
+            stroke_style: StrokeStyle::try_from(value.stroke_style)?,
+        })
+    }
+}
+
+/// Width of each edge of a border in pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BorderWidth {
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+    pub left: i32,
+}
+
+impl BorderWidth {
+    pub fn uniform(width: i32) -> Self {
+        Self {
+            top: width,
+            right: width,
+            bottom: width,
+            left: width,
+        }
+    }
+
+    /// Returns the widest edge, which determines how much room the border window needs.
+    pub fn max(&self) -> i32 {
+        self.top.max(self.right).max(self.bottom).max(self.left)
+    }
+
+    pub fn is_uniform(&self) -> bool {
+        self.top == self.right && self.top == self.bottom && self.top == self.left
+    }
+
+    /// Scales every edge from 96 DPI to the given DPI.
+    pub fn scale(&self, dpi: f32) -> Self {
+        let scale = |width: i32| (width as f32 * dpi / 96.0).round() as i32;
+
+        Self {
+            top: scale(self.top),
+            right: scale(self.right),
+            bottom: scale(self.bottom),
+            left: scale(self.left),
+        }
+    }
+}
+
+impl TryFrom<BorderWidthConfig> for BorderWidth {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BorderWidthConfig) -> Result<Self, Self::Error> {
+        let parse = |value: Option<Value>| -> anyhow::Result<i32> {
+            match value {
+                Some(value) => value
+                    .as_length_i32()
+                    .ok_or_else(|| anyhow!("invalid border width: {value:?}")),
+                None => Ok(0),
+            }
+        };
+
+        match value {
+            BorderWidthConfig::Uniform(width) => Ok(Self::uniform(parse(Some(width))?)),
+            BorderWidthConfig::Edges(edges) => Ok(Self {
+                top: parse(edges.top)?,
+                right: parse(edges.right)?,
+                bottom: parse(edges.bottom)?,
+                left: parse(edges.left)?,
+            }),
+        }
+    }
+}
+
+/// Offset of a border relative to its window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BorderOffset {
+    /// An offset in pixels.
+    Pixels(i32),
+    /// A fraction of the window's smaller dimension, e.g. `0.05` for `"5%"`.
+    Percentage(f32),
+}
+
+impl Default for BorderOffset {
+    fn default() -> Self {
+        Self::Pixels(0)
+    }
+}
+
+impl BorderOffset {
+    /// Returns the offset in pixels for a window of the given size.
+    pub fn to_pixels(self, window_width: i32, window_height: i32) -> i32 {
+        match self {
+            Self::Pixels(offset) => offset,
+            Self::Percentage(fraction) => {
+                (fraction * window_width.min(window_height).max(0) as f32).round() as i32
+            }
+        }
+    }
+}
+
+impl TryFrom<Value> for BorderOffset {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        if let Some(fraction) = value.as_percentage_f32() {
+            return Ok(Self::Percentage(fraction));
+        }
+
+        value
+            .as_length_i32()
+            .map(Self::Pixels)
+            .ok_or_else(|| anyhow!("invalid border offset: {value:?}"))
+    }
+}
+
+/// Line pattern of a border, with dash lengths in pixels at 96 DPI.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StrokeStyle {
+    pub kind: StrokeKind,
+    pub dash: Option<f32>,
+    pub gap: Option<f32>,
+}
+
+impl TryFrom<StrokeStyleConfig> for StrokeStyle {
+    type Error = anyhow::Error;
+
+    fn try_from(value: StrokeStyleConfig) -> Result<Self, Self::Error> {
+        let parse = |name: &str, value: Option<Value>| -> anyhow::Result<Option<f32>> {
+            value
+                .map(|value| match value.as_length_f32() {
+                    Some(length) if length > 0.0 => Ok(length),
+                    _ => Err(anyhow!("invalid stroke {name} length: {value:?}")),
+                })
+                .transpose()
+        };
+
+        Ok(Self {
+            kind: value.kind,
+            dash: parse("dash", value.dash)?,
+            gap: parse("gap", value.gap)?,
+        })
+    }
 }
 
 impl TryFrom<GlobalRuleConfig> for GlobalRule {
@@ -76,8 +355,8 @@ impl TryFrom<GlobalRuleConfig> for GlobalRule {
     fn try_from(value: GlobalRuleConfig) -> Result<Self, Self::Error> {
         let animation_manager = AnimationManager::try_from(value.animations)?;
         let effect_manager = EffectManager::try_from(value.effects)?;
-        let active_color = value.active_color.to_color()?;
-        let inactive_color = value.inactive_color.to_color()?;
+        let active_color = value.active_color.to_active_color()?;
+        let inactive_color = InactiveColor::try_from(&value.inactive_color)?;
 
         Ok(Self {
             animation_manager,
@@ -85,10 +364,37 @@ impl TryFrom<GlobalRuleConfig> for GlobalRule {
             active_color,
             inactive_color,
             border_style: value.border_style,
-            border_width: value.border_width,
-            border_offset: value.border_offset,
-            initialize_delay: value.initialize_delay,
-            unminimize_delay: value.unminimize_delay,
+            stroke_style: StrokeStyle::try_from(value.stroke_style)?,
+            placement: value.placement,
+            force_corners: value.force_corners,
+            layers: value
+                .layers
+                .into_iter()
+                .map(BorderLayer::try_from)
+                .collect::<anyhow::Result<_>>()?,
+            border_width: BorderWidth::try_from(value.border_width)?,
+            border_offset: BorderOffset::try_from(value.border_offset)?,
+            initialize_delay: value
+                .initialize_delay
+                .as_duration_u32()
+                .ok_or_else(|| anyhow!("invalid initialize_delay: {:?}", value.initialize_delay))?,
+            unminimize_delay: value
+                .unminimize_delay
+                .as_duration_u32()
+                .ok_or_else(|| anyhow!("invalid unminimize_delay: {:?}", value.unminimize_delay))?,
+            unminimize_detection: value.unminimize_detection,
+            creation_grace_period: value.creation_grace_period,
+            hover_only: value.hover_only,
+            focus_trail: value.focus_trail.map(FocusTrail::try_from).transpose()?,
+            flash_color: value
+                .flash_color
+                .map(|color| color.to_color())
+                .transpose()?,
+            flash_duration: value.flash_duration,
+            color_transition_duration: value.color_transition_duration,
+            clip_to_monitor: value.clip_to_monitor,
+            active_opacity: value.active_opacity,
+            inactive_opacity: value.inactive_opacity,
         })
     }
 }
@@ -104,6 +410,8 @@ pub enum ParsedMatchStrategy {
     Equals(String),
     Contains(String),
     Regex(CompiledRegex),
+    Glob(GlobPattern),
+    Styles(WindowStyles),
 }
 
 impl PartialEq for ParsedMatchStrategy {
@@ -115,6 +423,8 @@ impl PartialEq for ParsedMatchStrategy {
                 Self::Regex(CompiledRegex { pattern: a, .. }),
                 Self::Regex(CompiledRegex { pattern: b, .. }),
             ) => a == b,
+            (Self::Glob(a), Self::Glob(b)) => a.pattern().eq_ignore_ascii_case(b.pattern()),
+            (Self::Styles(a), Self::Styles(b)) => a == b,
             _ => false,
         }
     }
@@ -129,7 +439,264 @@ impl ParsedMatchStrategy {
                 value.to_ascii_lowercase().contains(contains)
             }
             ParsedMatchStrategy::Regex(CompiledRegex { regex, .. }) => regex.is_match(value),
+            ParsedMatchStrategy::Glob(pattern) => pattern.is_match(value),
+            // Styles are checked against the window's flags, see `WindowProperties::is_match`
+            ParsedMatchStrategy::Styles(_) => false,
+        }
+    }
+
+    /// Compiles the strategy for a condition on `kind`. Style conditions ignore the configured
+    /// strategy, since they always check that every listed flag is set.
+    pub fn for_kind(
+        kind: Option<&MatchKind>,
+        strategy: Option<MatchStrategy>,
+        value: String,
+    ) -> anyhow::Result<Self> {
+        match kind {
+            Some(MatchKind::Style) => Ok(ParsedMatchStrategy::Styles(value.parse()?)),
+            _ => Self::new(strategy, value),
+        }
+    }
+
+    /// Compiles the configured strategy for `value`, defaulting to `Equals` when no strategy is
+    /// given.
+    pub fn new(strategy: Option<MatchStrategy>, value: String) -> anyhow::Result<Self> {
+        Ok(match strategy.unwrap_or(MatchStrategy::Equals) {
+            MatchStrategy::Equals => ParsedMatchStrategy::Equals(value),
+            MatchStrategy::Contains => ParsedMatchStrategy::Contains(value),
+            MatchStrategy::Glob => ParsedMatchStrategy::Glob(GlobPattern::new(&value)),
+            MatchStrategy::Regex => {
+                let regex = Regex::new(&value).context("Invalid regex pattern")?;
+                ParsedMatchStrategy::Regex(CompiledRegex {
+                    pattern: value,
+                    regex,
+                })
+            }
+        })
+    }
+}
+
+/// A set of window style flags parsed from names like `WS_EX_LAYERED | WS_POPUP`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WindowStyles {
+    pub style: WINDOW_STYLE,
+    pub ex_style: WINDOW_EX_STYLE,
+}
+
+impl WindowStyles {
+    /// Returns whether the given styles contain all of these flags.
+    pub fn is_subset_of(&self, style: WINDOW_STYLE, ex_style: WINDOW_EX_STYLE) -> bool {
+        style.0 & self.style.0 == self.style.0 && ex_style.0 & self.ex_style.0 == self.ex_style.0
+    }
+}
+
+impl FromStr for WindowStyles {
+    type Err = anyhow::Error;
+
+    /// Parses flag names separated by `|`, commas or whitespace. Names are case-insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut styles = WindowStyles::default();
+
+        let names = s
+            .split(|c: char| c == '|' || c == ',' || c.is_whitespace())
+            .filter(|name| !name.is_empty());
+
+        for name in names {
+            let name = name.to_ascii_uppercase();
+
+            if let Some(style) = window_style_from_name(&name) {
+                styles.style |= style;
+            } else if let Some(ex_style) = window_ex_style_from_name(&name) {
+                styles.ex_style |= ex_style;
+            } else {
+                return Err(anyhow!("unknown window style '{name}'"));
+            }
         }
+
+        if styles == WindowStyles::default() {
+            return Err(anyhow!("expected at least one window style, got '{s}'"));
+        }
+
+        Ok(styles)
+    }
+}
+
+fn window_style_from_name(name: &str) -> Option<WINDOW_STYLE> {
+    let style = match name {
+        "WS_BORDER" => WS_BORDER,
+        "WS_CAPTION" => WS_CAPTION,
+        "WS_CHILD" => WS_CHILD,
+        "WS_CLIPCHILDREN" => WS_CLIPCHILDREN,
+        "WS_CLIPSIBLINGS" => WS_CLIPSIBLINGS,
+        "WS_DISABLED" => WS_DISABLED,
+        "WS_DLGFRAME" => WS_DLGFRAME,
+        "WS_HSCROLL" => WS_HSCROLL,
+        "WS_MAXIMIZE" => WS_MAXIMIZE,
+        "WS_MAXIMIZEBOX" => WS_MAXIMIZEBOX,
+        "WS_MINIMIZE" => WS_MINIMIZE,
+        "WS_MINIMIZEBOX" => WS_MINIMIZEBOX,
+        "WS_OVERLAPPEDWINDOW" => WS_OVERLAPPEDWINDOW,
+        "WS_POPUP" => WS_POPUP,
+        "WS_POPUPWINDOW" => WS_POPUPWINDOW,
+        "WS_SYSMENU" => WS_SYSMENU,
+        "WS_THICKFRAME" => WS_THICKFRAME,
+        "WS_VISIBLE" => WS_VISIBLE,
+        "WS_VSCROLL" => WS_VSCROLL,
+        _ => return None,
+    };
+
+    Some(style)
+}
+
+fn window_ex_style_from_name(name: &str) -> Option<WINDOW_EX_STYLE> {
+    let ex_style = match name {
+        "WS_EX_ACCEPTFILES" => WS_EX_ACCEPTFILES,
+        "WS_EX_APPWINDOW" => WS_EX_APPWINDOW,
+        "WS_EX_CLIENTEDGE" => WS_EX_CLIENTEDGE,
+        "WS_EX_COMPOSITED" => WS_EX_COMPOSITED,
+        "WS_EX_DLGMODALFRAME" => WS_EX_DLGMODALFRAME,
+        "WS_EX_LAYERED" => WS_EX_LAYERED,
+        "WS_EX_LAYOUTRTL" => WS_EX_LAYOUTRTL,
+        "WS_EX_MDICHILD" => WS_EX_MDICHILD,
+        "WS_EX_NOACTIVATE" => WS_EX_NOACTIVATE,
+        "WS_EX_NOREDIRECTIONBITMAP" => WS_EX_NOREDIRECTIONBITMAP,
+        "WS_EX_STATICEDGE" => WS_EX_STATICEDGE,
+        "WS_EX_TOOLWINDOW" => WS_EX_TOOLWINDOW,
+        "WS_EX_TOPMOST" => WS_EX_TOPMOST,
+        "WS_EX_TRANSPARENT" => WS_EX_TRANSPARENT,
+        "WS_EX_WINDOWEDGE" => WS_EX_WINDOWEDGE,
+        _ => return None,
+    };
+
+    Some(ex_style)
+}
+
+impl ParsedConfig {
+    /// Returns the override for the monitor with the given device name (e.g., `\\.\DISPLAY1`).
+    pub fn monitor_override(&self, device_name: &str) -> Option<&MonitorOverride> {
+        self.monitors
+            .iter()
+            .find(|monitor| monitor.matches(device_name))
+    }
+}
+
+/// Settings overriding the global and window rule settings for borders on a monitor.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MonitorOverride {
+    /// Device name or display number of the monitor, as written in the config.
+    pub key: String,
+    pub border_width: Option<BorderWidth>,
+    pub border_offset: Option<BorderOffset>,
+    pub border_style: Option<BorderStyle>,
+    pub active_color: Option<Color>,
+    pub inactive_color: Option<InactiveColor>,
+}
+
+impl MonitorOverride {
+    /// Returns whether the key names the given device, see `monitor_key_matches`.
+    pub fn matches(&self, device_name: &str) -> bool {
+        monitor_key_matches(&self.key, device_name)
+    }
+}
+
+/// Returns whether a monitor key from the config names the given device, either by name (with or
+/// without the `\\.\` prefix) or by its display number.
+pub fn monitor_key_matches(key: &str, device_name: &str) -> bool {
+    let name = device_name.trim_start_matches(r"\\.\");
+    let key = key.trim_start_matches(r"\\.\");
+
+    if name.eq_ignore_ascii_case(key) {
+        return true;
+    }
+
+    let number = name.trim_start_matches(|c: char| !c.is_ascii_digit());
+    key.parse::<u32>()
+        .is_ok_and(|key| number.parse::<u32>() == Ok(key))
+}
+
+impl TryFrom<(String, MonitorOverrideConfig)> for MonitorOverride {
+    type Error = anyhow::Error;
+
+    fn try_from((key, value): (String, MonitorOverrideConfig)) -> Result<Self, Self::Error> {
+        Ok(Self {
+            border_width: value.border_width.map(BorderWidth::try_from).transpose()?,
+            border_offset: value
+                .border_offset
+                .map(BorderOffset::try_from)
+                .transpose()?,
+            border_style: value.border_style,
+            active_color: value
+                .active_color
+                .map(|c| c.to_active_color())
+                .transpose()?,
+            inactive_color: value
+                .inactive_color
+                .as_ref()
+                .map(InactiveColor::try_from)
+                .transpose()?,
+            key,
+        })
+    }
+}
+
+/// A compiled match condition of a window rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchCondition {
+    pub kind: MatchKind,
+    pub strategy: ParsedMatchStrategy,
+}
+
+impl MatchCondition {
+    pub fn is_match(&self, window: &WindowProperties) -> bool {
+        window.is_match(&self.kind, &self.strategy)
+    }
+}
+
+impl TryFrom<MatchConditionConfig> for MatchCondition {
+    type Error = anyhow::Error;
+
+    fn try_from(value: MatchConditionConfig) -> Result<Self, Self::Error> {
+        Ok(Self {
+            strategy: ParsedMatchStrategy::for_kind(
+                Some(&value.kind),
+                value.strategy,
+                value.value,
+            )?,
+            kind: value.kind,
+        })
+    }
+}
+
+/// The properties of a window that rules can match against.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WindowProperties {
+    pub title: String,
+    pub class: String,
+    pub process: String,
+    pub path: String,
+    pub style: WINDOW_STYLE,
+    pub ex_style: WINDOW_EX_STYLE,
+}
+
+impl WindowProperties {
+    /// Returns whether the window's property of the given kind satisfies `strategy`.
+    pub fn is_match(&self, kind: &MatchKind, strategy: &ParsedMatchStrategy) -> bool {
+        let value = match kind {
+            MatchKind::Title => &self.title,
+            MatchKind::Class => &self.class,
+            MatchKind::Process => &self.process,
+            MatchKind::Path => &self.path,
+            MatchKind::Style => {
+                return match strategy {
+                    ParsedMatchStrategy::Styles(styles) => {
+                        styles.is_subset_of(self.style, self.ex_style)
+                    }
+                    _ => false,
+                };
+            }
+        };
+
+        strategy.is_match(value)
     }
 }
 
@@ -139,48 +706,104 @@ pub struct WindowRule {
     pub match_kind: Option<MatchKind>,
     /// Strategy for matching, such as exact match or regex.
     pub match_strategy: Option<ParsedMatchStrategy>,
+    /// Additional conditions that must all match.
+    pub conditions: Vec<MatchCondition>,
+    /// Precedence of the rule when several rules match a window.
+    pub priority: i32,
     /// Color for the border when the window is active.
     pub active_color: Option<Color>,
     /// Color for the border when the window is inactive.
-    pub inactive_color: Option<Color>,
+    pub inactive_color: Option<InactiveColor>,
     /// Animation settings for the window borders.
     pub animation_manager: Option<AnimationManager>,
     /// Effect settings for the window borders.
     pub effect_manager: Option<EffectManager>,
     /// Style of the border corners.
     pub border_style: Option<BorderStyle>,
+    /// Line pattern of the border.
+    pub stroke_style: Option<StrokeStyle>,
+    /// Where the border is drawn relative to the window's edge.
+    pub placement: Option<BorderPlacement>,
+    /// Corner shape used by the "auto" border style instead of the window's corner preference.
+    pub force_corners: Option<ForcedCorners>,
+    /// Extra strokes drawn behind the border, from back to front.
+    pub layers: Option<Vec<BorderLayer>>,
     /// Width of the border in pixels.
-    pub border_width: Option<i32>,
+    pub border_width: Option<BorderWidth>,
     /// Offset of the border relative to the window.
-    pub border_offset: Option<i32>,
+    pub border_offset: Option<BorderOffset>,
     /// Whether borders are enabled for this match.
     pub enabled: Option<bool>,
     /// Delay (in milliseconds) before applying the border after initialization.
     pub initialize_delay: Option<u32>,
     /// Delay (in milliseconds) before applying the border after unminimizing.
     pub unminimize_delay: Option<u32>,
+    /// Time (in milliseconds) a window must stay visible before its border is shown.
+    pub creation_grace_period: Option<u32>,
+    /// Only show the border while the cursor hovers over the window.
+    pub hover_only: Option<bool>,
+    /// Always use the active color for this window, even when it isn't focused.
+    pub always_active: Option<bool>,
+    /// Number of update and render passes performed when the border is first shown.
+    pub initial_render_passes: Option<u32>,
+    /// Delay (in milliseconds) between the initial render passes.
+    pub initial_render_interval: Option<u32>,
+    /// Clip the border and effects to the bounds of the window's monitor.
+    pub clip_to_monitor: Option<bool>,
+    /// Opacity multiplier (0.0 to 1.0) applied to the active color.
+    pub active_opacity: Option<f32>,
+    /// Opacity multiplier (0.0 to 1.0) applied to the inactive color.
+    pub inactive_opacity: Option<f32>,
+}
+
+impl WindowRule {
+    /// Returns whether the window satisfies the rule's `kind`/`value` and all of its additional
+    /// conditions. Rules without any condition never match.
+    pub fn is_match(&self, window: &WindowProperties) -> bool {
+        let primary = match (&self.match_kind, &self.match_strategy) {
+            (Some(kind), Some(strategy)) => Some(window.is_match(kind, strategy)),
+            (None, Some(_)) => {
+                error!("expected 'kind' for window rule but none found!");
+                return false;
+            }
+            _ => None,
+        };
+
+        if primary.is_none() && self.conditions.is_empty() {
+            return false;
+        }
+
+        primary.unwrap_or(true)
+            && self
+                .conditions
+                .iter()
+                .all(|condition| condition.is_match(window))
+    }
 }
 
 impl TryFrom<WindowRuleConfig> for WindowRule {
     type Error = anyhow::Error;
 
     fn try_from(value: WindowRuleConfig) -> Result<Self, Self::Error> {
+        let priority = value.priority;
         let match_window = value.match_window;
-        let match_strategy = match (match_window.match_strategy, match_window.match_value) {
-            (Some(kind), Some(value)) => Some(match kind {
-                MatchStrategy::Equals => ParsedMatchStrategy::Equals(value),
-                MatchStrategy::Contains => ParsedMatchStrategy::Contains(value),
-                MatchStrategy::Regex => {
-                    let regex = Regex::new(&value).context("Invalid regex pattern")?;
-                    ParsedMatchStrategy::Regex(CompiledRegex {
-                        pattern: value,
-                        regex,
-                    })
-                }
-            }),
-            (None, Some(value)) => Some(ParsedMatchStrategy::Equals(value)),
-            _ => None,
-        };
+        let match_strategy = match_window
+            .match_value
+            .map(|value| {
+                ParsedMatchStrategy::for_kind(
+                    match_window.match_kind.as_ref(),
+                    match_window.match_strategy,
+                    value,
+                )
+            })
+            .transpose()?;
+
+        let conditions = match_window
+            .conditions
+            .unwrap_or_default()
+            .into_iter()
+            .map(MatchCondition::try_from)
+            .collect::<anyhow::Result<Vec<_>>>()?;
 
         let animation_manager = match_window
             .animations
@@ -194,27 +817,164 @@ impl TryFrom<WindowRuleConfig> for WindowRule {
 
         let active_color = match_window
             .active_color
-            .map(|color| color.to_color())
+            .map(|color| color.to_active_color())
             .transpose()?;
-
         let inactive_color = match_window
             .inactive_color
-            .map(|color| color.to_color())
+            .as_ref()
+            .map(InactiveColor::try_from)
             .transpose()?;
 
         Ok(Self {
             match_strategy,
+            conditions,
+            priority,
             animation_manager,
             effect_manager,
             active_color,
             inactive_color,
             match_kind: match_window.match_kind,
             border_style: match_window.border_style,
-            border_width: match_window.border_width,
-            border_offset: match_window.border_offset,
+            stroke_style: match_window
+                .stroke_style
+                .map(StrokeStyle::try_from)
+                .transpose()?,
+            placement: match_window.placement,
+            force_corners: match_window.force_corners,
+            layers: match_window
+                .layers
+                .map(|layers| {
+                    layers
+                        .into_iter()
+                        .map(BorderLayer::try_from)
+                        .collect::<anyhow::Result<_>>()
+                })
+                .transpose()?,
+            border_width: match_window
+                .border_width
+                .map(BorderWidth::try_from)
+                .transpose()?,
+            border_offset: match_window
+                .border_offset
+                .map(BorderOffset::try_from)
+                .transpose()?,
             enabled: match_window.enabled,
-            initialize_delay: match_window.initialize_delay,
-            unminimize_delay: match_window.unminimize_delay,
+            initialize_delay: match_window
+                .initialize_delay
+                .map(|delay| {
+                    delay
+                        .as_duration_u32()
+                        .ok_or_else(|| anyhow!("invalid initialize_delay: {delay:?}"))
+                })
+                .transpose()?,
+            unminimize_delay: match_window
+                .unminimize_delay
+                .map(|delay| {
+                    delay
+                        .as_duration_u32()
+                        .ok_or_else(|| anyhow!("invalid unminimize_delay: {delay:?}"))
+                })
+                .transpose()?,
+            creation_grace_period: match_window.creation_grace_period,
+            hover_only: match_window.hover_only,
+            always_active: match_window.always_active,
+            initial_render_passes: match_window.initial_render_passes,
+            initial_render_interval: match_window.initial_render_interval,
+            clip_to_monitor: match_window.clip_to_monitor,
+            active_opacity: match_window.active_opacity,
+            inactive_opacity: match_window.inactive_opacity,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::GlobalColor;
+
+    fn layer_config(active_color: &str, inactive_color: Option<&str>) -> BorderLayerConfig {
+        BorderLayerConfig {
+            active_color: GlobalColor::String(active_color.to_string()),
+            inactive_color: inactive_color.map(|color| GlobalColor::String(color.to_string())),
+            width: 2,
+            offset: -1,
+            stroke_style: StrokeStyleConfig {
+                kind: StrokeKind::Dashed,
+                dash: Some(Value::Number(6.0)),
+                gap: None,
+            },
+        }
+    }
+
+    #[test]
+    fn parses_window_style_names() {
+        let cases = [
+            ("WS_POPUP", WS_POPUP, WINDOW_EX_STYLE(0)),
+            ("ws_ex_layered", WINDOW_STYLE(0), WS_EX_LAYERED),
+            ("WS_POPUP | WS_EX_TOOLWINDOW", WS_POPUP, WS_EX_TOOLWINDOW),
+            (
+                "WS_CAPTION,WS_SYSMENU  WS_EX_TOPMOST",
+                WS_CAPTION | WS_SYSMENU,
+                WS_EX_TOPMOST,
+            ),
+        ];
+
+        for (input, style, ex_style) in cases {
+            assert_eq!(
+                input.parse::<WindowStyles>().unwrap(),
+                WindowStyles { style, ex_style },
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_window_style_names() {
+        for input in ["", " | ", "WS_UNKNOWN", "WS_POPUP | LAYERED"] {
+            assert!(input.parse::<WindowStyles>().is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn window_styles_match_when_every_flag_is_set() {
+        let styles: WindowStyles = "WS_POPUP | WS_EX_LAYERED".parse().unwrap();
+
+        assert!(styles.is_subset_of(WS_POPUP | WS_VISIBLE, WS_EX_LAYERED | WS_EX_TOPMOST));
+        assert!(!styles.is_subset_of(WS_POPUP, WS_EX_TOPMOST));
+        assert!(!styles.is_subset_of(WS_VISIBLE, WS_EX_LAYERED));
+    }
+
+    #[test]
+    fn layer_inactive_color_defaults_to_active_color() {
+        let layer = BorderLayer::try_from(layer_config("#ff0000", None)).unwrap();
+
+        assert_eq!(layer.inactive_color, layer.active_color);
+        assert_eq!(layer.width, 2);
+        assert_eq!(layer.offset, -1);
+        assert_eq!(
+            layer.stroke_style,
+            StrokeStyle {
+                kind: StrokeKind::Dashed,
+                dash: Some(6.0),
+                gap: None,
+            }
+        );
+    }
+
+    #[test]
+    fn layer_inactive_color_can_reference_active_color() {
+        let layer = BorderLayer::try_from(layer_config("#ff0000", Some("active"))).unwrap();
+
+        assert_eq!(layer.inactive_color, layer.active_color);
+    }
+
+    #[test]
+    fn layer_rejects_invalid_config() {
+        // The active color can't reference itself
+        assert!(BorderLayer::try_from(layer_config("active", None)).is_err());
+
+        let mut config = layer_config("#ff0000", None);
+        config.stroke_style.gap = Some(Value::Number(-2.0));
+        assert!(BorderLayer::try_from(config).is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::app_manager::AppManager;
+use crate::border_manager::window_borders;
+use crate::error::LogIfErr;
+use crate::windows_api::HWNDConversion;
+use crate::windows_api::PointerConversion;
+use crate::windows_api::WM_APP_RECREATE;
+use crate::windows_api::WindowsApi;
+use anyhow::Context;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::LRESULT;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::System::Power::HPOWERNOTIFY;
+use windows::Win32::System::Power::POWERBROADCAST_SETTING;
+use windows::Win32::System::Power::RegisterPowerSettingNotification;
+use windows::Win32::System::Power::UnregisterPowerSettingNotification;
+use windows::Win32::System::SystemServices::GUID_CONSOLE_DISPLAY_STATE;
+use windows::Win32::UI::WindowsAndMessaging::DEVICE_NOTIFY_WINDOW_HANDLE;
+use windows::Win32::UI::WindowsAndMessaging::PBT_APMRESUMEAUTOMATIC;
+use windows::Win32::UI::WindowsAndMessaging::PBT_POWERSETTINGCHANGE;
+use windows::Win32::UI::WindowsAndMessaging::WM_POWERBROADCAST;
+use windows::core::PCWSTR;
+use windows::core::w;
+
+/// Display states reported through `GUID_CONSOLE_DISPLAY_STATE`.
+const DISPLAY_STATE_ON: u32 = 1;
+
+/// The last display state we were notified about. Windows sends the current state as soon as we
+/// register, so we start out as "on" to avoid refreshing every border at startup.
+static DISPLAY_STATE: AtomicU32 = AtomicU32::new(DISPLAY_STATE_ON);
+
+const CLASS_NAME: PCWSTR = w!("tacky-borders-power");
+
+/// Hidden window that listens for power events, see `start_power_monitor`.
+#[derive(Debug)]
+pub struct PowerMonitor {
+    window: HWND,
+    notification: HPOWERNOTIFY,
+}
+
+impl PowerMonitor {
+    /// Unregisters the notifications and destroys the window. Must be called from the thread that
+    /// started the power monitor.
+    pub fn stop(self) -> anyhow::Result<()> {
+        unsafe { UnregisterPowerSettingNotification(self.notification) }
+            .context("could not unregister display state notifications")?;
+        WindowsApi::destroy_hidden_window(self.window, CLASS_NAME)
+    }
+}
+
+/// Creates a hidden window on the current thread that listens for power events.
+///
+/// The window must be owned by a thread that runs a message loop, see
+/// `WindowsApi::create_hidden_window`.
+pub fn start_power_monitor() -> anyhow::Result<PowerMonitor> {
+    let window = WindowsApi::create_hidden_window(CLASS_NAME, Some(power_monitor_proc))
+        .context("could not create power monitor window")?;
+
+    let notification = unsafe {
+        RegisterPowerSettingNotification(
+            HANDLE(window.0),
+            &GUID_CONSOLE_DISPLAY_STATE,
+            DEVICE_NOTIFY_WINDOW_HANDLE,
+        )
+    }
+    .context("could not register for display state notifications")?;
+
+    Ok(PowerMonitor {
+        window,
+        notification,
+    })
+}
+
+extern "system" fn power_monitor_proc(
+    window: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if message != WM_POWERBROADCAST {
+        return WindowsApi::def_window_proc_w(window.as_int(), message, wparam.0, lparam.0);
+    }
+
+    match wparam.0 as u32 {
+        PBT_APMRESUMEAUTOMATIC => refresh_all_borders("system resumed"),
+        PBT_POWERSETTINGCHANGE => {
+            let setting = unsafe { &*(lparam.0 as *const POWERBROADCAST_SETTING) };
+
+            if setting.PowerSetting == GUID_CONSOLE_DISPLAY_STATE && setting.DataLength >= 1 {
+                let new_state = setting.Data[0] as u32;
+                let old_state = DISPLAY_STATE.swap(new_state, Ordering::SeqCst);
+
+                if new_state == DISPLAY_STATE_ON && old_state != DISPLAY_STATE_ON {
+                    refresh_all_borders("display turned on");
+                }
+            }
+        }
+        _ => {}
+    }
+
+    // Returning TRUE grants any query we may receive
+    LRESULT(1)
+}
+
+/// Tells every border to recreate its render resources and reposition itself.
+fn refresh_all_borders(reason: &str) {
+    if !AppManager::get().config().refresh_on_wake {
+        return;
+    }
+
+    info!("refreshing borders: {reason}");
+
+    let border_windows: Vec<isize> = window_borders()
+        .values()
+        .map(|border| border.border_window)
+        .collect();
+
+    for border_window in border_windows {
+        WindowsApi::post_message_w(
+            Some(border_window.as_hwnd()),
+            WM_APP_RECREATE,
+            WPARAM(0),
+            LPARAM(0),
+        )
+        .context("WM_POWERBROADCAST")
+        .log_if_err();
+    }
+}
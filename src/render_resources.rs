@@ -1,39 +1,168 @@
 use std::mem::ManuallyDrop;
 
 use anyhow::Context;
+use schema_jsonrs::JsonSchema;
+use serde::Deserialize;
+use serde::Deserializer;
+use std::ffi::c_void;
+use windows::Win32::Foundation::BOOL;
 use windows::Win32::Foundation::FALSE;
 use windows::Win32::Graphics::Direct2D::D2D1_BITMAP_OPTIONS;
 use windows::Win32::Graphics::DirectComposition::{
     DCompositionCreateDevice3, IDCompositionDesktopDevice,
 };
+use windows::Win32::Graphics::Dxgi::DXGI_FEATURE_PRESENT_ALLOW_TEARING;
+use windows::Win32::Graphics::Dxgi::DXGI_PRESENT;
+use windows::Win32::Graphics::Dxgi::DXGI_PRESENT_ALLOW_TEARING;
 use windows::Win32::Graphics::Dxgi::DXGI_SWAP_CHAIN_FLAG;
+use windows::Win32::Graphics::Dxgi::DXGI_SWAP_CHAIN_FLAG_ALLOW_TEARING;
 use windows::Win32::Graphics::{
     Direct2D::{
         Common::{D2D_SIZE_U, D2D1_ALPHA_MODE_PREMULTIPLIED, D2D1_PIXEL_FORMAT},
-        D2D1_ANTIALIAS_MODE_PER_PRIMITIVE, D2D1_BITMAP_OPTIONS_CANNOT_DRAW,
-        D2D1_BITMAP_OPTIONS_TARGET, D2D1_BITMAP_PROPERTIES1, D2D1_DEVICE_CONTEXT_OPTIONS_NONE,
-        ID2D1Bitmap1, ID2D1DeviceContext7,
+        D2D1_ANTIALIAS_MODE, D2D1_ANTIALIAS_MODE_ALIASED, D2D1_ANTIALIAS_MODE_PER_PRIMITIVE,
+        D2D1_BITMAP_OPTIONS_CANNOT_DRAW, D2D1_BITMAP_OPTIONS_TARGET, D2D1_BITMAP_PROPERTIES1,
+        D2D1_DEVICE_CONTEXT_OPTIONS_NONE, ID2D1Bitmap1, ID2D1DeviceContext7,
     },
     DirectComposition::IDCompositionTarget,
     Dxgi::{
         Common::{DXGI_ALPHA_MODE_PREMULTIPLIED, DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_SAMPLE_DESC},
-        DXGI_SCALING_STRETCH, DXGI_SWAP_CHAIN_DESC1, DXGI_SWAP_EFFECT_FLIP_DISCARD,
-        DXGI_USAGE_RENDER_TARGET_OUTPUT, IDXGIFactory7, IDXGISurface, IDXGISwapChain1,
+        DXGI_SCALING, DXGI_SCALING_ASPECT_RATIO_STRETCH, DXGI_SCALING_NONE, DXGI_SCALING_STRETCH,
+        DXGI_SWAP_CHAIN_DESC1, DXGI_SWAP_EFFECT_FLIP_DISCARD, DXGI_USAGE_RENDER_TARGET_OUTPUT,
+        IDXGIFactory7, IDXGISurface, IDXGISwapChain1,
     },
     Gdi::HMONITOR,
 };
 
 use crate::{
     app_manager::AppManager,
+    core::helpers::{serde_default_bool, serde_default_u32},
+    error::TackyError,
     windows_api::{PointerConversion, WindowsApi},
 };
 
+/// Default number of swap chain buffers.
+const DEFAULT_BUFFER_COUNT: u32 = 2;
+
+/// Configuration for the rendering backend shared by all borders.
+#[derive(Debug, Clone, Deserialize, PartialEq, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct RenderConfig {
+    /// Number of buffers in each border's swap chain (2-16).
+    ///
+    /// `2` gives the lowest latency, `3` can smooth out animations at the cost of an extra frame
+    /// of latency and some memory.
+    #[serde(
+        default = "serde_default_u32::<DEFAULT_BUFFER_COUNT>",
+        deserialize_with = "deserialize_buffer_count"
+    )]
+    pub buffer_count: u32,
+    /// How the swap chain is scaled when its size doesn't match the border window. Composition
+    /// swap chains only support "stretch", other modes fall back to it.
+    #[serde(deserialize_with = "deserialize_scaling")]
+    pub scaling: SwapChainScaling,
+    /// Waits for the display's vertical blank before presenting a frame.
+    ///
+    /// Disabling it presents frames immediately, which lowers latency. Where the hardware supports
+    /// tearing, frames are then also presented without waiting for the compositor.
+    #[serde(default = "serde_default_bool::<true>")]
+    pub vsync: bool,
+    /// Smooths the edges of the borders.
+    ///
+    /// Disabling it can make 1px borders sharper on displays with integer scaling, at the cost of
+    /// jagged rounded corners.
+    #[serde(default = "serde_default_bool::<true>")]
+    pub antialiasing: bool,
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        Self {
+            buffer_count: DEFAULT_BUFFER_COUNT,
+            scaling: SwapChainScaling::default(),
+            vsync: true,
+            antialiasing: true,
+        }
+    }
+}
+
+/// Scaling mode of the swap chain.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum SwapChainScaling {
+    /// Stretch the buffer to fit the window.
+    #[default]
+    Stretch,
+    /// Don't scale the buffer at all.
+    None,
+    /// Stretch the buffer while preserving its aspect ratio.
+    AspectRatioStretch,
+}
+
+impl From<SwapChainScaling> for DXGI_SCALING {
+    fn from(value: SwapChainScaling) -> Self {
+        match value {
+            SwapChainScaling::Stretch => DXGI_SCALING_STRETCH,
+            SwapChainScaling::None => DXGI_SCALING_NONE,
+            SwapChainScaling::AspectRatioStretch => DXGI_SCALING_ASPECT_RATIO_STRETCH,
+        }
+    }
+}
+
+/// Deserializes the buffer count, falling back to the default if DXGI would reject it. This warns
+/// once per config load instead of once per border.
+fn deserialize_buffer_count<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let buffer_count = u32::deserialize(deserializer)?;
+
+    // Flip model swap chains require between 2 and 16 buffers
+    if (2..=16).contains(&buffer_count) {
+        Ok(buffer_count)
+    } else {
+        warn!(
+            "swap chain buffer_count must be between 2 and 16 (got {buffer_count}); using {DEFAULT_BUFFER_COUNT}"
+        );
+        Ok(DEFAULT_BUFFER_COUNT)
+    }
+}
+
+/// Deserializes the scaling mode, falling back to "stretch" for the modes composition swap chains
+/// don't support.
+fn deserialize_scaling<'de, D>(deserializer: D) -> Result<SwapChainScaling, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match SwapChainScaling::deserialize(deserializer)? {
+        SwapChainScaling::Stretch => Ok(SwapChainScaling::Stretch),
+        scaling => {
+            warn!(
+                "swap chain scaling {scaling:?} is not supported by composition swap chains; using Stretch"
+            );
+            Ok(SwapChainScaling::Stretch)
+        }
+    }
+}
+
+impl RenderConfig {
+    fn antialias_mode(&self) -> D2D1_ANTIALIAS_MODE {
+        match self.antialiasing {
+            true => D2D1_ANTIALIAS_MODE_PER_PRIMITIVE,
+            false => D2D1_ANTIALIAS_MODE_ALIASED,
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct RenderResources {
     pub d2d_context: Option<ID2D1DeviceContext7>,
     pub swap_chain: Option<IDXGISwapChain1>,
     pub composition_target: Option<IDCompositionTarget>,
     pub bitmaps: Bitmaps,
+    /// Size of the swap chain buffers and bitmaps, see `update`.
+    bitmap_size: D2D_SIZE_U,
+    buffer_count: u32,
+    swap_chain_flags: DXGI_SWAP_CHAIN_FLAG,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -54,6 +183,19 @@ impl RenderResources {
         self.swap_chain.as_ref().context("could not get swap_chain")
     }
 
+    /// Returns the sync interval and flags to present the swap chain with, depending on `vsync`.
+    pub fn present_options(&self) -> (u32, DXGI_PRESENT) {
+        if AppManager::get().config().render.vsync {
+            return (1, DXGI_PRESENT::default());
+        }
+
+        // Tearing can only be requested if the swap chain was created to allow it
+        match self.swap_chain_flags.0 & DXGI_SWAP_CHAIN_FLAG_ALLOW_TEARING.0 != 0 {
+            true => (0, DXGI_PRESENT_ALLOW_TEARING),
+            false => (0, DXGI_PRESENT::default()),
+        }
+    }
+
     pub fn target_bitmap(&self) -> anyhow::Result<&ID2D1Bitmap1> {
         self.bitmaps
             .target_bitmap
@@ -81,18 +223,26 @@ impl RenderResources {
         border_width: i32,
         window_padding: i32,
         border_window: isize,
-    ) -> anyhow::Result<()> {
+    ) -> Result<(), TackyError> {
+        // The devices are shared by all borders, so only the per-border resources are created here
         let app_manager = AppManager::get();
+        let device = app_manager.device();
+        let dxgi_device = app_manager.dxgi_device();
         let d2d_context = unsafe {
             app_manager
                 .d2d_device()
                 .CreateDeviceContext(D2D1_DEVICE_CONTEXT_OPTIONS_NONE)
         }
-        .context("d2d_context")?;
+        .map_err(TackyError::direct2d("d2d_context"))?;
 
-        unsafe { d2d_context.SetAntialiasMode(D2D1_ANTIALIAS_MODE_PER_PRIMITIVE) };
+        let render_config = app_manager.config().render.clone();
 
-        let m_info = WindowsApi::get_monitor_info(current_monitor).context("mi")?;
+        // The mode is part of the context's state, so it applies to every draw call of the border,
+        // including the ones recorded into effect command lists
+        unsafe { d2d_context.SetAntialiasMode(render_config.antialias_mode()) };
+
+        let m_info = WindowsApi::get_monitor_info(current_monitor)
+            .map_err(|_| TackyError::InvalidMonitor(current_monitor.0.as_int()))?;
         let screen_width = (m_info.rcMonitor.right - m_info.rcMonitor.left) as u32;
         let screen_height = (m_info.rcMonitor.bottom - m_info.rcMonitor.top) as u32;
 
@@ -101,7 +251,7 @@ impl RenderResources {
             height: screen_height + ((border_width + window_padding) * 2) as u32,
         };
 
-        let swap_chain_desc = DXGI_SWAP_CHAIN_DESC1 {
+        let mut swap_chain_desc = DXGI_SWAP_CHAIN_DESC1 {
             Width: bitmap_size.width,
             Height: bitmap_size.height,
             Format: DXGI_FORMAT_B8G8R8A8_UNORM,
@@ -111,58 +261,108 @@ impl RenderResources {
                 Quality: 0,
             },
             BufferUsage: DXGI_USAGE_RENDER_TARGET_OUTPUT,
-            BufferCount: 2,
-            Scaling: DXGI_SCALING_STRETCH,
+            BufferCount: render_config.buffer_count,
+            Scaling: render_config.scaling.into(),
             SwapEffect: DXGI_SWAP_EFFECT_FLIP_DISCARD,
             AlphaMode: DXGI_ALPHA_MODE_PREMULTIPLIED,
             Flags: 0,
         };
 
         unsafe {
-            let dxgi_adapter = app_manager
-                .dxgi_device()
+            let dxgi_adapter = dxgi_device
                 .GetAdapter()
-                .context("dxgi_adapter")?;
-            let dxgi_factory: IDXGIFactory7 = dxgi_adapter.GetParent().context("dxgi_factory")?;
-
-            let swap_chain = dxgi_factory
-                .CreateSwapChainForComposition(app_manager.device(), &swap_chain_desc, None)
-                .context("swap_chain")?;
-
-            let d_comp_device: IDCompositionDesktopDevice =
-                DCompositionCreateDevice3(app_manager.dxgi_device())?;
+                .map_err(TackyError::direct2d("dxgi_adapter"))?;
+            let dxgi_factory: IDXGIFactory7 = dxgi_adapter
+                .GetParent()
+                .map_err(TackyError::direct2d("dxgi_factory"))?;
+
+            if !render_config.vsync && supports_tearing(&dxgi_factory) {
+                swap_chain_desc.Flags = DXGI_SWAP_CHAIN_FLAG_ALLOW_TEARING.0 as u32;
+            }
+
+            let swap_chain = match dxgi_factory.CreateSwapChainForComposition(
+                &device,
+                &swap_chain_desc,
+                None,
+            ) {
+                Ok(swap_chain) => swap_chain,
+                Err(err)
+                    if swap_chain_desc.BufferCount != DEFAULT_BUFFER_COUNT
+                        || swap_chain_desc.Flags != 0 =>
+                {
+                    // DXGI rejected the configured combination, so retry with the defaults
+                    // rather than failing border creation altogether.
+                    warn!(
+                        "could not create swap chain with buffer_count {} and flags {:#x}; falling back to defaults: {err}",
+                        swap_chain_desc.BufferCount, swap_chain_desc.Flags
+                    );
+                    swap_chain_desc.BufferCount = DEFAULT_BUFFER_COUNT;
+                    swap_chain_desc.Flags = 0;
+
+                    dxgi_factory
+                        .CreateSwapChainForComposition(&device, &swap_chain_desc, None)
+                        .map_err(TackyError::direct2d("swap_chain"))?
+                }
+                Err(err) => return Err(TackyError::direct2d("swap_chain")(err)),
+            };
+
+            let d_comp_device: IDCompositionDesktopDevice = DCompositionCreateDevice3(&dxgi_device)
+                .map_err(TackyError::direct2d("d_comp_device"))?;
 
             let d_comp_target = d_comp_device
                 .CreateTargetForHwnd(border_window.as_hwnd(), true)
-                .context("d_comp_target")?;
-            let d_comp_visual = d_comp_device.CreateVisual().context("visual")?;
+                .map_err(TackyError::direct2d("d_comp_target"))?;
+            let d_comp_visual = d_comp_device
+                .CreateVisual()
+                .map_err(TackyError::direct2d("visual"))?;
 
             d_comp_visual
                 .SetContent(&swap_chain)
-                .context("d_comp_visual.SetContent()")?;
+                .map_err(TackyError::direct2d("d_comp_visual.SetContent()"))?;
             d_comp_target
                 .SetRoot(&d_comp_visual)
-                .context("d_comp_target.SetRoot()")?;
-            d_comp_device.Commit().context("d_comp_device.Commit()")?;
+                .map_err(TackyError::direct2d("d_comp_target.SetRoot()"))?;
+            d_comp_device
+                .Commit()
+                .map_err(TackyError::direct2d("d_comp_device.Commit()"))?;
 
             self.bitmaps
-                .create(&d2d_context, &swap_chain, &bitmap_size)
-                .context("could not create bitmaps")?;
+                .create(&d2d_context, &swap_chain, &bitmap_size)?;
 
             self.d2d_context = Some(d2d_context);
             self.swap_chain = Some(swap_chain);
+            self.bitmap_size = bitmap_size;
             self.composition_target = Some(d_comp_target);
+            self.buffer_count = swap_chain_desc.BufferCount;
+            self.swap_chain_flags = DXGI_SWAP_CHAIN_FLAG(swap_chain_desc.Flags as i32);
         }
 
         Ok(())
     }
 
+    /// Resizes the swap chain buffers and bitmaps for `current_monitor`. Keeps them if their size
+    /// doesn't change, so effect command lists recorded with the bitmaps stay valid (e.g. when a
+    /// window moves between monitors with the same resolution).
     pub fn update(
         &mut self,
         current_monitor: HMONITOR,
         border_width: i32,
         window_padding: i32,
-    ) -> anyhow::Result<()> {
+    ) -> Result<(), TackyError> {
+        let m_info = WindowsApi::get_monitor_info(current_monitor)
+            .map_err(|_| TackyError::InvalidMonitor(current_monitor.0.as_int()))?;
+        let screen_width = (m_info.rcMonitor.right - m_info.rcMonitor.left) as u32;
+        let screen_height = (m_info.rcMonitor.bottom - m_info.rcMonitor.top) as u32;
+
+        let bitmap_size = D2D_SIZE_U {
+            width: screen_width + ((border_width + window_padding) * 2) as u32,
+            height: screen_height + ((border_width + window_padding) * 2) as u32,
+        };
+
+        if bitmap_size == self.bitmap_size && self.bitmaps.is_created() {
+            return Ok(());
+        }
+
         // Release buffer references
         self.bitmaps.target_bitmap = None;
         self.bitmaps.border_bitmap = None;
@@ -173,38 +373,33 @@ impl RenderResources {
 
         unsafe { d2d_context.SetTarget(None) };
 
-        let m_info = WindowsApi::get_monitor_info(current_monitor).context("mi")?;
-        let screen_width = (m_info.rcMonitor.right - m_info.rcMonitor.left) as u32;
-        let screen_height = (m_info.rcMonitor.bottom - m_info.rcMonitor.top) as u32;
-
-        let bitmap_size = D2D_SIZE_U {
-            width: screen_width + ((border_width + window_padding) * 2) as u32,
-            height: screen_height + ((border_width + window_padding) * 2) as u32,
-        };
-
         unsafe {
             swap_chain.ResizeBuffers(
-                2,
+                self.buffer_count,
                 bitmap_size.width,
                 bitmap_size.height,
                 DXGI_FORMAT_B8G8R8A8_UNORM,
-                DXGI_SWAP_CHAIN_FLAG::default(),
+                self.swap_chain_flags,
             )
         }
-        .context("swap_chain.ResizeBuffers()")?;
+        .map_err(TackyError::direct2d("swap_chain.ResizeBuffers()"))?;
 
         // Supposedly, cloning d2d_context or swap_chain just increases the underlying object's
         // reference count, so it's not actually cloning the object itself. Unfortunately, I need
         // to do it because Rust's borrow checker is a little stupid.
         self.bitmaps
-            .create(&d2d_context.clone(), &swap_chain.clone(), &bitmap_size)
-            .context("could not create bitmaps")?;
+            .create(&d2d_context.clone(), &swap_chain.clone(), &bitmap_size)?;
+        self.bitmap_size = bitmap_size;
 
         Ok(())
     }
 }
 
 impl Bitmaps {
+    fn is_created(&self) -> bool {
+        self.target_bitmap.is_some() && self.border_bitmap.is_some() && self.mask_bitmap.is_some()
+    }
+
     fn create_bitmap_properties<I>(bitmap_options: I) -> D2D1_BITMAP_PROPERTIES1
     where
         I: IntoIterator<Item = D2D1_BITMAP_OPTIONS>,
@@ -230,17 +425,17 @@ impl Bitmaps {
         d2d_context: &ID2D1DeviceContext7,
         swap_chain: &IDXGISwapChain1,
         bitmap_size: &D2D_SIZE_U,
-    ) -> anyhow::Result<()> {
+    ) -> Result<(), TackyError> {
         let bitmap_properties =
             Self::create_bitmap_properties(Some(D2D1_BITMAP_OPTIONS_CANNOT_DRAW));
 
         let dxgi_back_buffer: IDXGISurface =
-            unsafe { swap_chain.GetBuffer(0) }.context("dxgi_back_buffer")?;
+            unsafe { swap_chain.GetBuffer(0) }.map_err(TackyError::direct2d("dxgi_back_buffer"))?;
 
         let target_bitmap = unsafe {
             d2d_context.CreateBitmapFromDxgiSurface(&dxgi_back_buffer, Some(&bitmap_properties))
         }
-        .context("d2d_target_bitmap")?;
+        .map_err(TackyError::direct2d("d2d_target_bitmap"))?;
 
         unsafe { d2d_context.SetTarget(&target_bitmap) };
 
@@ -248,11 +443,11 @@ impl Bitmaps {
         let bitmap_properties = Self::create_bitmap_properties(None);
         let border_bitmap =
             unsafe { d2d_context.CreateBitmap(*bitmap_size, None, 0, &bitmap_properties) }
-                .context("border_bitmap")?;
+                .map_err(TackyError::direct2d("border_bitmap"))?;
 
         let mask_bitmap =
             unsafe { d2d_context.CreateBitmap(*bitmap_size, None, 0, &bitmap_properties) }
-                .context("mask_bitmap")?;
+                .map_err(TackyError::direct2d("mask_bitmap"))?;
 
         self.target_bitmap = Some(target_bitmap);
         self.border_bitmap = Some(border_bitmap);
@@ -261,3 +456,18 @@ impl Bitmaps {
         Ok(())
     }
 }
+
+/// Returns whether the display hardware supports presenting frames with tearing.
+fn supports_tearing(dxgi_factory: &IDXGIFactory7) -> bool {
+    let mut allow_tearing = FALSE;
+
+    let result = unsafe {
+        dxgi_factory.CheckFeatureSupport(
+            DXGI_FEATURE_PRESENT_ALLOW_TEARING,
+            &mut allow_tearing as *mut _ as *mut c_void,
+            size_of::<BOOL>() as u32,
+        )
+    };
+
+    result.is_ok() && allow_tearing.as_bool()
+}
@@ -0,0 +1,93 @@
+use crate::border_manager::flash_window;
+use crate::border_manager::window_border;
+use crate::error::LogIfErr;
+use crate::windows_api::HWNDConversion;
+use crate::windows_api::WindowsApi;
+use anyhow::Context;
+use anyhow::anyhow;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering;
+use windows::Win32::Foundation::GetLastError;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::LRESULT;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::UI::WindowsAndMessaging::DeregisterShellHookWindow;
+use windows::Win32::UI::WindowsAndMessaging::RegisterShellHookWindow;
+use windows::Win32::UI::WindowsAndMessaging::RegisterWindowMessageW;
+use windows::core::PCWSTR;
+use windows::core::w;
+
+/// Sent when a window flashes. Not exported by the windows crate.
+const HSHELL_FLASH: u32 = 0x8006;
+
+/// The message shell hook notifications are sent with, registered as "SHELLHOOK".
+static SHELLHOOK_MESSAGE: AtomicU32 = AtomicU32::new(0);
+
+const CLASS_NAME: PCWSTR = w!("tacky-borders-shell-hook");
+
+/// Hidden window that receives shell hook notifications, see `start_shell_hook`.
+#[derive(Debug)]
+pub struct ShellHook {
+    window: HWND,
+}
+
+impl ShellHook {
+    /// Deregisters the window from shell hook notifications and destroys it. Must be called from
+    /// the thread that started the shell hook.
+    pub fn stop(self) -> anyhow::Result<()> {
+        if !unsafe { DeregisterShellHookWindow(self.window) }.as_bool() {
+            return Err(anyhow!(
+                "could not deregister shell hook window: {:?}",
+                unsafe { GetLastError() }
+            ));
+        }
+
+        WindowsApi::destroy_hidden_window(self.window, CLASS_NAME)
+    }
+}
+
+/// Creates a hidden window on the current thread that receives shell hook notifications, which
+/// tell us when a window flashes to request attention (e.g. `FlashWindowEx`).
+///
+/// The window must be owned by a thread that runs a message loop.
+pub fn start_shell_hook() -> anyhow::Result<ShellHook> {
+    let window = WindowsApi::create_hidden_window(CLASS_NAME, Some(shell_hook_proc))
+        .context("could not create shell hook window")?;
+
+    let message = unsafe { RegisterWindowMessageW(w!("SHELLHOOK")) };
+    if message == 0 {
+        return Err(anyhow!(
+            "could not register shell hook message: {:?}",
+            unsafe { GetLastError() }
+        ));
+    }
+    SHELLHOOK_MESSAGE.store(message, Ordering::SeqCst);
+
+    if !unsafe { RegisterShellHookWindow(window) }.as_bool() {
+        return Err(anyhow!(
+            "could not register shell hook window: {:?}",
+            unsafe { GetLastError() }
+        ));
+    }
+
+    Ok(ShellHook { window })
+}
+
+extern "system" fn shell_hook_proc(
+    window: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if message != SHELLHOOK_MESSAGE.load(Ordering::SeqCst) {
+        return WindowsApi::def_window_proc_w(window.as_int(), message, wparam.0, lparam.0);
+    }
+
+    // The flashing window is passed in lparam
+    if wparam.0 as u32 == HSHELL_FLASH && window_border(lparam.0).is_some() {
+        flash_window(lparam.0).log_if_err();
+    }
+
+    LRESULT(0)
+}
@@ -1,23 +1,41 @@
+use crate::border_manager::toggle_animations;
+use crate::border_manager::toggle_borders;
+use crate::border_manager::toggle_foreground_border;
 use crate::core::helpers::type_name_of_val;
 use crate::exit_application;
+use crate::open_log_file;
+use crate::theme_manager::next_theme;
+use crate::theme_manager::prev_theme;
 use crate::user_config::UserConfig;
 use anyhow::Context;
 use anyhow::Error;
 use anyhow::bail;
+use std::path::PathBuf;
 use std::str::FromStr;
 use tray_icon_win::Icon;
 use tray_icon_win::TrayIcon;
 use tray_icon_win::TrayIconBuilder;
+use tray_icon_win::menu::IsMenuItem;
 use tray_icon_win::menu::Menu;
 use tray_icon_win::menu::MenuEvent;
 use tray_icon_win::menu::MenuItem;
 use tray_icon_win::menu::PredefinedMenuItem;
+use tray_icon_win::menu::Submenu;
+
+/// Prefix of the menu ids of the config file submenu, followed by the path of the file.
+const CONFIG_FILE_MENU_PREFIX: &str = "config_file:";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SystemTrayEvent {
     Exit,
     OpenConfig,
+    OpenLogs,
     ReloadConfig,
+    ToggleBorders,
+    ToggleWindowBorder,
+    PauseAnimations,
+    NextTheme,
+    PrevTheme,
 }
 
 impl SystemTrayEvent {
@@ -25,9 +43,15 @@ impl SystemTrayEvent {
         match self {
             SystemTrayEvent::Exit => exit_application(),
             SystemTrayEvent::OpenConfig => UserConfig::open(),
+            SystemTrayEvent::OpenLogs => open_log_file(),
             SystemTrayEvent::ReloadConfig => {
                 let _ = UserConfig::reload();
             }
+            SystemTrayEvent::ToggleBorders => toggle_borders(),
+            SystemTrayEvent::ToggleWindowBorder => toggle_foreground_border(),
+            SystemTrayEvent::PauseAnimations => toggle_animations(),
+            SystemTrayEvent::NextTheme => next_theme(),
+            SystemTrayEvent::PrevTheme => prev_theme(),
         }
     }
 
@@ -35,7 +59,13 @@ impl SystemTrayEvent {
         match self {
             SystemTrayEvent::Exit => type_name_of_val(&exit_application),
             SystemTrayEvent::OpenConfig => type_name_of_val(&UserConfig::open),
+            SystemTrayEvent::OpenLogs => type_name_of_val(&open_log_file),
             SystemTrayEvent::ReloadConfig => type_name_of_val(&UserConfig::reload),
+            SystemTrayEvent::ToggleBorders => type_name_of_val(&toggle_borders),
+            SystemTrayEvent::ToggleWindowBorder => type_name_of_val(&toggle_foreground_border),
+            SystemTrayEvent::PauseAnimations => type_name_of_val(&toggle_animations),
+            SystemTrayEvent::NextTheme => type_name_of_val(&next_theme),
+            SystemTrayEvent::PrevTheme => type_name_of_val(&prev_theme),
         }
     }
 
@@ -57,7 +87,13 @@ impl FromStr for SystemTrayEvent {
         let event_name_split: Vec<&str> = event.split("_").collect();
         match event_name_split.as_slice() {
             ["open", "config"] => Ok(SystemTrayEvent::OpenConfig),
+            ["open", "logs"] => Ok(SystemTrayEvent::OpenLogs),
             ["reload", "config"] => Ok(SystemTrayEvent::ReloadConfig),
+            ["toggle", "borders"] => Ok(SystemTrayEvent::ToggleBorders),
+            ["toggle", "window", "border"] => Ok(SystemTrayEvent::ToggleWindowBorder),
+            ["pause", "animations"] => Ok(SystemTrayEvent::PauseAnimations),
+            ["next", "theme"] => Ok(SystemTrayEvent::NextTheme),
+            ["prev", "theme"] => Ok(SystemTrayEvent::PrevTheme),
             ["exit"] => Ok(SystemTrayEvent::Exit),
             _ => bail!("Invalid menu event: {}", event),
         }
@@ -69,7 +105,13 @@ impl From<SystemTrayEvent> for &'static str {
         match value {
             SystemTrayEvent::Exit => "exit",
             SystemTrayEvent::OpenConfig => "open_config",
+            SystemTrayEvent::OpenLogs => "open_logs",
             SystemTrayEvent::ReloadConfig => "reload_config",
+            SystemTrayEvent::ToggleBorders => "toggle_borders",
+            SystemTrayEvent::ToggleWindowBorder => "toggle_window_border",
+            SystemTrayEvent::PauseAnimations => "pause_animations",
+            SystemTrayEvent::NextTheme => "next_theme",
+            SystemTrayEvent::PrevTheme => "prev_theme",
         }
     }
 }
@@ -107,7 +149,29 @@ impl SystemTray {
         let tray_menu = Menu::new();
         tray_menu.append_items(&[
             &MenuItem::with_id(SystemTrayEvent::OpenConfig, "Open config", true, None),
+            &MenuItem::with_id(SystemTrayEvent::OpenLogs, "Open logs", true, None),
             &MenuItem::with_id(SystemTrayEvent::ReloadConfig, "Reload config", true, None),
+        ])?;
+
+        let config_file_items = Self::create_config_file_items();
+        if config_file_items.len() > 1 {
+            let items: Vec<&dyn IsMenuItem> = config_file_items
+                .iter()
+                .map(|item| item as &dyn IsMenuItem)
+                .collect();
+            tray_menu.append(&Submenu::with_items("Use config file", true, &items)?)?;
+        }
+
+        tray_menu.append_items(&[
+            &MenuItem::with_id(SystemTrayEvent::ToggleBorders, "Toggle borders", true, None),
+            &MenuItem::with_id(
+                SystemTrayEvent::PauseAnimations,
+                "Pause/resume animations",
+                true,
+                None,
+            ),
+            &MenuItem::with_id(SystemTrayEvent::NextTheme, "Next theme", true, None),
+            &MenuItem::with_id(SystemTrayEvent::PrevTheme, "Previous theme", true, None),
             &PredefinedMenuItem::separator(),
             &MenuItem::with_id(SystemTrayEvent::Exit, "Exit", true, None),
         ])?;
@@ -117,7 +181,9 @@ impl SystemTray {
             .with_tooltip(format!("tacky-borders v{}", env!("CARGO_PKG_VERSION")))
             .with_icon(icon)
             .on_menu_event(move |event: MenuEvent| {
-                if let Ok(event) = SystemTrayEvent::from_str(event.id.as_ref()) {
+                if let Some(path) = event.id.as_ref().strip_prefix(CONFIG_FILE_MENU_PREFIX) {
+                    UserConfig::switch_config_file(PathBuf::from(path));
+                } else if let Ok(event) = SystemTrayEvent::from_str(event.id.as_ref()) {
                     event.execute()
                 }
             })
@@ -126,4 +192,24 @@ impl SystemTray {
 
         tray
     }
+
+    /// Creates a menu item for every config file found in the config directory.
+    fn create_config_file_items() -> Vec<MenuItem> {
+        UserConfig::available_config_files()
+            .into_iter()
+            .map(|path| {
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.display().to_string());
+
+                MenuItem::with_id(
+                    format!("{CONFIG_FILE_MENU_PREFIX}{}", path.display()),
+                    name,
+                    true,
+                    None,
+                )
+            })
+            .collect()
+    }
 }
@@ -1,20 +1,35 @@
 use core::fmt;
+use regex::Captures;
+use regex::Regex;
 use schema_jsonrs::JsonSchema;
 use serde::Deserialize;
 use serde::Deserializer;
 use serde::de;
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
 use std::ffi::OsStr;
 use std::fs::DirBuilder;
+use std::fs::canonicalize;
+use std::fs::read_to_string;
 use std::path::Path;
 use std::path::PathBuf;
 
+use crate::app_manager::AppManager;
+use crate::border_manager::restyle_borders;
 use crate::env;
 use crate::env::OStringExt;
 use crate::env::PathBufExt;
 use crate::user_config::UserConfig;
 
 #[derive(PartialEq, Clone, Default, JsonSchema)]
-pub struct ThemeManager(Option<String>);
+#[serde(transparent)]
+pub struct ThemeManager(
+    Option<String>,
+    /// Colors of a theme that `extends` another theme, merged with the ones it inherits, see
+    /// `resolve_theme_extends`
+    #[serde(skip)]
+    Option<BTreeMap<String, String>>,
+);
 
 impl fmt::Debug for ThemeManager {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -71,6 +86,32 @@ impl ThemeManager {
                 .map(|name| name.to_string())
         })
     }
+
+    /// Replaces the color names of a theme that `extends` another theme in `s` with their colors.
+    ///
+    /// The color parser only reads themes from files, so it can't see inherited colors. Returns
+    /// `None` if the theme doesn't extend another theme, in which case its file is used as is.
+    pub fn substitute_colors(&self, s: &str) -> Option<String> {
+        let colors = self.1.as_ref().filter(|colors| !colors.is_empty())?;
+
+        // Longer names go first, so that e.g. `red.light` isn't matched as `red`
+        let mut names = colors
+            .keys()
+            .map(|name| regex::escape(name))
+            .collect::<Vec<_>>();
+        names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+        let pattern = Regex::new(&format!(r"(?i)\b(?:{})\b", names.join("|"))).ok()?;
+
+        let substituted = pattern.replace_all(s, |caps: &Captures| {
+            colors
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(&caps[0]))
+                .map(|(_, color)| color.clone())
+                .unwrap_or_else(|| caps[0].to_string())
+        });
+
+        Some(substituted.into_owned())
+    }
 }
 
 fn get_theme_path(theme_dir: &std::path::Path, theme_name: &str) -> Option<std::path::PathBuf> {
@@ -100,62 +141,254 @@ where
     let theme_name: Option<String> = Option::deserialize(deserializer)?;
 
     match theme_name {
-        Some(theme_name) => {
-            if let Some(theme_path) = fix_absolute_path(&theme_name) {
-                if is_valid_theme(&theme_path) {
-                    match theme_path.clone().try_into_string() {
-                        Some(theme_path_str) => Ok(ThemeManager(Some(theme_path_str))),
-                        None => Err(de::Error::custom(format!(
-                            "theme '{}' is not valid",
-                            theme_path.to_string_lossy()
-                        ))),
-                    }
-                } else {
-                    Err(de::Error::custom(format!(
-                        "theme '{}' is not valid",
-                        theme_path.to_string_lossy()
-                    )))
-                }
-            } else {
-                let config_dir = UserConfig::get_config_dir().map_err(|e| {
-                    de::Error::custom(format!("failed to retrieve the config directory: {}", e))
-                })?;
-
-                let theme_dir = config_dir.join("themes");
-
-                // Ensure theme directory exists, creating it if necessary.
-                if !theme_dir.exists() {
-                    create_theme_directory(&theme_dir).map_err(|e| {
-                        de::Error::custom(format!("failed to create themes directory: {}", e))
-                    })?;
-
-                    return Err(de::Error::custom(format!(
-                        "theme '{}' is not found in the newly created themes directory",
-                        theme_name
-                    )));
-                }
+        Some(theme_name) => resolve_theme(&theme_name).map_err(de::Error::custom),
+        None => Ok(ThemeManager(None, None)),
+    }
+}
 
-                // Try to find the theme file with any valid extension.
-                if let Some(theme_path) = get_theme_path(&theme_dir, &theme_name) {
-                    match theme_path.clone().try_into_string() {
-                        Some(theme_path_str) => Ok(ThemeManager(Some(theme_path_str))),
-                        None => Err(de::Error::custom(format!(
-                            "theme '{}' is not found in the themes directory",
-                            theme_path.to_string_lossy()
-                        ))),
-                    }
-                } else {
-                    Err(de::Error::custom(format!(
-                        "theme '{}' is not found in the themes directory",
-                        theme_name
-                    )))
+/// Deserializes a list of theme names or paths, resolving each like `deserialize_theme`.
+pub fn deserialize_themes<'de, D>(deserializer: D) -> Result<Vec<ThemeManager>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let theme_names: Vec<String> = Vec::deserialize(deserializer)?;
+
+    theme_names
+        .iter()
+        .map(|theme_name| resolve_theme(theme_name).map_err(de::Error::custom))
+        .collect()
+}
+
+/// Resolves a theme name or absolute path to the theme file it refers to.
+///
+/// Themes that extend another theme also keep all of their colors, see `resolve_theme_extends`.
+fn resolve_theme(theme_name: &str) -> Result<ThemeManager, String> {
+    let theme_path = find_theme(theme_name)?;
+    let colors = resolve_theme_extends(&theme_path)?;
+
+    match theme_path.clone().try_into_string() {
+        Some(theme_path_str) => Ok(ThemeManager(Some(theme_path_str), colors)),
+        None => Err(format!(
+            "theme '{}' is not valid",
+            theme_path.to_string_lossy()
+        )),
+    }
+}
+
+/// Finds the file of a theme, given its name within the themes directory or an absolute path.
+fn find_theme(theme_name: &str) -> Result<PathBuf, String> {
+    if let Some(theme_path) = fix_absolute_path(theme_name) {
+        return match is_valid_theme(&theme_path) {
+            true => Ok(theme_path),
+            false => Err(format!(
+                "theme '{}' is not valid",
+                theme_path.to_string_lossy()
+            )),
+        };
+    }
+
+    let config_dir = UserConfig::get_config_dir()
+        .map_err(|e| format!("failed to retrieve the config directory: {}", e))?;
+
+    let theme_dir = config_dir.join("themes");
+
+    // Ensure theme directory exists, creating it if necessary.
+    if !theme_dir.exists() {
+        create_theme_directory(&theme_dir)
+            .map_err(|e| format!("failed to create themes directory: {}", e))?;
+
+        return Err(format!(
+            "theme '{}' is not found in the newly created themes directory",
+            theme_name
+        ));
+    }
+
+    // Try to find the theme file with any valid extension.
+    get_theme_path(&theme_dir, theme_name).ok_or_else(|| {
+        format!(
+            "theme '{}' is not found in the themes directory",
+            theme_name
+        )
+    })
+}
+
+/// The name or path of the base theme a theme file inherits from, if any.
+///
+/// Everything else in the file is ignored, so only themes with `extends` are parsed in full.
+#[derive(Deserialize)]
+struct ThemeHeader {
+    extends: Option<String>,
+}
+
+/// The contents of a theme file: color names mapped to colors, and optionally the name or path
+/// of a base theme whose colors are inherited.
+#[derive(Deserialize)]
+struct ThemeDefinition {
+    extends: Option<String>,
+    #[serde(flatten)]
+    colors: BTreeMap<String, ThemeValue>,
+}
+
+/// A value of a theme file. Subthemes are referenced with dotted names, e.g. `red.light`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ThemeValue {
+    Subtheme(BTreeMap<String, ThemeValue>),
+    Color(String),
+    Number(f64),
+    Bool(bool),
+}
+
+impl ThemeValue {
+    /// Adds this value to `colors` under `name`, and each color of a subtheme as `name.color`.
+    fn flatten_into(self, name: String, colors: &mut BTreeMap<String, String>) {
+        match self {
+            ThemeValue::Subtheme(subtheme) => {
+                for (key, value) in subtheme {
+                    value.flatten_into(format!("{name}.{key}"), colors);
                 }
             }
+            ThemeValue::Color(color) => {
+                colors.insert(name, color);
+            }
+            ThemeValue::Number(number) => {
+                colors.insert(name, number.to_string());
+            }
+            ThemeValue::Bool(boolean) => {
+                colors.insert(name, boolean.to_string());
+            }
+        }
+    }
+}
+
+/// Merges a theme that `extends` another theme with its base themes, and returns all of their
+/// colors. Colors of a theme take precedence over those it inherits.
+///
+/// Themes that don't extend another theme are used as is, so `None` is returned.
+fn resolve_theme_extends(theme_path: &Path) -> Result<Option<BTreeMap<String, String>>, String> {
+    match read_theme::<ThemeHeader>(theme_path)? {
+        Some(ThemeHeader { extends: Some(_) }) => {
+            let theme = read_theme(theme_path)?.ok_or_else(|| unsupported_theme(theme_path))?;
+            merge_theme(theme_path, theme, &mut Vec::new()).map(Some)
         }
-        None => Ok(ThemeManager(None)),
+        _ => Ok(None),
     }
 }
 
+/// Returns the colors of a theme, including the ones inherited through `extends`.
+///
+/// `chain` holds the themes currently being merged, so that cycles are reported instead of
+/// recursing forever.
+fn merge_theme(
+    theme_path: &Path,
+    theme: ThemeDefinition,
+    chain: &mut Vec<PathBuf>,
+) -> Result<BTreeMap<String, String>, String> {
+    let canonical_path = canonicalize(theme_path).unwrap_or_else(|_| theme_path.to_path_buf());
+
+    if let Some(start) = chain.iter().position(|path| *path == canonical_path) {
+        let cycle = chain[start..]
+            .iter()
+            .chain([&canonical_path])
+            .map(|path| path.file_stem().unwrap_or_default().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+
+        return Err(format!("theme inheritance cycle detected: {cycle}"));
+    }
+
+    chain.push(canonical_path);
+
+    let mut colors = match theme.extends {
+        Some(base_name) => {
+            let base_path = find_theme(&base_name)?;
+            let base = read_theme(&base_path)?.ok_or_else(|| unsupported_theme(&base_path))?;
+
+            merge_theme(&base_path, base, chain)?
+        }
+        None => BTreeMap::new(),
+    };
+    for (name, value) in theme.colors {
+        value.flatten_into(name, &mut colors);
+    }
+
+    Ok(colors)
+}
+
+fn unsupported_theme(theme_path: &Path) -> String {
+    format!(
+        "theme '{}' can't be extended, since its format is not supported",
+        theme_path.to_string_lossy()
+    )
+}
+
+/// Reads a theme file, or returns `None` if its format isn't supported by the enabled features.
+fn read_theme<T: DeserializeOwned>(theme_path: &Path) -> Result<Option<T>, String> {
+    let read = || {
+        read_to_string(theme_path).map_err(|e| {
+            format!(
+                "failed to read theme '{}': {}",
+                theme_path.to_string_lossy(),
+                e
+            )
+        })
+    };
+    let invalid = |e: &dyn std::fmt::Display| {
+        format!(
+            "theme '{}' is not valid: {}",
+            theme_path.to_string_lossy(),
+            e
+        )
+    };
+
+    match theme_path.extension().and_then(OsStr::to_str) {
+        #[cfg(feature = "json")]
+        Some("json" | "jsonc") => serde_jsonc2::from_str(&read()?)
+            .map(Some)
+            .map_err(|e| invalid(&e)),
+        #[cfg(feature = "yml")]
+        Some("yaml") => serde_yml::from_str(&read()?)
+            .map(Some)
+            .map_err(|e| invalid(&e)),
+        _ => Ok(None),
+    }
+}
+
+/// Switches to the next theme in the config's `themes`.
+pub fn next_theme() {
+    cycle_theme(1);
+}
+
+/// Switches to the previous theme in the config's `themes`.
+pub fn prev_theme() {
+    cycle_theme(-1);
+}
+
+/// Moves `step` themes forward (or backward, if negative) through the configured `theme` followed
+/// by the config's `themes`, wrapping around at either end. The chosen theme is kept for the rest
+/// of the session, even across reloads, and applied to the existing borders in place.
+fn cycle_theme(step: isize) {
+    let app_manager = AppManager::get();
+    let themes = app_manager.config().theme_cycle();
+
+    if themes.len() < 2 {
+        warn!("[cycle_theme] no themes to switch to; add some to 'themes' in the config");
+        return;
+    }
+
+    let current = app_manager.active_theme();
+    let index = themes
+        .iter()
+        .position(|theme| *theme == current)
+        .unwrap_or(0);
+    let next = themes[(index as isize + step).rem_euclid(themes.len() as isize) as usize].clone();
+
+    info!("[cycle_theme] Theme: {next}");
+    app_manager.set_active_theme(Some(next));
+
+    restyle_borders();
+}
+
 fn create_theme_directory(path: &Path) -> anyhow::Result<()> {
     DirBuilder::new().recursive(true).create(path)?;
     info!("created theme directory at {:?}", path);
@@ -179,3 +412,99 @@ fn is_valid_theme(path: &Path) -> bool {
         None => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::create_dir_all;
+    use std::fs::write;
+
+    /// Writes each `(name, contents)` pair to `<name>.json` in a fresh directory for `test_name`.
+    fn write_themes(test_name: &str, themes: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tacky-borders-{test_name}"));
+        create_dir_all(&dir).unwrap();
+        for (name, contents) in themes {
+            write(dir.join(format!("{name}.json")), contents).unwrap();
+        }
+        dir
+    }
+
+    fn theme_path(dir: &Path, name: &str) -> String {
+        dir.join(format!("{name}.json"))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn merge(theme_path: &Path) -> Result<BTreeMap<String, String>, String> {
+        let theme = read_theme(theme_path)?.unwrap();
+        merge_theme(theme_path, theme, &mut Vec::new())
+    }
+
+    #[test]
+    fn merges_base_themes_in_order() {
+        let dir = write_themes("merge-order", &[]);
+        let base = r##"{ "red": "#ff0000", "green": "#00ff00", "blue": "#0000ff" }"##;
+        let middle = format!(
+            r##"{{ "extends": {:?}, "green": "#00aa00", "accent": {{ "light": "#eeeeee" }} }}"##,
+            theme_path(&dir, "base")
+        );
+        let top = format!(
+            r##"{{ "extends": {:?}, "blue": "#0000aa", "opacity": 0.5 }}"##,
+            theme_path(&dir, "middle")
+        );
+        write_themes(
+            "merge-order",
+            &[("base", base), ("middle", &middle), ("top", &top)],
+        );
+
+        let colors = merge(Path::new(&theme_path(&dir, "top"))).unwrap();
+
+        assert_eq!(colors["red"], "#ff0000");
+        assert_eq!(colors["green"], "#00aa00");
+        assert_eq!(colors["blue"], "#0000aa");
+        assert_eq!(colors["accent.light"], "#eeeeee");
+        assert_eq!(colors["opacity"], "0.5");
+        assert!(!colors.contains_key("extends"));
+    }
+
+    #[test]
+    fn theme_colors_override_inherited_ones() {
+        let dir = write_themes("merge-override", &[]);
+        let base = r##"{ "accent": { "light": "#ffffff", "dark": "#000000" } }"##;
+        let theme = format!(
+            r##"{{ "extends": {:?}, "accent": {{ "dark": "#111111" }} }}"##,
+            theme_path(&dir, "base")
+        );
+        write_themes("merge-override", &[("base", base), ("theme", &theme)]);
+
+        let colors = merge(Path::new(&theme_path(&dir, "theme"))).unwrap();
+
+        assert_eq!(colors["accent.light"], "#ffffff");
+        assert_eq!(colors["accent.dark"], "#111111");
+    }
+
+    #[test]
+    fn detects_inheritance_cycles() {
+        let dir = write_themes("merge-cycle", &[]);
+        let first = format!(r#"{{ "extends": {:?} }}"#, theme_path(&dir, "second"));
+        let second = format!(r#"{{ "extends": {:?} }}"#, theme_path(&dir, "first"));
+        write_themes("merge-cycle", &[("first", &first), ("second", &second)]);
+
+        let err = merge(Path::new(&theme_path(&dir, "first"))).unwrap_err();
+
+        assert_eq!(
+            err,
+            "theme inheritance cycle detected: first -> second -> first"
+        );
+    }
+
+    #[test]
+    fn themes_without_extends_are_used_as_is() {
+        let dir = write_themes("merge-none", &[("plain", r#"{ "list": [1, 2] }"#)]);
+
+        assert_eq!(
+            resolve_theme_extends(Path::new(&theme_path(&dir, "plain"))),
+            Ok(None)
+        );
+    }
+}
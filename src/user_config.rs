@@ -1,9 +1,12 @@
 use crate::animation::AnimationsConfig;
 use crate::app_manager::AppManager;
 use crate::border_manager::reload_borders;
+use crate::border_manager::restyle_borders;
 use crate::colors::GlobalColor;
+use crate::core::helpers::GlobPattern;
 use crate::core::helpers::parse_length_str;
-use crate::core::helpers::serde_default_i32;
+use crate::core::helpers::serde_default_bool;
+use crate::core::helpers::serde_default_f32;
 use crate::core::helpers::serde_default_u32;
 use crate::core::keybindings::KeybindingConfig;
 use crate::core::keybindings::Keybindings;
@@ -11,9 +14,15 @@ use crate::core::value::Value;
 use crate::core::value::ValueConversion;
 use crate::effect::EffectsConfig;
 use crate::error::LogIfErr;
+use crate::error::TackyError;
+use crate::game_mode::GameModeConfig;
 use crate::keyboard_hook::KEYBOARD_HOOK;
+use crate::parsed_config::ParsedConfig;
+use crate::render_resources::RenderConfig;
+use crate::set_log_level;
 use crate::theme_manager::ThemeManager;
 use crate::theme_manager::deserialize_theme;
+use crate::theme_manager::deserialize_themes;
 use crate::windows_api::WindowsApi;
 use anyhow::Context;
 use anyhow::anyhow;
@@ -23,10 +32,13 @@ use serde::Deserialize;
 use serde::Deserializer;
 use serde::Serialize;
 use serde::de;
+use sp_log2::LevelFilter;
+use std::collections::BTreeMap;
 use std::fs::DirBuilder;
 use std::fs::exists;
 use std::fs::read_to_string;
 use std::fs::write;
+use std::num::NonZeroUsize;
 use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -64,6 +76,125 @@ pub enum ConfigFormat {
     None,
 }
 
+/// Width of a border, either a single dimension for every edge or one dimension per edge.
+#[derive(Debug, PartialEq, Clone, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum BorderWidthConfig {
+    /// The same width for every edge (e.g., `2` or `"2px"`).
+    Uniform(Value),
+    /// A separate width for each edge (e.g., `{ top: 4, bottom: 1, left: 2, right: 2 }`).
+    Edges(BorderEdgeWidthsConfig),
+}
+
+impl Default for BorderWidthConfig {
+    fn default() -> Self {
+        BorderWidthConfig::Uniform(Value::Number(1.0))
+    }
+}
+
+/// Per-edge border widths. Edges left unspecified have no border.
+#[derive(Debug, PartialEq, Clone, Default, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct BorderEdgeWidthsConfig {
+    /// Width of the top edge.
+    pub top: Option<Value>,
+    /// Width of the right edge.
+    pub right: Option<Value>,
+    /// Width of the bottom edge.
+    pub bottom: Option<Value>,
+    /// Width of the left edge.
+    pub left: Option<Value>,
+}
+
+/// Line pattern used to draw a border.
+#[derive(Debug, PartialEq, Clone, Copy, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StrokeKind {
+    /// A continuous line.
+    #[default]
+    Solid,
+    /// A line broken into dashes.
+    Dashed,
+    /// A line of round dots.
+    Dotted,
+    /// Two parallel lines, each a third of the border width.
+    Double,
+}
+
+/// Most verbose level of messages written to the log.
+#[derive(Debug, PartialEq, Clone, Copy, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => LevelFilter::Error,
+            LogLevel::Warn => LevelFilter::Warn,
+            LogLevel::Info => LevelFilter::Info,
+            LogLevel::Debug => LevelFilter::Debug,
+            LogLevel::Trace => LevelFilter::Trace,
+        }
+    }
+}
+
+/// How a border waits for its window to finish restoring from minimized before showing again.
+#[derive(Debug, PartialEq, Clone, Copy, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum UnminimizeDetection {
+    /// Waits for `unminimize_delay` milliseconds.
+    #[default]
+    Delay,
+    /// Polls the window's position until it stops changing, for at most `unminimize_delay`
+    /// milliseconds.
+    Settle,
+}
+
+/// Corner shape used instead of the one a window reports to DWM, see `BorderStyle::Auto`.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ForcedCorners {
+    /// Rounded corners, like `BorderStyle::Round`.
+    Round,
+    /// Slightly rounded corners, like `BorderStyle::SmallRound`.
+    Small,
+    /// Square corners, like `BorderStyle::Square`.
+    Square,
+}
+
+/// Where a border is drawn relative to the edge of its window.
+#[derive(Debug, PartialEq, Clone, Copy, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BorderPlacement {
+    /// Around the window, outside its bounds.
+    #[default]
+    Outside,
+    /// Over the window's own edge, inside its bounds.
+    Inside,
+    /// Centered on the window's edge.
+    Center,
+}
+
+/// Defines how the line of a border is drawn.
+#[derive(Debug, PartialEq, Clone, Default, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct StrokeStyleConfig {
+    /// Line pattern of the border (e.g., "solid", "dashed", "dotted", "double").
+    pub kind: StrokeKind,
+    /// Length of each dash in pixels. Defaults to twice the border width.
+    pub dash: Option<Value>,
+    /// Length of the gap between dashes or dots in pixels. Defaults to twice the border width
+    /// for dashes and to the border width for dots.
+    pub gap: Option<Value>,
+}
+
 /// Defines options for border radius customization.
 #[derive(Debug, PartialEq, Clone, Default, JsonSchema)]
 pub enum BorderStyle {
@@ -78,6 +209,53 @@ pub enum BorderStyle {
     Auto,
     /// Custom border radius, specified in pixels.
     Radius(f32),
+    /// Custom radius for each corner (top left, top right, bottom right, bottom left), specified
+    /// in pixels.
+    Radius4 { tl: f32, tr: f32, br: f32, bl: f32 },
+}
+
+/// Radius of each corner of a border in pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CornerRadii {
+    pub top_left: f32,
+    pub top_right: f32,
+    pub bottom_right: f32,
+    pub bottom_left: f32,
+}
+
+impl CornerRadii {
+    pub fn uniform(radius: f32) -> Self {
+        Self {
+            top_left: radius,
+            top_right: radius,
+            bottom_right: radius,
+            bottom_left: radius,
+        }
+    }
+
+    /// Returns the largest radius.
+    pub fn max(&self) -> f32 {
+        self.top_left
+            .max(self.top_right)
+            .max(self.bottom_right)
+            .max(self.bottom_left)
+    }
+
+    /// Grows (or, with a negative `amount`, shrinks) every radius by `amount`, e.g. to follow a
+    /// rectangle that was expanded by that amount. Square corners stay square.
+    pub fn expand(&self, amount: f32) -> Self {
+        let expand = |radius: f32| match radius {
+            0.0 => 0.0,
+            _ => (radius + amount).max(0.0),
+        };
+
+        Self {
+            top_left: expand(self.top_left),
+            top_right: expand(self.top_right),
+            bottom_right: expand(self.bottom_right),
+            bottom_left: expand(self.bottom_left),
+        }
+    }
 }
 
 impl<'de> Deserialize<'de> for BorderStyle {
@@ -95,9 +273,20 @@ impl<'de> Deserialize<'de> for BorderStyle {
             "AUTO" => Ok(BorderStyle::Auto),
             _ if upper.starts_with("RADIUS(") && upper.ends_with(')') => {
                 let inner = &s[7..s.len() - 1];
-                parse_length_str(inner)
-                    .map(|value| BorderStyle::Radius(value as f32))
-                    .ok_or_else(|| de::Error::custom("Invalid radius value"))
+                let radii = inner
+                    .split(',')
+                    .map(|value| parse_length_str(value.trim()).map(|value| value as f32))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| de::Error::custom("Invalid radius value"))?;
+
+                match radii[..] {
+                    [radius] => Ok(BorderStyle::Radius(radius)),
+                    [tl, tr, br, bl] if tl == tr && tl == br && tl == bl => {
+                        Ok(BorderStyle::Radius(tl))
+                    }
+                    [tl, tr, br, bl] => Ok(BorderStyle::Radius4 { tl, tr, br, bl }),
+                    _ => Err(de::Error::custom("Expected 1 or 4 radius values")),
+                }
             }
             _ => Err(de::Error::custom("Invalid border style")),
         }
@@ -105,13 +294,28 @@ impl<'de> Deserialize<'de> for BorderStyle {
 }
 
 impl BorderStyle {
-    pub fn to_radius(&self, border_width: i32, dpi: f32, tracking_window: isize) -> f32 {
+    /// Converts the style to a radius in pixels. `Auto` follows the window's corner preference
+    /// unless `force_corners` is set.
+    pub fn to_radius(
+        &self,
+        border_width: i32,
+        dpi: f32,
+        tracking_window: isize,
+        force_corners: Option<ForcedCorners>,
+    ) -> f32 {
         let base_radius = (border_width as f32) / 2.0;
         let scale_factor = dpi / 96.0;
 
         match self {
             BorderStyle::Radius(-1.0) | BorderStyle::Auto => {
-                match WindowsApi::get_window_corner_preference(tracking_window) {
+                let corner_preference = match force_corners {
+                    Some(ForcedCorners::Round) => DWMWCP_ROUND,
+                    Some(ForcedCorners::Small) => DWMWCP_ROUNDSMALL,
+                    Some(ForcedCorners::Square) => DWMWCP_DONOTROUND,
+                    None => WindowsApi::get_window_corner_preference(tracking_window),
+                };
+
+                match corner_preference {
                     DWMWCP_DEFAULT | DWMWCP_ROUND => 8.0 * scale_factor + base_radius,
                     DWMWCP_ROUNDSMALL => 4.0 * scale_factor + base_radius,
                     DWMWCP_DONOTROUND => 0.0,
@@ -122,6 +326,26 @@ impl BorderStyle {
             BorderStyle::SmallRound => 4.0 * scale_factor + base_radius,
             BorderStyle::Square => 0.0,
             BorderStyle::Radius(radius) => radius * scale_factor,
+            BorderStyle::Radius4 { .. } => self
+                .to_corner_radii(dpi)
+                .map(|radii| radii.max())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Returns the radius of each corner for styles with per-corner radii, or `None` if every
+    /// corner uses the radius from `to_radius`.
+    pub fn to_corner_radii(&self, dpi: f32) -> Option<CornerRadii> {
+        let scale_factor = dpi / 96.0;
+
+        match *self {
+            BorderStyle::Radius4 { tl, tr, br, bl } => Some(CornerRadii {
+                top_left: tl * scale_factor,
+                top_right: tr * scale_factor,
+                bottom_right: br * scale_factor,
+                bottom_left: bl * scale_factor,
+            }),
+            _ => None,
         }
     }
 }
@@ -135,6 +359,10 @@ pub enum MatchKind {
     Class,
     /// Match based on the process name or executable associated with the window.
     Process,
+    /// Match based on the full path of the executable associated with the window.
+    Path,
+    /// Match windows that have all of the listed style flags (e.g., `WS_EX_LAYERED | WS_POPUP`).
+    Style,
 }
 
 impl FromStr for MatchKind {
@@ -145,6 +373,8 @@ impl FromStr for MatchKind {
             "title" => Ok(MatchKind::Title),
             "class" => Ok(MatchKind::Class),
             "process" => Ok(MatchKind::Process),
+            "path" => Ok(MatchKind::Path),
+            "style" => Ok(MatchKind::Style),
             _ => Err(anyhow!("MatchKind {s} does not exist")),
         }
     }
@@ -169,6 +399,8 @@ pub enum MatchStrategy {
     Regex,
     /// Match values that contain the specified substring.
     Contains,
+    /// Match values using `*` and `?` wildcards (e.g., `Visual Studio*`).
+    Glob,
 }
 
 impl FromStr for MatchStrategy {
@@ -179,6 +411,7 @@ impl FromStr for MatchStrategy {
             "contains" => Ok(MatchStrategy::Contains),
             "equals" => Ok(MatchStrategy::Equals),
             "regex" => Ok(MatchStrategy::Regex),
+            "glob" => Ok(MatchStrategy::Glob),
             _ => Err(anyhow!("MatchStrategy {s} does not exist")),
         }
     }
@@ -207,10 +440,23 @@ impl MatchStrategy {
             MatchStrategy::Regex => Regex::new(value_2)
                 .map(|re| re.captures(value_1).is_some())
                 .unwrap_or(false),
+            MatchStrategy::Glob => GlobPattern::new(value_2).is_match(value_1),
         }
     }
 }
 
+/// A single condition a window must satisfy, matching one of its properties against a value.
+#[derive(Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MatchConditionConfig {
+    /// Type of match (e.g., title, class, or process).
+    pub kind: MatchKind,
+    /// The value to match against (e.g., window title or class name).
+    pub value: String,
+    /// Strategy for matching, such as exact match or regex. Defaults to `Equals`.
+    pub strategy: Option<MatchStrategy>,
+}
+
 /// Represents criteria used to match windows for applying specific configurations.
 #[derive(Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -224,30 +470,82 @@ pub struct WindowMatchConfig {
     /// Strategy for matching, such as exact match or regex.
     #[serde(rename = "strategy")]
     pub match_strategy: Option<MatchStrategy>,
+    /// Additional conditions that must all match, alongside `kind`/`value` if given.
+    pub conditions: Option<Vec<MatchConditionConfig>>,
     /// Color for the border when the window is active.
     pub active_color: Option<GlobalColor>,
     /// Color for the border when the window is inactive.
     pub inactive_color: Option<GlobalColor>,
     /// Animation settings for the window borders.
     pub animations: Option<AnimationsConfig>,
-    /// Effect settings for the window borders.
+    /// Effect settings for the window borders. `false` turns off the global effects for the
+    /// window, `true` keeps them.
+    #[serde(deserialize_with = "deserialize_optional_effects", default)]
     pub effects: Option<EffectsConfig>,
     /// Radius of the border corners.
     pub border_style: Option<BorderStyle>,
-    /// Width of the border in pixels.
-    #[serde(deserialize_with = "deserialize_optional_dimension", default)]
-    pub border_width: Option<i32>,
-    /// Offset of the border relative to the window.
+    /// Line pattern of the border.
+    pub stroke_style: Option<StrokeStyleConfig>,
+    /// Where the border is drawn relative to the window's edge.
+    pub placement: Option<BorderPlacement>,
+    /// Corner shape used by the "auto" border style instead of the window's corner preference.
+    pub force_corners: Option<ForcedCorners>,
+    /// Extra strokes drawn behind the border, from back to front.
+    pub layers: Option<Vec<BorderLayerConfig>>,
+    /// Width of the border in pixels, either for every edge or per edge.
+    pub border_width: Option<BorderWidthConfig>,
+    /// Offset of the border relative to the window, in pixels or as a percentage of the window's
+    /// smaller dimension (e.g., `"5%"`).
     #[serde(deserialize_with = "deserialize_optional_dimension", default)]
-    pub border_offset: Option<i32>,
+    pub border_offset: Option<Value>,
     /// Whether borders are enabled for this match.
     #[serde(rename = "enabled")]
     pub enabled: Option<bool>,
-    /// Delay (in milliseconds) before applying the border after initialization.
-    pub initialize_delay: Option<u32>,
-    /// Delay (in milliseconds) before applying the border after unminimizing.
-    #[serde(alias = "restore_delay")]
-    pub unminimize_delay: Option<u32>,
+    /// Delay before applying the border after initialization, in milliseconds or as a duration
+    /// (e.g., `"0.25s"`).
+    #[serde(deserialize_with = "deserialize_optional_duration", default)]
+    pub initialize_delay: Option<Value>,
+    /// Delay before applying the border after unminimizing, in milliseconds or as a duration.
+    #[serde(
+        alias = "restore_delay",
+        deserialize_with = "deserialize_optional_duration",
+        default
+    )]
+    pub unminimize_delay: Option<Value>,
+    /// Time (in milliseconds) a window must stay visible before its border is shown.
+    pub creation_grace_period: Option<u32>,
+    /// Only show the border while the cursor hovers over the window.
+    pub hover_only: Option<bool>,
+    /// Always use the active color for this window, even when it isn't focused.
+    pub always_active: Option<bool>,
+    /// Number of update and render passes performed when the border is first shown.
+    pub initial_render_passes: Option<u32>,
+    /// Delay (in milliseconds) between the initial render passes.
+    pub initial_render_interval: Option<u32>,
+    /// Clip the border and effects to the bounds of the window's monitor.
+    pub clip_to_monitor: Option<bool>,
+    /// Opacity multiplier (0.0 to 1.0) applied to the active color.
+    pub active_opacity: Option<f32>,
+    /// Opacity multiplier (0.0 to 1.0) applied to the inactive color.
+    pub inactive_opacity: Option<f32>,
+}
+
+/// Settings that override the global and window rule settings for borders on a monitor.
+#[derive(Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MonitorOverrideConfig {
+    /// Width of the border in pixels, either for every edge or per edge.
+    pub border_width: Option<BorderWidthConfig>,
+    /// Offset of the border relative to the window, in pixels or as a percentage of the window's
+    /// smaller dimension (e.g., `"5%"`).
+    #[serde(deserialize_with = "deserialize_optional_dimension", default)]
+    pub border_offset: Option<Value>,
+    /// Radius of the border corners.
+    pub border_style: Option<BorderStyle>,
+    /// Color for the border when the window is active.
+    pub active_color: Option<GlobalColor>,
+    /// Color for the border when the window is inactive.
+    pub inactive_color: Option<GlobalColor>,
 }
 
 /// Represents a rule for a specific window, including matching criteria and associated actions.
@@ -256,35 +554,61 @@ pub struct WindowRuleConfig {
     /// The matching details and settings for a specific type of window.
     #[serde(rename = "match")]
     pub match_window: WindowMatchConfig,
+    /// Precedence of the rule when several rules match a window. Higher values win, and ties go
+    /// to the rule listed first.
+    #[serde(default)]
+    pub priority: i32,
+}
+
+fn serde_default_border_offset() -> Value {
+    Value::Number(-1.0)
+}
+
+fn serde_default_initialize_delay() -> Value {
+    Value::Number(250.0)
+}
+
+fn serde_default_unminimize_delay() -> Value {
+    Value::Number(200.0)
 }
 
 fn serde_default_global() -> GlobalRuleConfig {
     GlobalRuleConfig {
-        border_width: serde_default_i32::<2>(),
-        border_offset: serde_default_i32::<-1>(),
+        border_width: BorderWidthConfig::Uniform(Value::Number(2.0)),
         ..Default::default()
     }
 }
 
 /// Contains global configuration settings applied across all windows.
-#[derive(Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+#[derive(Debug, Deserialize, Clone, PartialEq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct GlobalRuleConfig {
-    /// Default width of the window borders.
-    #[serde(
-        deserialize_with = "deserialize_dimension",
-        default = "serde_default_i32::<1>"
-    )]
-    pub border_width: i32,
-    /// Default offset for the window borders.
+    /// Default width of the window borders, either for every edge or per edge.
+    #[serde(default)]
+    pub border_width: BorderWidthConfig,
+    /// Default offset for the window borders, in pixels or as a percentage of the window's smaller
+    /// dimension (e.g., `"5%"`).
     #[serde(
         deserialize_with = "deserialize_dimension",
-        default = "serde_default_i32::<-1>"
+        default = "serde_default_border_offset"
     )]
-    pub border_offset: i32,
+    pub border_offset: Value,
     /// Default border radius settings.
     #[serde(default)]
     pub border_style: BorderStyle,
+    /// Default line pattern of the window borders.
+    #[serde(default)]
+    pub stroke_style: StrokeStyleConfig,
+    /// Where borders are drawn relative to their window's edge (e.g., "outside", "inside").
+    #[serde(default)]
+    pub placement: BorderPlacement,
+    /// Corner shape used by the "auto" border style instead of each window's corner preference,
+    /// for apps that report a misleading one.
+    #[serde(default)]
+    pub force_corners: Option<ForcedCorners>,
+    /// Extra strokes drawn behind the borders, from back to front.
+    #[serde(default)]
+    pub layers: Vec<BorderLayerConfig>,
     /// Default color for active window borders.
     #[serde(default)]
     pub active_color: GlobalColor,
@@ -297,12 +621,114 @@ pub struct GlobalRuleConfig {
     /// Effect settings for borders.
     #[serde(default)]
     pub effects: EffectsConfig,
-    /// Delay (in milliseconds) before applying borders after initialization.
-    #[serde(alias = "init_delay", default = "serde_default_u32::<250>")]
-    pub initialize_delay: u32,
-    /// Delay (in milliseconds) before applying borders after unminimizing.
-    #[serde(alias = "restore_delay", default = "serde_default_u32::<200>")]
-    pub unminimize_delay: u32,
+    /// Delay before applying borders after initialization, in milliseconds or as a duration
+    /// (e.g., `"0.25s"`).
+    #[serde(
+        alias = "init_delay",
+        deserialize_with = "deserialize_duration",
+        default = "serde_default_initialize_delay"
+    )]
+    pub initialize_delay: Value,
+    /// Delay before applying borders after unminimizing, in milliseconds or as a duration.
+    #[serde(
+        alias = "restore_delay",
+        deserialize_with = "deserialize_duration",
+        default = "serde_default_unminimize_delay"
+    )]
+    pub unminimize_delay: Value,
+    /// How borders wait for their window to finish restoring (e.g., "delay", "settle").
+    #[serde(alias = "restore_detection", default)]
+    pub unminimize_detection: UnminimizeDetection,
+    /// Time (in milliseconds) a window must stay visible before its border is shown.
+    #[serde(default)]
+    pub creation_grace_period: u32,
+    /// Only show borders while the cursor hovers over their window.
+    #[serde(default)]
+    pub hover_only: bool,
+    /// Briefly highlights the previously focused window's border after focus moves away.
+    #[serde(default)]
+    pub focus_trail: Option<FocusTrailConfig>,
+    /// Color a border flashes when its window requests attention, e.g. a taskbar flash.
+    #[serde(default)]
+    pub flash_color: Option<GlobalColor>,
+    /// Duration (in milliseconds) of the fade from the flash color back to the border color.
+    #[serde(default = "serde_default_u32::<500>")]
+    pub flash_duration: u32,
+    /// Duration (in milliseconds) of the fade to a border's new color when a config reload changes
+    /// it. `0` switches colors instantly.
+    #[serde(default = "serde_default_u32::<250>")]
+    pub color_transition_duration: u32,
+    /// Clip borders and effects to the bounds of the monitor their window is on.
+    #[serde(default)]
+    pub clip_to_monitor: bool,
+    /// Opacity multiplier (0.0 to 1.0) applied to active colors.
+    #[serde(default = "serde_default_f32::<1>")]
+    pub active_opacity: f32,
+    /// Opacity multiplier (0.0 to 1.0) applied to inactive colors.
+    #[serde(default = "serde_default_f32::<1>")]
+    pub inactive_opacity: f32,
+}
+
+// `Value` has no sensible default, so this spells out the same defaults as the serde attributes
+impl Default for GlobalRuleConfig {
+    fn default() -> Self {
+        Self {
+            border_width: BorderWidthConfig::default(),
+            border_offset: serde_default_border_offset(),
+            border_style: BorderStyle::default(),
+            stroke_style: StrokeStyleConfig::default(),
+            placement: BorderPlacement::default(),
+            force_corners: None,
+            layers: Vec::new(),
+            active_color: GlobalColor::default(),
+            inactive_color: GlobalColor::default(),
+            animations: AnimationsConfig::default(),
+            effects: EffectsConfig::default(),
+            initialize_delay: serde_default_initialize_delay(),
+            unminimize_delay: serde_default_unminimize_delay(),
+            unminimize_detection: UnminimizeDetection::default(),
+            creation_grace_period: 0,
+            hover_only: false,
+            focus_trail: None,
+            flash_color: None,
+            flash_duration: serde_default_u32::<500>(),
+            color_transition_duration: serde_default_u32::<250>(),
+            clip_to_monitor: false,
+            active_opacity: serde_default_f32::<1>(),
+            inactive_opacity: serde_default_f32::<1>(),
+        }
+    }
+}
+
+/// Settings for the fading highlight left on a border after its window loses focus.
+#[derive(Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FocusTrailConfig {
+    /// Color the border starts from before fading to its inactive color.
+    pub color: GlobalColor,
+    /// Duration (in milliseconds) of the fade.
+    #[serde(default = "serde_default_u32::<300>")]
+    pub duration: u32,
+}
+
+/// An extra stroke drawn behind the border, e.g. a thin gradient outline around a solid border.
+#[derive(Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BorderLayerConfig {
+    /// Color of the layer while the window is active.
+    pub active_color: GlobalColor,
+    /// Color of the layer while the window is inactive. Defaults to `active_color`.
+    pub inactive_color: Option<GlobalColor>,
+    /// Width of the layer in pixels.
+    #[serde(default = "serde_default_u32::<1>")]
+    pub width: u32,
+    /// Distance in pixels between the window's edge and the inner edge of the layer. Negative
+    /// values move the layer over the window.
+    #[serde(default)]
+    pub offset: i32,
+    /// Line pattern of the layer. Default dash and gap lengths follow the layer's width.
+    #[serde(default)]
+    pub stroke_style: StrokeStyleConfig,
 }
 
 /// Stores the complete configuration including global rules, window rules, and keybindings.
@@ -315,6 +741,16 @@ pub struct UserConfig {
     /// Specific rules for individual windows.
     #[serde(default)]
     pub window_rules: Vec<WindowRuleConfig>,
+    /// Windows that never get a border, regardless of any window rule.
+    #[serde(default)]
+    pub exclude: Vec<MatchConditionConfig>,
+    /// Per-monitor overrides, keyed by device name (e.g., "DISPLAY2") or display number (e.g., "2").
+    #[serde(default)]
+    pub monitors: BTreeMap<String, MonitorOverrideConfig>,
+    /// Monitors that never get borders, by device name (e.g., "DISPLAY2") or display number
+    /// (e.g., "2").
+    #[serde(default)]
+    pub disabled_monitors: Vec<String>,
     /// Application keybindings.
     #[serde(default)]
     pub keybindings: Keybindings,
@@ -324,59 +760,148 @@ pub struct UserConfig {
     /// Enable custom predefined theme
     #[serde(deserialize_with = "deserialize_theme")]
     pub theme: ThemeManager,
+    /// Additional themes to switch between with the `next_theme` and `prev_theme` keybindings.
+    #[serde(default, deserialize_with = "deserialize_themes")]
+    pub themes: Vec<ThemeManager>,
+    /// Rendering backend settings shared by all borders.
+    #[serde(default)]
+    pub render: RenderConfig,
+    /// Suppresses borders while a configured application runs fullscreen.
+    #[serde(default)]
+    pub game_mode: GameModeConfig,
+    /// Recreates and repositions all borders when the system resumes or the display wakes up.
+    #[serde(default = "serde_default_bool::<true>")]
+    pub refresh_on_wake: bool,
+    /// Hides borders while their window covers its entire monitor, e.g. in borderless fullscreen.
+    #[serde(default = "serde_default_bool::<true>")]
+    pub hide_on_fullscreen: bool,
+    /// Draws borders on maximized windows, along the inside of the monitor's work area.
+    #[serde(default)]
+    pub border_maximized: bool,
+    /// Runs animations on borders of inactive windows. Disabling it stops their animation timers
+    /// once they finished fading out, which saves CPU and GPU time on busy desktops.
+    #[serde(default = "serde_default_bool::<true>")]
+    pub animate_inactive: bool,
+    /// Keeps borders above all other topmost windows instead of just above their window. The
+    /// border may then cover windows that overlap its window.
+    #[serde(default)]
+    pub always_on_top: bool,
+    /// Accepts commands over the `\\.\pipe\tacky-borders` named pipe. Requires a restart.
+    #[serde(default)]
+    pub ipc_enabled: bool,
+    /// Installs the low-level keyboard hook that handles `keybindings`. Requires a restart.
+    #[serde(default = "serde_default_bool::<true>")]
+    pub keybindings_enabled: bool,
+    /// Maximum number of borders that exist at once. Once reached, windows only get a border when
+    /// they gain focus, replacing the border of the least recently focused window. Must be at
+    /// least 1, so the focused window always keeps its border.
+    #[serde(default)]
+    pub max_borders: Option<NonZeroUsize>,
+    /// Most verbose level of messages written to the log (e.g., "warn", "info", "debug").
+    #[serde(default)]
+    pub log_level: LogLevel,
+    /// Lets borders catch left clicks and log the window they belong to, instead of passing
+    /// clicks through to the window below. Meant for debugging.
+    #[serde(default)]
+    pub debug_clickable: bool,
 }
 
 /// Methods for managing the configuration, including loading, saving, and reloading.
 impl UserConfig {
     /// Attempts to create a new configuration instance by reading from the config file.
-    pub fn create() -> anyhow::Result<Self> {
-        let config_file = Self::detect_config_file().unwrap_or_else(|_| {
-            debug!("Creating default config file");
-            Self::create_default_config().unwrap_or_default()
+    pub fn create() -> Result<Self, TackyError> {
+        Self::create_from(None)
+    }
+
+    /// Like `create`, but reads `config_file` instead of the detected config file if given.
+    fn create_from(config_file: Option<PathBuf>) -> Result<Self, TackyError> {
+        let config_file = config_file.unwrap_or_else(|| {
+            Self::detect_config_file().unwrap_or_else(|_| {
+                debug!("Creating default config file");
+                Self::create_default_config().unwrap_or_default()
+            })
         });
 
-        let config_format = UserConfig::detect_config_format()?;
+        let config_format = Self::config_format_of(&config_file)?;
         *CONFIG_FORMAT.write().unwrap() = config_format.clone();
 
         Self::read(&config_file)
     }
 
-    fn read(path: &PathBuf) -> anyhow::Result<Self> {
-        let content = read_to_string(path).inspect_err(|e| {
+    fn read(path: &PathBuf) -> Result<Self, TackyError> {
+        let content = read_to_string(path).map_err(|source| {
             WindowsApi::show_error_dialog(
                 "UserConfig",
                 &format!(
                     "Failed to read config file: {}\n(Error: {:?}",
                     path.display(),
-                    e
+                    source
                 ),
             );
+            TackyError::ConfigIo {
+                path: path.clone(),
+                source,
+            }
         })?;
 
-        let config_format = &*CONFIG_FORMAT
-            .read()
-            .map_err(|_| anyhow!("config format lock poisoned"))?;
+        let config_format = &*CONFIG_FORMAT.read().unwrap_or_else(|e| e.into_inner());
+
+        Self::deserialize(&content, config_format).map_err(|err| {
+            WindowsApi::show_error_dialog("UserConfig", &format!("{}", err));
+            err
+        })
+    }
 
-        let config: anyhow::Result<Self> = match config_format {
+    fn deserialize(content: &str, config_format: &ConfigFormat) -> Result<Self, TackyError> {
+        match config_format {
             #[cfg(feature = "json")]
             ConfigFormat::Json | ConfigFormat::Jsonc => {
-                serde_jsonc2::from_str(&content).with_context(|| "failed to deserialize JSON")
+                serde_jsonc2::from_str(content).map_err(|e| TackyError::ConfigParse {
+                    format: "JSON",
+                    source: Box::new(e),
+                })
             }
             #[cfg(feature = "yml")]
             ConfigFormat::Yaml => {
-                serde_yml::from_str(&content).with_context(|| "failed to deserialize YAML")
+                serde_yml::from_str(content).map_err(|e| TackyError::ConfigParse {
+                    format: "YAML",
+                    source: Box::new(e),
+                })
             }
-            _ => Err(anyhow!("Unsupported config format")),
-        };
+            _ => Err(TackyError::UnsupportedConfigFormat(format!(
+                "{config_format:?}"
+            ))),
+        }
+    }
 
-        config.map_err(|err| {
-            WindowsApi::show_error_dialog("UserConfig", &format!("{}", err));
-            err
-        })
+    /// Reads, deserializes and parses the config file without applying it or showing any dialogs,
+    /// so values that only fail to parse once applied, e.g. colors or match patterns, are caught
+    /// too.
+    ///
+    /// Returns the path of the config file on success. Errors include the path of the config file.
+    pub fn check() -> anyhow::Result<PathBuf> {
+        let config_file = Self::detect_config_file()?;
+        let config_format = Self::detect_config_format()?;
+
+        read_to_string(&config_file)
+            .map_err(|source| TackyError::ConfigIo {
+                path: config_file.clone(),
+                source,
+            })
+            .and_then(|content| Self::deserialize(&content, &config_format))
+            .and_then(|config| Ok(ParsedConfig::try_from(config)?))
+            .with_context(|| format!("invalid config file: {}", config_file.display()))?;
+
+        Ok(config_file)
     }
 
     /// Find the configuration file in config directory
     fn find_config_file(config_dir: &Path) -> Option<PathBuf> {
+        Self::find_config_files(config_dir).into_iter().next()
+    }
+
+    /// Finds every configuration file in the config directory, in order of precedence.
+    fn find_config_files(config_dir: &Path) -> Vec<PathBuf> {
         let candidates = [
             #[cfg(feature = "yml")]
             "yaml",
@@ -388,24 +913,43 @@ impl UserConfig {
             "jsonc",
         ];
 
-        for ext in candidates {
-            let file_path = config_dir.join("config").with_extension(ext);
-            if exists(file_path.clone()).unwrap_or(false) {
-                return Some(file_path);
+        candidates
+            .into_iter()
+            .map(|ext| config_dir.join("config").with_extension(ext))
+            .filter(|file_path| exists(file_path).unwrap_or(false))
+            .collect()
+    }
+
+    /// Lists the configuration files the tray menu can switch between.
+    pub fn available_config_files() -> Vec<PathBuf> {
+        match Self::get_config_dir() {
+            Ok(config_dir) => Self::find_config_files(&config_dir),
+            Err(err) => {
+                error!("could not get config dir: {err:#}");
+                Vec::new()
             }
         }
-        None
+    }
+
+    /// Returns the config file in use: the one picked from the tray menu, or the detected one.
+    pub fn active_config_file() -> Result<PathBuf, TackyError> {
+        match AppManager::get().active_config_path() {
+            Some(config_file) => Ok(config_file),
+            None => Self::detect_config_file(),
+        }
+    }
+
+    /// Reads the config from `config_file` from now on and reloads it.
+    pub fn switch_config_file(config_file: PathBuf) {
+        info!("switching config file to {}", config_file.display());
+        AppManager::get().set_active_config_path(config_file);
+        Self::reload();
     }
 
     /// Detects the configuration file in the given directory or creates a default config file if none exists.
-    pub fn detect_config_file() -> anyhow::Result<PathBuf> {
+    pub fn detect_config_file() -> Result<PathBuf, TackyError> {
         let config_dir = Self::get_config_dir()?;
-        Self::find_config_file(&config_dir).ok_or_else(|| {
-            anyhow!(
-                "[detect_config_file] Config: Not Found (Directory: {})",
-                config_dir.display()
-            )
-        })
+        Self::find_config_file(&config_dir).ok_or(TackyError::ConfigNotFound(config_dir))
     }
 
     /// Creates a default configuration file in the specified directory.
@@ -450,11 +994,12 @@ impl UserConfig {
         Ok(())
     }
 
-    pub fn detect_config_format() -> anyhow::Result<ConfigFormat> {
-        let config_dir = Self::get_config_dir()?;
-        let file_path = Self::find_config_file(&config_dir)
-            .ok_or_else(|| anyhow!("No supported config file found"))?;
+    pub fn detect_config_format() -> Result<ConfigFormat, TackyError> {
+        Self::config_format_of(&Self::detect_config_file()?)
+    }
 
+    /// Returns the format of a config file based on its extension.
+    fn config_format_of(file_path: &Path) -> Result<ConfigFormat, TackyError> {
         let extension = file_path
             .extension()
             .and_then(|e| e.to_str())
@@ -467,7 +1012,7 @@ impl UserConfig {
             "json" => Ok(ConfigFormat::Json),
             #[cfg(feature = "json")]
             "jsonc" => Ok(ConfigFormat::Jsonc),
-            _ => Err(anyhow!("Unsupported config file extension: {}", extension)),
+            _ => Err(TackyError::UnsupportedConfigFormat(extension.to_string())),
         }
     }
 
@@ -501,8 +1046,10 @@ impl UserConfig {
     /// If loading fails, it falls back to the default configuration and logs an error.
     pub fn update() {
         let app_manager = AppManager::get();
-        let new_config = match Self::create() {
+        let new_config = match Self::create_from(app_manager.active_config_path()) {
             Ok(config) => {
+                set_log_level(config.log_level.into());
+
                 let config_watcher_is_running = app_manager.config_watcher_is_running();
 
                 if config.monitor_config_changes && !config_watcher_is_running {
@@ -530,7 +1077,8 @@ impl UserConfig {
     /// # Side Effects
     /// - The configuration is reloaded from the file and written to the shared configuration store.
     /// - The borders are reloaded, which may involve reinitializing UI components.
-    /// - If a keyboard hook is available, the keybindings are refreshed and applied.
+    /// - If a keyboard hook is available and keybindings are enabled, the keybindings are
+    ///   refreshed and applied.
     pub fn reload() -> bool {
         let app_manager = AppManager::get();
         debug!("[reload] UserConfig: Reloading and restarting borders");
@@ -539,8 +1087,12 @@ impl UserConfig {
         let new_config = app_manager.config();
 
         if old_config != *new_config {
-            reload_borders();
-            if let Some(hook) = KEYBOARD_HOOK.get() {
+            // Borders only need to be recreated if more than their look changed
+            match old_config.differs_only_in_style(&new_config) {
+                true => restyle_borders(),
+                false => reload_borders(),
+            }
+            if let (true, Some(hook)) = (new_config.keybindings_enabled, KEYBOARD_HOOK.get()) {
                 let bindings = Vec::<KeybindingConfig>::from(&new_config.keybindings);
                 hook.update(&bindings);
             }
@@ -549,12 +1101,66 @@ impl UserConfig {
         false
     }
 
+    /// Returns whether `other` only differs from this config in options that existing borders can
+    /// apply in place: colors, themes, widths, offsets, corner and stroke styles, opacities,
+    /// monitor overrides and keybindings. The log level is applied on load and doesn't affect
+    /// borders at all.
+    pub fn differs_only_in_style(&self, other: &UserConfig) -> bool {
+        if self.window_rules.len() != other.window_rules.len() {
+            return false;
+        }
+
+        // Copy the style options over, so any remaining difference is a structural one
+        let mut restyled = other.clone();
+        restyled.keybindings = self.keybindings.clone();
+        restyled.theme = self.theme.clone();
+        restyled.themes = self.themes.clone();
+        restyled.monitors = self.monitors.clone();
+        restyled.log_level = self.log_level;
+
+        let (global, new_global) = (&self.global_rule, &mut restyled.global_rule);
+        new_global.border_width = global.border_width.clone();
+        new_global.border_offset = global.border_offset.clone();
+        new_global.border_style = global.border_style.clone();
+        new_global.stroke_style = global.stroke_style.clone();
+        new_global.active_color = global.active_color.clone();
+        new_global.inactive_color = global.inactive_color.clone();
+        new_global.active_opacity = global.active_opacity;
+        new_global.inactive_opacity = global.inactive_opacity;
+
+        for (rule, new_rule) in self.window_rules.iter().zip(&mut restyled.window_rules) {
+            let (rule, new_rule) = (&rule.match_window, &mut new_rule.match_window);
+            new_rule.border_width = rule.border_width.clone();
+            new_rule.border_offset = rule.border_offset.clone();
+            new_rule.border_style = rule.border_style.clone();
+            new_rule.stroke_style = rule.stroke_style.clone();
+            new_rule.active_color = rule.active_color.clone();
+            new_rule.inactive_color = rule.inactive_color.clone();
+            new_rule.active_opacity = rule.active_opacity;
+            new_rule.inactive_opacity = rule.inactive_opacity;
+        }
+
+        restyled == *self
+    }
+
+    /// Returns the themes `next_theme` and `prev_theme` cycle through: the configured `theme`,
+    /// followed by the additional `themes`.
+    pub fn theme_cycle(&self) -> Vec<ThemeManager> {
+        let mut themes = vec![self.theme.clone()];
+        for theme in &self.themes {
+            if !themes.contains(theme) {
+                themes.push(theme.clone());
+            }
+        }
+        themes
+    }
+
     /// Opens the configuration file in the default editor.
     ///
     /// This method determines the configuration file's path based on the current config type
     /// (e.g., JSON, YAML, JSONC) and attempts to open it using the default file association on the system.
     pub fn open() {
-        match Self::detect_config_file() {
+        match Self::active_config_file() {
             Ok(config_file) => win_open::that(config_file).log_if_err(),
             Err(err) => error!("{err}"),
         }
@@ -563,22 +1169,81 @@ impl UserConfig {
 
 // Deserializer
 
-/// Deserializes a dimension value.
-pub fn deserialize_dimension<'de, D>(deserializer: D) -> Result<i32, D::Error>
+/// Deserializes a dimension value, which is either a length or a percentage.
+pub fn deserialize_dimension<'de, D>(deserializer: D) -> Result<Value, D::Error>
 where
     D: Deserializer<'de>,
 {
     let value = Value::deserialize(deserializer)?;
-    value
-        .as_length_i32()
-        .ok_or_else(|| de::Error::custom("Invalid Value"))
+    match is_dimension(&value) {
+        true => Ok(value),
+        false => Err(de::Error::custom("Invalid Value")),
+    }
 }
 
-/// Deserializes an optional dimension value.
-pub fn deserialize_optional_dimension<'de, D>(deserializer: D) -> Result<Option<i32>, D::Error>
+/// Deserializes an optional dimension value, which is either a length or a percentage.
+pub fn deserialize_optional_dimension<'de, D>(deserializer: D) -> Result<Option<Value>, D::Error>
 where
     D: Deserializer<'de>,
 {
     let value = Option::<Value>::deserialize(deserializer)?;
-    Ok(value.and_then(|v| v.as_length_i32()))
+    Ok(value.filter(is_dimension))
+}
+
+/// Deserializes a duration, which is either a number of milliseconds or a string with a unit
+/// (e.g., `"250ms"`, `"0.25s"`).
+pub fn deserialize_duration<'de, D>(deserializer: D) -> Result<Value, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Value::deserialize(deserializer)?;
+    match is_duration(&value) {
+        true => Ok(value),
+        false => Err(de::Error::custom("Invalid duration")),
+    }
+}
+
+/// Deserializes an optional duration, which is either a number of milliseconds or a string with a
+/// unit.
+pub fn deserialize_optional_duration<'de, D>(deserializer: D) -> Result<Option<Value>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<Value>::deserialize(deserializer)? {
+        Some(value) if !is_duration(&value) => Err(de::Error::custom("Invalid duration")),
+        value => Ok(value),
+    }
+}
+
+/// Deserializes optional effect settings, which may also be given as a boolean: `false` disables
+/// effects, while `true` is the same as leaving them out.
+pub fn deserialize_optional_effects<'de, D>(
+    deserializer: D,
+) -> Result<Option<EffectsConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum EffectsOrBool {
+        Bool(bool),
+        Effects(EffectsConfig),
+    }
+
+    Ok(match Option::<EffectsOrBool>::deserialize(deserializer)? {
+        Some(EffectsOrBool::Bool(false)) => Some(EffectsConfig {
+            enabled: false,
+            ..Default::default()
+        }),
+        Some(EffectsOrBool::Bool(true)) | None => None,
+        Some(EffectsOrBool::Effects(effects)) => Some(effects),
+    })
+}
+
+fn is_dimension(value: &Value) -> bool {
+    value.as_length_i32().is_some() || value.as_percentage_f32().is_some()
+}
+
+fn is_duration(value: &Value) -> bool {
+    value.as_duration_u32().is_some()
 }
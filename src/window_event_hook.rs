@@ -4,6 +4,8 @@ use crate::border_manager::window_border;
 use crate::border_manager::window_borders;
 use crate::border_manager::Border;
 use crate::error::LogIfErr;
+use crate::game_mode::is_game_window;
+use crate::game_mode::update_game_mode;
 use crate::windows_api::PointerConversion;
 use crate::windows_api::WindowsApi;
 use crate::windows_api::WM_APP_FOREGROUND;
@@ -128,6 +130,11 @@ impl WindowEventHook {
                     return;
                 }
 
+                // Stop game mode once the game leaves fullscreen, even if it keeps the focus
+                if is_game_window(handle.0.as_int()) {
+                    update_game_mode(handle.0.as_int());
+                }
+
                 if let Some(border) = window_border(handle.0.as_int()) {
                     WindowsApi::send_notify_message_w(
                         border.border_window.as_hwnd(),
@@ -204,6 +211,11 @@ impl WindowEventHook {
                     if let Some(border) = window_border(handle.0.as_int()) {
                         border.destroy();
                     }
+
+                    // Window handles may be reused, so forget the override and cached rule of
+                    // destroyed windows
+                    AppManager::get().set_window_override(handle.0.as_int(), None);
+                    AppManager::get().remove_cached_window_rule(handle.0.as_int());
                 }
             }
             _ => {}
@@ -266,6 +278,19 @@ fn handle_foreground_event(potential_active_hwnd: isize, event_hwnd: isize) {
     };
 
     set_active_window(new_active_window);
+    update_game_mode(new_active_window);
+
+    // With `max_borders` or `disabled_monitors`, windows may not have a border until they gain
+    // focus (e.g. after being moved off a disabled monitor)
+    let borders_deferred = {
+        let config = AppManager::get().config();
+        config.max_borders.is_some() || !config.disabled_monitors.is_empty()
+    };
+    match window_border(new_active_window) {
+        Some(_) => AppManager::get().mark_focused(new_active_window),
+        None if borders_deferred => Border::show(new_active_window),
+        None => {}
+    }
 
     let visible_windows: Vec<HWND> = window_borders()
         .iter()
@@ -3,12 +3,13 @@
 
 extern crate windows;
 use crate::app_manager::AppManager;
+use crate::app_manager::RuleCacheKey;
 use crate::border_manager::Border;
 use crate::core::rect::Rect;
 use crate::error::LogIfErr;
 use crate::parsed_config::ParsedConfig;
+use crate::parsed_config::WindowProperties;
 use crate::parsed_config::WindowRule;
-use crate::user_config::MatchKind;
 use crate::windows_callback::enum_windows;
 use anyhow::Context;
 use anyhow::anyhow;
@@ -35,9 +36,11 @@ use windows::Win32::Foundation::HMODULE;
 use windows::Win32::Foundation::HWND;
 use windows::Win32::Foundation::LPARAM;
 use windows::Win32::Foundation::LRESULT;
+use windows::Win32::Foundation::POINT;
 use windows::Win32::Foundation::RECT;
 use windows::Win32::Foundation::SetLastError;
 use windows::Win32::Foundation::WPARAM;
+use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
 use windows::Win32::Graphics::Dwm::DWM_CLOAKED_APP;
 use windows::Win32::Graphics::Dwm::DWM_CLOAKED_INHERITED;
 use windows::Win32::Graphics::Dwm::DWM_CLOAKED_SHELL;
@@ -46,14 +49,21 @@ use windows::Win32::Graphics::Dwm::DWMWA_CLOAKED;
 use windows::Win32::Graphics::Dwm::DWMWA_EXTENDED_FRAME_BOUNDS;
 use windows::Win32::Graphics::Dwm::DWMWA_WINDOW_CORNER_PREFERENCE;
 use windows::Win32::Graphics::Dwm::DWMWINDOWATTRIBUTE;
+use windows::Win32::Graphics::Dwm::DwmGetColorizationColor;
 use windows::Win32::Graphics::Dwm::DwmGetWindowAttribute;
+use windows::Win32::Graphics::Gdi::DEVMODEW;
+use windows::Win32::Graphics::Gdi::ENUM_CURRENT_SETTINGS;
+use windows::Win32::Graphics::Gdi::EnumDisplaySettingsW;
 use windows::Win32::Graphics::Gdi::GetMonitorInfoW;
 use windows::Win32::Graphics::Gdi::HMONITOR;
 use windows::Win32::Graphics::Gdi::MONITOR_DEFAULTTONEAREST;
 use windows::Win32::Graphics::Gdi::MONITORINFO;
+use windows::Win32::Graphics::Gdi::MONITORINFOEXW;
 use windows::Win32::Graphics::Gdi::MonitorFromWindow;
 use windows::Win32::Graphics::Gdi::ValidateRect;
 use windows::Win32::System::Com::CoTaskMemFree;
+use windows::Win32::System::Console::ATTACH_PARENT_PROCESS;
+use windows::Win32::System::Console::AttachConsole;
 use windows::Win32::System::LibraryLoader::GetModuleHandleW;
 use windows::Win32::System::Threading::GetThreadId;
 use windows::Win32::System::Threading::OpenProcess;
@@ -72,11 +82,15 @@ use windows::Win32::UI::Shell::SHGetKnownFolderPath;
 use windows::Win32::UI::WindowsAndMessaging::CW_USEDEFAULT;
 use windows::Win32::UI::WindowsAndMessaging::CreateWindowExW;
 use windows::Win32::UI::WindowsAndMessaging::DefWindowProcW;
+use windows::Win32::UI::WindowsAndMessaging::DestroyWindow;
 use windows::Win32::UI::WindowsAndMessaging::DispatchMessageW;
 use windows::Win32::UI::WindowsAndMessaging::EnumWindows;
+use windows::Win32::UI::WindowsAndMessaging::GA_ROOT;
 use windows::Win32::UI::WindowsAndMessaging::GW_HWNDPREV;
 use windows::Win32::UI::WindowsAndMessaging::GWL_EXSTYLE;
 use windows::Win32::UI::WindowsAndMessaging::GWL_STYLE;
+use windows::Win32::UI::WindowsAndMessaging::GetAncestor;
+use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
 use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
 use windows::Win32::UI::WindowsAndMessaging::GetMessageW;
 use windows::Win32::UI::WindowsAndMessaging::GetWindow;
@@ -87,7 +101,9 @@ use windows::Win32::UI::WindowsAndMessaging::GetWindowTextW;
 use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
 use windows::Win32::UI::WindowsAndMessaging::HMENU;
 use windows::Win32::UI::WindowsAndMessaging::HWND_TOP;
+use windows::Win32::UI::WindowsAndMessaging::HWND_TOPMOST;
 use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
+use windows::Win32::UI::WindowsAndMessaging::KillTimer;
 use windows::Win32::UI::WindowsAndMessaging::LAYERED_WINDOW_ATTRIBUTES_FLAGS;
 use windows::Win32::UI::WindowsAndMessaging::MB_ICONERROR;
 use windows::Win32::UI::WindowsAndMessaging::MB_OK;
@@ -99,6 +115,7 @@ use windows::Win32::UI::WindowsAndMessaging::PostMessageW;
 use windows::Win32::UI::WindowsAndMessaging::PostQuitMessage;
 use windows::Win32::UI::WindowsAndMessaging::PostThreadMessageW;
 use windows::Win32::UI::WindowsAndMessaging::RealGetWindowClassW;
+use windows::Win32::UI::WindowsAndMessaging::RegisterClassW;
 use windows::Win32::UI::WindowsAndMessaging::SET_WINDOW_POS_FLAGS;
 use windows::Win32::UI::WindowsAndMessaging::SWP_NOACTIVATE;
 use windows::Win32::UI::WindowsAndMessaging::SWP_NOREDRAW;
@@ -106,16 +123,20 @@ use windows::Win32::UI::WindowsAndMessaging::SWP_NOSENDCHANGING;
 use windows::Win32::UI::WindowsAndMessaging::SWP_NOZORDER;
 use windows::Win32::UI::WindowsAndMessaging::SendNotifyMessageW;
 use windows::Win32::UI::WindowsAndMessaging::SetLayeredWindowAttributes;
+use windows::Win32::UI::WindowsAndMessaging::SetTimer;
 use windows::Win32::UI::WindowsAndMessaging::SetWindowLongPtrW;
 use windows::Win32::UI::WindowsAndMessaging::SetWindowPos;
 use windows::Win32::UI::WindowsAndMessaging::TranslateMessage;
+use windows::Win32::UI::WindowsAndMessaging::UnregisterClassW;
 use windows::Win32::UI::WindowsAndMessaging::WINDOW_EX_STYLE;
 use windows::Win32::UI::WindowsAndMessaging::WINDOW_LONG_PTR_INDEX;
 use windows::Win32::UI::WindowsAndMessaging::WINDOW_STYLE;
 use windows::Win32::UI::WindowsAndMessaging::WM_APP;
 use windows::Win32::UI::WindowsAndMessaging::WM_NCDESTROY;
 use windows::Win32::UI::WindowsAndMessaging::WM_QUIT;
+use windows::Win32::UI::WindowsAndMessaging::WNDCLASSW;
 use windows::Win32::UI::WindowsAndMessaging::WNDENUMPROC;
+use windows::Win32::UI::WindowsAndMessaging::WNDPROC;
 use windows::Win32::UI::WindowsAndMessaging::WS_CHILD;
 use windows::Win32::UI::WindowsAndMessaging::WS_DISABLED;
 use windows::Win32::UI::WindowsAndMessaging::WS_EX_LAYERED;
@@ -128,6 +149,8 @@ use windows::Win32::UI::WindowsAndMessaging::WS_MAXIMIZE;
 use windows::Win32::UI::WindowsAndMessaging::WS_MINIMIZE;
 use windows::Win32::UI::WindowsAndMessaging::WS_POPUP;
 use windows::Win32::UI::WindowsAndMessaging::WS_SYSMENU;
+use windows::Win32::UI::WindowsAndMessaging::WindowFromPoint;
+use windows::core::HSTRING;
 use windows::core::PCWSTR;
 use windows::core::PWSTR;
 use windows::core::Param;
@@ -141,6 +164,13 @@ pub const WM_APP_HIDECLOAKED: u32 = WM_APP + 4;
 pub const WM_APP_MINIMIZESTART: u32 = WM_APP + 5;
 pub const WM_APP_MINIMIZEEND: u32 = WM_APP + 6;
 pub const WM_APP_TIMER: u32 = WM_APP + 7;
+pub const WM_APP_RECREATE: u32 = WM_APP + 8;
+pub const WM_APP_FORCE_STATE: u32 = WM_APP + 9;
+pub const WM_APP_IPC_EVENT: u32 = WM_APP + 10;
+pub const WM_APP_ANIMATIONS: u32 = WM_APP + 11;
+pub const WM_APP_RESTYLE: u32 = WM_APP + 12;
+pub const WM_APP_FLASH: u32 = WM_APP + 13;
+pub const WM_APP_STATUS: u32 = WM_APP + 14;
 
 pub trait PointerConversion {
     fn as_int(&self) -> isize;
@@ -216,6 +246,12 @@ impl WindowsApi {
         unsafe { ImmDisableIME(0xFFFFFFFF) }
     }
 
+    /// Attaches to the console of the parent process, so release builds (which use the windows
+    /// subsystem) can print to the terminal they were started from.
+    pub fn attach_parent_console() -> windows::core::Result<()> {
+        unsafe { AttachConsole(ATTACH_PARENT_PROCESS) }
+    }
+
     pub fn set_process_dpi_awareness_context() -> windows::core::Result<()> {
         unsafe { SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2) }
     }
@@ -246,6 +282,58 @@ impl WindowsApi {
         Ok(mi)
     }
 
+    /// Returns the device name of a monitor (e.g., `\\.\DISPLAY1`).
+    pub fn get_monitor_device_name(hmonitor: HMONITOR) -> anyhow::Result<String> {
+        let mut mi = MONITORINFOEXW::default();
+        mi.monitorInfo.cbSize = size_of::<MONITORINFOEXW>() as u32;
+
+        if !unsafe { GetMonitorInfoW(hmonitor, &mut mi.monitorInfo) }.as_bool() {
+            return Err(anyhow!(
+                "could not get monitor device name for {:?}: {:?}",
+                hmonitor,
+                unsafe { GetLastError() }
+            ));
+        };
+
+        let len = mi
+            .szDevice
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(mi.szDevice.len());
+        Ok(String::from_utf16_lossy(&mi.szDevice[..len]))
+    }
+
+    /// Returns the current refresh rate of a monitor in hertz.
+    pub fn get_monitor_refresh_rate(hmonitor: HMONITOR) -> anyhow::Result<u32> {
+        let device_name = HSTRING::from(Self::get_monitor_device_name(hmonitor)?);
+        let mut dev_mode = DEVMODEW {
+            dmSize: size_of::<DEVMODEW>() as u16,
+            ..Default::default()
+        };
+
+        if !unsafe {
+            EnumDisplaySettingsW(
+                PCWSTR(device_name.as_ptr()),
+                ENUM_CURRENT_SETTINGS,
+                &mut dev_mode,
+            )
+        }
+        .as_bool()
+        {
+            return Err(anyhow!(
+                "could not get display settings for {:?}: {:?}",
+                hmonitor,
+                unsafe { GetLastError() }
+            ));
+        }
+
+        // 0 and 1 both stand for the hardware's default refresh rate, which isn't reported
+        match dev_mode.dmDisplayFrequency {
+            0 | 1 => Err(anyhow!("unknown refresh rate for {hmonitor:?}")),
+            refresh_rate => Ok(refresh_rate),
+        }
+    }
+
     pub fn post_message_w(
         hwnd: Option<HWND>,
         msg: u32,
@@ -255,6 +343,20 @@ impl WindowsApi {
         unsafe { PostMessageW(hwnd, msg, wparam, lparam) }
     }
 
+    /// Starts (or restarts) a native timer that posts `WM_TIMER` with the given id to `hwnd`.
+    pub fn set_timer(hwnd: isize, id: usize, elapse_ms: u32) -> anyhow::Result<()> {
+        match unsafe { SetTimer(Some(hwnd.as_hwnd()), id, elapse_ms, None) } {
+            0 => Err(anyhow!("could not set timer: {:?}", unsafe {
+                GetLastError()
+            })),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn kill_timer(hwnd: isize, id: usize) -> windows::core::Result<()> {
+        unsafe { KillTimer(Some(hwnd.as_hwnd()), id) }
+    }
+
     pub fn send_notify_message_w(
         hwnd: HWND,
         msg: u32,
@@ -322,6 +424,52 @@ impl WindowsApi {
         }
     }
 
+    /// Registers a window class and creates a hidden window of it on the current thread, which
+    /// must run a message loop.
+    ///
+    /// We use a hidden top-level window instead of a message-only window because the latter does
+    /// not receive broadcast messages like `PBT_APMRESUMEAUTOMATIC`.
+    pub fn create_hidden_window(class_name: PCWSTR, wnd_proc: WNDPROC) -> anyhow::Result<HWND> {
+        let hinstance = Self::module_handle_w()?;
+
+        let wc = WNDCLASSW {
+            lpfnWndProc: wnd_proc,
+            hInstance: hinstance.into(),
+            lpszClassName: class_name,
+            ..Default::default()
+        };
+
+        if unsafe { RegisterClassW(&wc) } == 0 {
+            return Err(anyhow!("could not register window class: {:?}", unsafe {
+                GetLastError()
+            }));
+        }
+
+        Self::create_window_ex_w(
+            WS_EX_TOOLWINDOW,
+            class_name,
+            class_name,
+            WS_POPUP,
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            Some(hinstance.into()),
+            None,
+        )
+        .context("could not create hidden window")
+    }
+
+    /// Destroys a window created by `create_hidden_window` and unregisters its class. Must be
+    /// called from the thread that created the window.
+    pub fn destroy_hidden_window(hwnd: HWND, class_name: PCWSTR) -> anyhow::Result<()> {
+        unsafe { DestroyWindow(hwnd) }.context("could not destroy hidden window")?;
+        unsafe { UnregisterClassW(class_name, Some(Self::module_handle_w()?.into())) }
+            .context("could not unregister hidden window class")
+    }
+
     pub fn set_layered_window_attributes(
         hwnd: isize,
         crkey: COLORREF,
@@ -406,7 +554,6 @@ impl WindowsApi {
         Self::get_foreground_window() == hwnd
     }
 
-    #[allow(dead_code)]
     pub fn is_window_minimized(hwnd: isize) -> bool {
         let style = Self::get_window_style(hwnd);
 
@@ -417,6 +564,33 @@ impl WindowsApi {
         Self::is_window_visible(hwnd) && !Self::is_window_cloaked(hwnd)
     }
 
+    pub fn is_cursor_over_window(hwnd: isize) -> bool {
+        let mut point = POINT::default();
+        if unsafe { GetCursorPos(&mut point) }.is_err() {
+            return false;
+        }
+
+        // Our border windows are transparent to hit-testing, so this resolves to whatever window
+        // is actually under the cursor. Compare its root so child controls count as well.
+        let hwnd_under_cursor = unsafe { WindowFromPoint(point) };
+        unsafe { GetAncestor(hwnd_under_cursor, GA_ROOT) }.as_int() == hwnd
+    }
+
+    pub fn is_window_fullscreen(hwnd: isize) -> bool {
+        let Ok(window_rect) = Self::window_rect(hwnd) else {
+            return false;
+        };
+        let Ok(monitor_info) = Self::get_monitor_info(Self::monitor_from_window(hwnd)) else {
+            return false;
+        };
+        let monitor_rect = monitor_info.rcMonitor;
+
+        window_rect.left <= monitor_rect.left
+            && window_rect.top <= monitor_rect.top
+            && window_rect.right >= monitor_rect.right
+            && window_rect.bottom >= monitor_rect.bottom
+    }
+
     pub fn is_window_top_level(hwnd: isize) -> bool {
         let style = Self::get_window_style(hwnd);
 
@@ -430,10 +604,15 @@ impl WindowsApi {
     }
 
     pub fn has_native_border(hwnd: isize) -> bool {
-        let style = Self::get_window_style(hwnd);
         let ex_style = Self::get_window_ex_style(hwnd);
 
-        ex_style.contains(WS_EX_WINDOWEDGE) && !style.contains(WS_MAXIMIZE)
+        // Maximized windows don't show their frame, unless the user wants borders on them anyway
+        ex_style.contains(WS_EX_WINDOWEDGE)
+            && (!Self::is_window_maximized(hwnd) || AppManager::get().config().border_maximized)
+    }
+
+    pub fn is_window_maximized(hwnd: isize) -> bool {
+        Self::get_window_style(hwnd).contains(WS_MAXIMIZE)
     }
 
     pub fn get_window_text_w(hwnd: isize, lpstring: &mut [u16]) -> i32 {
@@ -486,6 +665,11 @@ impl WindowsApi {
     }
 
     pub fn get_process_name(hwnd: isize) -> anyhow::Result<String> {
+        Self::get_process_path(hwnd).map(|exe_path| Self::process_name_from_path(&exe_path))
+    }
+
+    /// Returns the full path of the executable that owns the window.
+    pub fn get_process_path(hwnd: isize) -> anyhow::Result<String> {
         let mut process_id = 0u32;
         unsafe {
             GetWindowThreadProcessId(hwnd.as_hwnd(), Some(&mut process_id));
@@ -502,7 +686,7 @@ impl WindowsApi {
             }
         };
 
-        let mut buffer = [0u16; 256];
+        let mut buffer = [0u16; 1024];
         let mut length = buffer.len() as u32;
 
         let result = unsafe {
@@ -524,17 +708,19 @@ impl WindowsApi {
             CloseHandle(process_handle).ok(); // Ensure the handle is closed, ignoring the result
         }
 
-        let exe_path = String::from_utf16_lossy(&buffer[..length as usize]);
+        Ok(String::from_utf16_lossy(&buffer[..length as usize]))
+    }
 
-        let process_name = exe_path
+    /// Strips the directory and extension off an executable path, e.g. `C:\Apps\app.exe` becomes
+    /// `app`.
+    fn process_name_from_path(exe_path: &str) -> String {
+        exe_path
             .split('\\')
             .next_back()
             .and_then(|file_name| file_name.split('.').next()) // Extract the file name without extension
             .unwrap_or("") // Fallback to empty string if parsing fails
             .trim_end_matches('\0')
-            .to_string();
-
-        Ok(process_name)
+            .to_string()
     }
 
     pub fn get_window_rule(hwnd: isize) -> WindowRule {
@@ -546,6 +732,30 @@ impl WindowsApi {
             }
         };
 
+        let key = RuleCacheKey {
+            title,
+            style: Self::get_window_style(hwnd),
+            ex_style: Self::get_window_ex_style(hwnd),
+        };
+        let mut rule = match AppManager::get().cached_window_rule(hwnd, &key) {
+            Some(rule) => rule,
+            None => {
+                let rule = Self::resolve_window_rule(hwnd, key.clone());
+                AppManager::get().cache_window_rule(hwnd, key, rule.clone());
+                rule
+            }
+        };
+
+        // Borders toggled at runtime stay that way until the window is destroyed
+        if let Some(enabled) = AppManager::get().window_override(hwnd) {
+            rule.enabled = Some(enabled);
+        }
+
+        rule
+    }
+
+    /// Finds the window rule matching the given window, ignoring the rule cache.
+    fn resolve_window_rule(hwnd: isize, key: RuleCacheKey) -> WindowRule {
         let class = match Self::get_window_class(hwnd) {
             Ok(val) => val,
             Err(err) => {
@@ -554,13 +764,14 @@ impl WindowsApi {
             }
         };
 
-        let process = match Self::get_process_name(hwnd) {
+        let path = match Self::get_process_path(hwnd) {
             Ok(val) => val,
             Err(err) => {
-                error!("could not retrieve process name for {hwnd:?}: {err}");
+                error!("could not retrieve process path for {hwnd:?}: {err}");
                 "".to_string()
             }
         };
+        let process = Self::process_name_from_path(&path);
 
         let config = AppManager::get().config().clone();
         let parsed_config = ParsedConfig::try_from(config).unwrap_or_else(|err| {
@@ -568,23 +779,34 @@ impl WindowsApi {
             ParsedConfig::default()
         });
 
-        let rule = parsed_config.window_rules.iter().find(|rule| {
-            let window_name = match rule.match_kind {
-                Some(MatchKind::Title) => &title,
-                Some(MatchKind::Process) => &process,
-                Some(MatchKind::Class) => &class,
-                None => {
-                    error!("expected 'kind' for window rule but none found!");
-                    return false;
-                }
-            };
-
-            rule.match_strategy
-                .as_ref()
-                .is_some_and(|s| s.is_match(window_name))
-        });
+        let window = WindowProperties {
+            title: key.title,
+            class,
+            process,
+            path,
+            style: key.style,
+            ex_style: key.ex_style,
+        };
 
-        rule.cloned().unwrap_or_default()
+        // Excluded windows never get a border, so they skip the window rules entirely
+        if parsed_config.exclude.iter().any(|c| c.is_match(&window)) {
+            debug!("window is excluded from borders: {hwnd:?}");
+            WindowRule {
+                enabled: Some(false),
+                ..Default::default()
+            }
+        } else {
+            // Iterate in reverse so max_by_key, which returns the last of equal elements, breaks
+            // ties in favor of the rule listed first
+            parsed_config
+                .window_rules
+                .iter()
+                .rev()
+                .filter(|rule| rule.is_match(&window))
+                .max_by_key(|rule| rule.priority)
+                .cloned()
+                .unwrap_or_default()
+        }
     }
 
     pub fn collect_window_handles() -> anyhow::Result<Vec<isize>> {
@@ -625,17 +847,43 @@ impl WindowsApi {
         corner_preference
     }
 
+    /// Returns the DWM colorization (accent) color.
+    pub fn get_accent_color() -> anyhow::Result<D2D1_COLOR_F> {
+        let mut colorization = 0u32;
+        let mut opaque_blend = BOOL::default();
+
+        unsafe { DwmGetColorizationColor(&mut colorization, &mut opaque_blend) }
+            .context("could not retrieve the accent color")?;
+
+        // The color is packed as 0xAARRGGBB
+        let channel = |shift: u32| ((colorization >> shift) & 0xff) as f32 / 255.0;
+
+        Ok(D2D1_COLOR_F {
+            r: channel(16),
+            g: channel(8),
+            b: channel(0),
+            a: 1.0,
+        })
+    }
+
+    /// Moves the border and places it right above `position` in the z-order, or above all other
+    /// topmost windows if `always_on_top` is set.
     pub fn set_border_pos(
         hwnd: isize,
         layout: &Rect,
         position: isize,
+        always_on_top: bool,
         other_flags: Option<SET_WINDOW_POS_FLAGS>,
     ) -> windows::core::Result<()> {
-        let hwnd_above_tracking = unsafe { GetWindow(position.as_hwnd(), GW_HWNDPREV) };
-
         let mut flags =
             SWP_NOSENDCHANGING | SWP_NOACTIVATE | SWP_NOREDRAW | other_flags.unwrap_or_default();
 
+        if always_on_top {
+            return Self::set_window_pos(hwnd.as_hwnd(), layout, Some(HWND_TOPMOST), flags);
+        }
+
+        let hwnd_above_tracking = unsafe { GetWindow(position.as_hwnd(), GW_HWNDPREV) };
+
         if hwnd_above_tracking == Ok(hwnd.as_hwnd()) {
             flags |= SWP_NOZORDER;
         }
@@ -667,12 +915,26 @@ impl WindowsApi {
         }
     }
 
-    pub fn create_border_window(name: PCWSTR, border: &mut Border) -> windows::core::Result<isize> {
+    /// Creates the window a border is drawn into. Unless `clickable` is set, the window is
+    /// transparent to mouse input, so clicks reach the window below it.
+    pub fn create_border_window(
+        name: PCWSTR,
+        border: &mut Border,
+        clickable: bool,
+    ) -> windows::core::Result<isize> {
+        let mut ex_style = WS_EX_LAYERED | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE;
+        let mut style = WS_POPUP | WS_SYSMENU;
+        // Disabled windows get no mouse input, so a clickable border must stay enabled
+        if !clickable {
+            ex_style |= WS_EX_TRANSPARENT;
+            style |= WS_DISABLED;
+        }
+
         match Self::create_window_ex_w(
-            WS_EX_LAYERED | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_TRANSPARENT | WS_EX_NOACTIVATE,
+            ex_style,
             w!("border"),
             name,
-            WS_POPUP | WS_DISABLED | WS_SYSMENU,
+            style,
             CW_USEDEFAULT,
             CW_USEDEFAULT,
             CW_USEDEFAULT,